@@ -1,3 +1,6 @@
+use core::convert::Infallible;
+use core::time::Duration;
+
 use embedded_hal::delay::DelayNs;
 use riscv::register::{cycle, mcycle};
 
@@ -17,17 +20,62 @@ impl McycleDelay {
     pub const fn new(ticks_second: u64) -> Self {
         Self { ticks_second }
     }
+
+    /// Gets the current value of the machine mode cycle counter (`mcycle`).
+    ///
+    /// Use as the starting point for [McycleDelay::elapsed_ns].
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// use jh71xx_hal::delay;
+    ///
+    /// let clock = delay::u74_mdelay();
+    /// let start = clock.now();
+    /// // ... do some work ...
+    /// let _ns = clock.elapsed_ns(start);
+    /// ```
+    pub fn now(&self) -> u64 {
+        mcycle::read64()
+    }
+
+    /// Gets the time elapsed (in nanoseconds) since the `since` cycle count from
+    /// [McycleDelay::now].
+    ///
+    /// The cycle difference is computed with wrap-around, and the conversion saturates at
+    /// [u64::MAX].
+    pub fn elapsed_ns(&self, since: u64) -> u64 {
+        cycles_to_ns(mcycle::read64().wrapping_sub(since), self.ticks_second)
+    }
+
+    /// Pauses execution for at least `ms` milliseconds.
+    ///
+    /// The cycle count is computed in 64-bit, and saturates at [u64::MAX] cycles (over 150
+    /// years at the `U74` clock rate).
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// use jh71xx_hal::delay;
+    ///
+    /// let mut delay = delay::u74_mdelay();
+    /// // 10 minutes
+    /// delay.delay_ms_u64(600_000);
+    /// ```
+    pub fn delay_ms_u64(&mut self, ms: u64) {
+        spin_cycles(mcycle::read64, ms_to_cycles(ms, self.ticks_second));
+    }
+
+    /// Pauses execution for at least `s` seconds.
+    pub fn delay_s(&mut self, s: u32) {
+        self.delay_ms_u64(u64::from(s).saturating_mul(1_000));
+    }
 }
 
 impl DelayNs for McycleDelay {
     #[inline]
     fn delay_ns(&mut self, ns: u32) {
-        let t0 = mcycle::read64();
-        let ns_64 = u64::from(ns);
-        let clock = ns_64
-            .saturating_mul(self.ticks_second)
-            .saturating_div(1_000_000_000u64);
-        while mcycle::read64().wrapping_sub(t0) <= clock {}
+        spin_cycles(mcycle::read64, ns_to_cycles(ns.into(), self.ticks_second));
     }
 }
 
@@ -44,17 +92,107 @@ impl UcycleDelay {
     pub const fn new(ticks_second: u64) -> Self {
         Self { ticks_second }
     }
+
+    /// Pauses execution for at least `ms` milliseconds.
+    ///
+    /// The cycle count is computed in 64-bit, and saturates at [u64::MAX] cycles (over 150
+    /// years at the `U74` clock rate).
+    pub fn delay_ms_u64(&mut self, ms: u64) {
+        spin_cycles(cycle::read64, ms_to_cycles(ms, self.ticks_second));
+    }
+
+    /// Pauses execution for at least `s` seconds.
+    pub fn delay_s(&mut self, s: u32) {
+        self.delay_ms_u64(u64::from(s).saturating_mul(1_000));
+    }
 }
 
 impl DelayNs for UcycleDelay {
     #[inline]
     fn delay_ns(&mut self, ns: u32) {
-        let t0 = cycle::read64();
-        let ns_64 = u64::from(ns);
-        let clock = ns_64
-            .saturating_mul(self.ticks_second)
-            .saturating_div(1_000_000_000u64);
-        while cycle::read64().wrapping_sub(t0) <= clock {}
+        spin_cycles(cycle::read64, ns_to_cycles(ns.into(), self.ticks_second));
+    }
+}
+
+/// Cycle counter delay provider for either privilege mode.
+///
+/// Wraps a [McycleDelay], or a [UcycleDelay], so portable code can depend on a single delay
+/// type, and leave the choice of counter to the application.
+///
+/// Example:
+///
+/// ```no_run
+/// use embedded_hal::delay::DelayNs;
+/// use jh71xx_hal::delay;
+///
+/// let mut delay = delay::Delay::auto();
+/// delay.delay_ms(10);
+///
+/// // Explicitly use the user mode cycle counter
+/// let mut delay = delay::Delay::from(delay::u74_udelay());
+/// delay.delay_us(100);
+/// ```
+#[derive(Clone, Copy)]
+pub enum Delay {
+    /// Machine mode cycle counter (`mcycle`).
+    Machine(McycleDelay),
+    /// User mode cycle counter (`cycle`).
+    User(UcycleDelay),
+}
+
+impl Delay {
+    /// Creates a new [Delay] for the `U74` riscv core.
+    ///
+    /// Selects the machine mode cycle counter (`mcycle`) with the `rt` feature (without `rts`),
+    /// since the HAL then runs in machine mode, and the user mode cycle counter (`cycle`)
+    /// otherwise. Supervisor mode builds (`rts`) cannot access `mcycle`.
+    pub const fn auto() -> Self {
+        if cfg!(all(feature = "rt", not(feature = "rts"))) {
+            Self::Machine(McycleDelay::new(U74_CLOCK_HZ))
+        } else {
+            Self::User(UcycleDelay::new(U74_CLOCK_HZ))
+        }
+    }
+
+    /// Pauses execution for at least `ms` milliseconds.
+    pub fn delay_ms_u64(&mut self, ms: u64) {
+        match self {
+            Self::Machine(delay) => delay.delay_ms_u64(ms),
+            Self::User(delay) => delay.delay_ms_u64(ms),
+        }
+    }
+
+    /// Pauses execution for at least `s` seconds.
+    pub fn delay_s(&mut self, s: u32) {
+        self.delay_ms_u64(u64::from(s).saturating_mul(1_000));
+    }
+}
+
+impl Default for Delay {
+    fn default() -> Self {
+        Self::auto()
+    }
+}
+
+impl From<McycleDelay> for Delay {
+    fn from(val: McycleDelay) -> Self {
+        Self::Machine(val)
+    }
+}
+
+impl From<UcycleDelay> for Delay {
+    fn from(val: UcycleDelay) -> Self {
+        Self::User(val)
+    }
+}
+
+impl DelayNs for Delay {
+    #[inline]
+    fn delay_ns(&mut self, ns: u32) {
+        match self {
+            Self::Machine(delay) => delay.delay_ns(ns),
+            Self::User(delay) => delay.delay_ns(ns),
+        }
     }
 }
 
@@ -67,3 +205,175 @@ pub fn u74_mdelay() -> McycleDelay {
 pub fn u74_udelay() -> UcycleDelay {
     UcycleDelay::new(U74_CLOCK_HZ)
 }
+
+/// Non-blocking countdown timer backed by the machine mode cycle counter (`mcycle`).
+///
+/// Once started, [CountDown::wait] returns `WouldBlock` until the deadline is reached. Each
+/// time the deadline is reached, the countdown is restarted with the same period, so it can
+/// drive periodic work.
+///
+/// Started with [CountDown::start_oneshot], the countdown is not restarted, and bounds polling
+/// loops as a timeout instead.
+///
+/// Example:
+///
+/// ```no_run
+/// use core::time::Duration;
+/// use jh71xx_hal::delay;
+///
+/// let mut timer = delay::CountDown::new(delay::U74_CLOCK_HZ);
+/// timer.start(Duration::from_millis(100));
+///
+/// loop {
+///     if timer.wait().is_ok() {
+///         // poll the sensor every 100 ms
+///     }
+///     // do other work
+/// }
+/// ```
+#[derive(Clone, Copy)]
+pub struct CountDown {
+    ticks_second: u64,
+    period: u64,
+    deadline: u64,
+    running: bool,
+    periodic: bool,
+}
+
+impl CountDown {
+    /// Create a new [CountDown] from the provided parameter.
+    ///
+    /// `ticks_second`: clock cycle rate (in Hertz).
+    pub const fn new(ticks_second: u64) -> Self {
+        Self {
+            ticks_second,
+            period: 0,
+            deadline: 0,
+            running: false,
+            periodic: false,
+        }
+    }
+
+    /// Starts the countdown, expiring after `duration`.
+    ///
+    /// Restarts the countdown if it is already running.
+    pub fn start(&mut self, duration: Duration) {
+        self.arm(duration);
+        self.periodic = true;
+    }
+
+    /// Starts a one-shot countdown, expiring after `duration`.
+    ///
+    /// Unlike [CountDown::start], the countdown is not restarted on expiry: [CountDown::wait]
+    /// keeps returning `Ok` until the countdown is restarted, or cancelled.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// use core::time::Duration;
+    /// use jh71xx_hal::delay;
+    ///
+    /// let mut timeout = delay::u74_countdown();
+    /// timeout.start_oneshot(Duration::from_millis(1));
+    ///
+    /// while timeout.wait().is_err() {
+    ///     // poll the peripheral status
+    /// }
+    /// ```
+    pub fn start_oneshot(&mut self, duration: Duration) {
+        self.arm(duration);
+        self.periodic = false;
+    }
+
+    // Sets the period, and deadline for a countdown expiring after `duration`.
+    fn arm(&mut self, duration: Duration) {
+        // Multiply in 128-bit, a 64-bit product saturates after ~12 s at the `U74` clock rate
+        let cycles = duration
+            .as_nanos()
+            .saturating_mul(self.ticks_second as u128)
+            / 1_000_000_000u128;
+        self.period = u64::try_from(cycles).unwrap_or(u64::MAX);
+        self.deadline = mcycle::read64().wrapping_add(self.period);
+        self.running = true;
+    }
+
+    /// Stops the countdown.
+    pub fn cancel(&mut self) {
+        self.running = false;
+    }
+
+    /// Gets whether the countdown is running.
+    pub const fn is_running(&self) -> bool {
+        self.running
+    }
+
+    /// Gets whether the deadline has been reached, without restarting a periodic countdown.
+    ///
+    /// Returns `false` if the countdown is not running.
+    pub fn is_expired(&self) -> bool {
+        // Compare the signed distance, so the deadline is handled across counter wrap-around
+        self.running && (mcycle::read64().wrapping_sub(self.deadline) as i64) >= 0
+    }
+
+    /// Waits for the countdown to expire (non-blocking).
+    ///
+    /// Returns `WouldBlock` until the deadline is reached, or if the countdown is not running.
+    /// On expiry, a periodic countdown restarts from the expired deadline, so periodic work
+    /// does not drift.
+    pub fn wait(&mut self) -> nb::Result<(), Infallible> {
+        if !self.is_expired() {
+            Err(nb::Error::WouldBlock)
+        } else {
+            if self.periodic {
+                self.deadline = self.deadline.wrapping_add(self.period);
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Convenience function to get a [CountDown] for the `U74` riscv core.
+pub fn u74_countdown() -> CountDown {
+    CountDown::new(U74_CLOCK_HZ)
+}
+
+// Converts clock `cycles` to nanoseconds, saturating on overflow.
+const fn cycles_to_ns(cycles: u64, ticks_second: u64) -> u64 {
+    if ticks_second == 0 {
+        return u64::MAX;
+    }
+
+    let ns = (cycles as u128) * 1_000_000_000u128 / (ticks_second as u128);
+    if ns > u64::MAX as u128 {
+        u64::MAX
+    } else {
+        ns as u64
+    }
+}
+
+// Busy-waits until `counter` has advanced by more than `cycles`.
+#[inline]
+fn spin_cycles(counter: fn() -> u64, cycles: u64) {
+    let t0 = counter();
+    while counter().wrapping_sub(t0) <= cycles {}
+}
+
+// Converts `ms` milliseconds to clock cycles, saturating on overflow.
+const fn ms_to_cycles(ms: u64, ticks_second: u64) -> u64 {
+    let cycles = (ms as u128) * (ticks_second as u128) / 1_000u128;
+    if cycles > u64::MAX as u128 {
+        u64::MAX
+    } else {
+        cycles as u64
+    }
+}
+
+// Converts `ns` nanoseconds to clock cycles, saturating on overflow.
+const fn ns_to_cycles(ns: u64, ticks_second: u64) -> u64 {
+    let cycles = (ns as u128) * (ticks_second as u128) / 1_000_000_000u128;
+    if cycles > u64::MAX as u128 {
+        u64::MAX
+    } else {
+        cycles as u64
+    }
+}
@@ -0,0 +1,307 @@
+//! Minimal driver for the JH7110 `DesignWare` AXI DMA controller (`DMAC`).
+//!
+//! Only single-block transfers between memory and a peripheral FIFO using hardware handshaking
+//! are supported, which is enough to feed peripheral transmit FIFOs from memory, and drain
+//! receive FIFOs into memory.
+//!
+//! The `DMAC` is not bus-coherent with the CPU caches. Source buffers must be written back to
+//! memory (or placed in uncached memory) before starting a transfer, and destination buffers
+//! must be invalidated before reading the received data.
+//!
+//! Peripheral drivers only use the `DMAC` with the `dma` feature. Without it, their DMA methods
+//! fall back to PIO, and ignore the [Channel] argument.
+
+/// Base address of the AXI `DMAC` register space.
+pub const DMAC_BASE: usize = 0x1605_0000;
+/// Number of `DMAC` channels.
+pub const DMAC_CHANNELS: usize = 4;
+/// Maximum number of data items in a single block transfer.
+pub const MAX_BLOCK_TS: usize = 65536;
+
+// Common register offsets
+const DMAC_CFGREG: usize = 0x10;
+const DMAC_CHENREG: usize = 0x18;
+
+// Channel register offsets (from the channel register block)
+const CH_BASE: usize = 0x100;
+const CH_STRIDE: usize = 0x100;
+const CH_SAR: usize = 0x00;
+const CH_DAR: usize = 0x08;
+const CH_BLOCK_TS: usize = 0x10;
+const CH_CTL: usize = 0x18;
+const CH_CFG: usize = 0x20;
+const CH_INTSTATUS_ENABLE: usize = 0x80;
+const CH_INTSTATUS: usize = 0x88;
+const CH_INTCLEAR: usize = 0x98;
+
+// Number of status polls before giving up on disabling a channel.
+const ABORT_POLL_COUNT: usize = 1000;
+
+// `DMAC_CFGREG` bits
+const CFG_DMAC_EN: u32 = 1 << 0;
+
+// `CHx_CTL` (low word) fields
+const CTL_SINC_FIXED: u32 = 1 << 4;
+const CTL_DINC_FIXED: u32 = 1 << 6;
+const CTL_SRC_TR_WIDTH_SHIFT: u32 = 8;
+const CTL_DST_TR_WIDTH_SHIFT: u32 = 11;
+const CTL_SRC_MSIZE_SHIFT: u32 = 14;
+const CTL_DST_MSIZE_SHIFT: u32 = 18;
+// Burst of four data items
+const CTL_MSIZE_4: u32 = 1;
+
+// `CHx_CFG` fields, the JH7110 `DMAC` uses the extended (`CFG2`) layout
+const CFG_LO_SRC_PER_SHIFT: u32 = 4;
+const CFG_LO_DST_PER_SHIFT: u32 = 11;
+const CFG_LO_PER_MASK: u32 = 0x7f;
+const CFG_HI_TT_FC_MEM_TO_PER: u32 = 1;
+const CFG_HI_TT_FC_PER_TO_MEM: u32 = 2;
+
+// `CHx_INTSTATUS` bits
+const INT_DMA_TFR_DONE: u32 = 1 << 1;
+// Source/destination decode, slave and LLI errors
+const INT_ERR_MASK: u32 = 0xfe0;
+
+/// Represents a `DMAC` channel.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Channel {
+    #[default]
+    Ch0 = 0,
+    Ch1 = 1,
+    Ch2 = 2,
+    Ch3 = 3,
+}
+
+impl Channel {
+    /// Gets the channel index.
+    pub const fn index(&self) -> usize {
+        *self as usize
+    }
+
+    const fn reg(&self, offset: usize) -> usize {
+        DMAC_BASE + CH_BASE + self.index() * CH_STRIDE + offset
+    }
+}
+
+/// Represents the width of a single data item transferred by the `DMAC`.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum TransferWidth {
+    #[default]
+    Bits8 = 0,
+    Bits16 = 1,
+    Bits32 = 2,
+}
+
+/// Represents `DMAC` errors.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Error {
+    /// Block transfer exceeds [MAX_BLOCK_TS] data items.
+    BlockSize(usize),
+    /// `DMAC` channel is still busy with another transfer.
+    Busy(Channel),
+    /// `DMAC` reported a bus or decode error, contains the raw interrupt status.
+    Transfer(u32),
+}
+
+/// Convenience alias for the `DMAC` result type.
+pub type Result<T> = core::result::Result<T, Error>;
+
+// SAFETY: callers provide offsets within the `DMAC` register space.
+unsafe fn read_reg(addr: usize) -> u32 {
+    (addr as *const u32).read_volatile()
+}
+
+// SAFETY: callers provide offsets within the `DMAC` register space.
+unsafe fn write_reg(addr: usize, val: u32) {
+    (addr as *mut u32).write_volatile(val)
+}
+
+/// Gets whether the `DMAC` channel is enabled (i.e. has a transfer in progress).
+pub fn is_enabled(ch: Channel) -> bool {
+    // SAFETY: `DMAC_CHENREG` is within the `DMAC` register space.
+    unsafe { read_reg(DMAC_BASE + DMAC_CHENREG) & (1 << ch.index()) != 0 }
+}
+
+/// Starts a memory-to-peripheral transfer of `count` data items on `ch`.
+///
+/// - `src`: address of the source buffer, incremented after each item
+/// - `dst`: address of the peripheral data register, fixed for the whole transfer
+/// - `handshake`: hardware handshaking interface of the destination peripheral
+///
+/// # Safety
+///
+/// `src` must be valid for reads of `count` items of `width` until the transfer completes, or
+/// is aborted. `dst` must be the address of a peripheral data register.
+pub unsafe fn start_mem_to_periph(
+    ch: Channel,
+    src: usize,
+    dst: usize,
+    count: usize,
+    width: TransferWidth,
+    handshake: u8,
+) -> Result<()> {
+    // Hardware handshaking on the destination, software on the (memory) source
+    let cfg_lo = (handshake as u32 & CFG_LO_PER_MASK) << CFG_LO_DST_PER_SHIFT;
+
+    start(
+        ch,
+        src,
+        dst,
+        count,
+        width,
+        CTL_DINC_FIXED,
+        (cfg_lo, CFG_HI_TT_FC_MEM_TO_PER),
+    )
+}
+
+/// Starts a memory-to-peripheral transfer of `count` copies of the data item at `src` on `ch`.
+///
+/// Same as [start_mem_to_periph], except the source address is fixed, e.g. to clock out
+/// dummy words for a receive-only transfer.
+///
+/// # Safety
+///
+/// `src` must be valid for reads of one item of `width` until the transfer completes, or is
+/// aborted. `dst` must be the address of a peripheral data register.
+pub unsafe fn start_fixed_to_periph(
+    ch: Channel,
+    src: usize,
+    dst: usize,
+    count: usize,
+    width: TransferWidth,
+    handshake: u8,
+) -> Result<()> {
+    let cfg_lo = (handshake as u32 & CFG_LO_PER_MASK) << CFG_LO_DST_PER_SHIFT;
+
+    start(
+        ch,
+        src,
+        dst,
+        count,
+        width,
+        CTL_SINC_FIXED | CTL_DINC_FIXED,
+        (cfg_lo, CFG_HI_TT_FC_MEM_TO_PER),
+    )
+}
+
+/// Starts a peripheral-to-memory transfer of `count` data items on `ch`.
+///
+/// - `src`: address of the peripheral data register, fixed for the whole transfer
+/// - `dst`: address of the destination buffer, incremented after each item
+/// - `handshake`: hardware handshaking interface of the source peripheral
+///
+/// # Safety
+///
+/// `dst` must be valid for writes of `count` items of `width` until the transfer completes, or
+/// is aborted. `src` must be the address of a peripheral data register.
+pub unsafe fn start_periph_to_mem(
+    ch: Channel,
+    src: usize,
+    dst: usize,
+    count: usize,
+    width: TransferWidth,
+    handshake: u8,
+) -> Result<()> {
+    // Hardware handshaking on the source, software on the (memory) destination
+    let cfg_lo = (handshake as u32 & CFG_LO_PER_MASK) << CFG_LO_SRC_PER_SHIFT;
+
+    start(
+        ch,
+        src,
+        dst,
+        count,
+        width,
+        CTL_SINC_FIXED,
+        (cfg_lo, CFG_HI_TT_FC_PER_TO_MEM),
+    )
+}
+
+// Programs, and enables a single-block transfer on `ch`.
+//
+// SAFETY: see the public `start_*` functions.
+unsafe fn start(
+    ch: Channel,
+    src: usize,
+    dst: usize,
+    count: usize,
+    width: TransferWidth,
+    ctl_inc: u32,
+    (cfg_lo, cfg_hi): (u32, u32),
+) -> Result<()> {
+    if count == 0 || count > MAX_BLOCK_TS {
+        return Err(Error::BlockSize(count));
+    }
+    if is_enabled(ch) {
+        return Err(Error::Busy(ch));
+    }
+
+    let width = width as u32;
+    let ctl = ctl_inc
+        | (width << CTL_SRC_TR_WIDTH_SHIFT)
+        | (width << CTL_DST_TR_WIDTH_SHIFT)
+        | (CTL_MSIZE_4 << CTL_SRC_MSIZE_SHIFT)
+        | (CTL_MSIZE_4 << CTL_DST_MSIZE_SHIFT);
+
+    write_reg(
+        DMAC_BASE + DMAC_CFGREG,
+        read_reg(DMAC_BASE + DMAC_CFGREG) | CFG_DMAC_EN,
+    );
+
+    write_reg(ch.reg(CH_SAR), src as u32);
+    write_reg(ch.reg(CH_SAR) + 4, ((src as u64) >> 32) as u32);
+    write_reg(ch.reg(CH_DAR), dst as u32);
+    write_reg(ch.reg(CH_DAR) + 4, ((dst as u64) >> 32) as u32);
+    write_reg(ch.reg(CH_BLOCK_TS), (count - 1) as u32);
+    write_reg(ch.reg(CH_CTL), ctl);
+    write_reg(ch.reg(CH_CTL) + 4, 0);
+    write_reg(ch.reg(CH_CFG), cfg_lo);
+    write_reg(ch.reg(CH_CFG) + 4, cfg_hi);
+
+    write_reg(ch.reg(CH_INTCLEAR), u32::MAX);
+    write_reg(ch.reg(CH_INTSTATUS_ENABLE), INT_DMA_TFR_DONE | INT_ERR_MASK);
+
+    // Channel enable bits are only written when the matching write-enable bit is set
+    let en = 1u32 << ch.index();
+    write_reg(DMAC_BASE + DMAC_CHENREG, en | (en << 8));
+
+    Ok(())
+}
+
+/// Polls the transfer on `ch` for completion.
+///
+/// Completion status is kept until the next transfer starts on the channel, so polling a
+/// completed transfer repeatedly is fine.
+pub fn poll(ch: Channel) -> nb::Result<(), Error> {
+    // SAFETY: channel registers are within the `DMAC` register space.
+    let status = unsafe { read_reg(ch.reg(CH_INTSTATUS)) };
+
+    if status & INT_ERR_MASK != 0 {
+        abort(ch);
+        Err(nb::Error::Other(Error::Transfer(status)))
+    } else if status & INT_DMA_TFR_DONE != 0 {
+        Ok(())
+    } else {
+        Err(nb::Error::WouldBlock)
+    }
+}
+
+/// Disables `ch`, aborting any in-progress transfer.
+pub fn abort(ch: Channel) {
+    let en = 1u32 << ch.index();
+
+    // SAFETY: `DMAC` registers are within the `DMAC` register space, and only the bits
+    // for the selected channel are written.
+    unsafe {
+        write_reg(DMAC_BASE + DMAC_CHENREG, en << 8);
+        for _ in 0..ABORT_POLL_COUNT {
+            if read_reg(DMAC_BASE + DMAC_CHENREG) & en == 0 {
+                break;
+            }
+            core::hint::spin_loop();
+        }
+        write_reg(ch.reg(CH_INTCLEAR), u32::MAX);
+    }
+}
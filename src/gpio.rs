@@ -10,7 +10,7 @@
 //! let gpio0 = gpio::get_gpio(dp.SYS_PINCTRL.gpio_0());
 //!
 //! // Configure as an enabled output
-//! let mut gpio0_out = gpio0.into_enabled_output();
+//! let mut gpio0_out = gpio0.into_enabled_output().into_infallible();
 //!
 //! // Drive pin high
 //! gpio0_out.set_high();
@@ -18,7 +18,7 @@
 //! gpio0_out.set_low();
 //!
 //! // Configure as an enabled input
-//! let gpio0_in = gpio0_out.into_enabled_input();
+//! let gpio0_in = gpio0_out.into_inner().into_enabled_input();
 //!
 //! // Configure as high-impedance input
 //! let gpio0_in_high_z = gpio0_in.into_input_high_z();
@@ -42,22 +42,73 @@
 //!
 //! `JH7110` SoCs use a pin multiplexer to configure pins for specialized functionality (I2C, SPI, etc.).
 //!
-//! Work is on-going to provide high-level interfaces to configure specialized functions for GPIO pins.
+//! Function signals can be routed to `SYS_PINCTRL` GPIO pads with `Gpio::into_function`, or `gpio::set_function`. Work is on-going to provide typed function signals, and function routing for the always-on (`AON`) muxer.
+//!
+//! Pads carrying high-speed SPI, or I2C signals can be given recommended drive strength, slew, and Schmitt Trigger settings with `gpio::apply_electrical_preset`, or `Gpio::with_electrical_profile`.
+//!
+//! The always-on (`AON`) GPIO pads are available as plain GPIOs with `gpio::AonPins`, and `gpio::get_aon_gpio`. They can be armed as `PMU` wakeup sources with `AonGpio::arm_wakeup`.
 //!
 //! Low-level configuration can currently be achieved through the `jh71xx-pac` crate which is re-exported as `jh71xx_hal::pac`.
+//!
+//! ### Debounce
+//!
+//! The `JH7110` pad configuration registers (`IE`, `DS`, `PU`, `PD`, `SLEW`, `SMT`, `POS`) have no debounce filter, for any `SYS_PINCTRL` or `AON_PINCTRL` pad. So there is no hardware debounce period to program, and `Gpio::set_debounce`, and `Gpio::set_debounce_period` always return `Error::InvalidPad`.
+//!
+//! For mechanical buttons, enable the Schmitt Trigger hysteresis to reject slow edges and noise, and debounce in software, e.g. by sampling the input with a [CountDown](crate::delay::CountDown):
+//!
+//! ```no_run
+//! use core::time::Duration;
+//! use jh71xx_hal::{delay, gpio, pac};
+//! use embedded_hal::digital::InputPin;
+//!
+//! let dp = pac::Peripherals::take().unwrap();
+//! let mut button = gpio::get_gpio(dp.SYS_PINCTRL.gpio_40())
+//!     .into_enabled_input()
+//!     .into_input_pull_up()
+//!     .with_schmitt_trigger(gpio::SchmittTrigger::Enable);
+//!
+//! let mut timer = delay::u74_countdown();
+//! timer.start(Duration::from_millis(5));
+//!
+//! let mut last = button.is_low().unwrap();
+//! let mut stable = 0;
+//! loop {
+//!     if timer.wait().is_ok() {
+//!         let now = button.is_low().unwrap();
+//!         stable = if now == last { stable + 1 } else { 0 };
+//!         last = now;
+//!
+//!         if stable == 4 && now {
+//!             // button press held stable for 20 ms
+//!         }
+//!     }
+//! }
+//! ```
+
+use core::convert::Infallible;
+use core::marker::PhantomData;
+use core::time::Duration;
 
-use embedded_hal::digital::{ErrorType, InputPin, OutputPin};
+use embedded_hal::digital::{ErrorType, InputPin, OutputPin, PinState, StatefulOutputPin};
 
 use crate::pac::SYS_PINCTRL;
 
+mod aon;
+mod bank;
 mod config;
+mod dynamic;
 mod error;
 mod functions;
+mod irq;
 mod pad;
 
+pub use aon::*;
+pub use bank::*;
 pub use config::*;
+pub use dynamic::*;
 pub use error::*;
 pub use functions::*;
+pub use irq::*;
 pub use pad::*;
 
 /// Configures the GPIO as enabled.
@@ -77,24 +128,45 @@ pub struct PullDown;
 /// Configures the GPIO as pull-up input.
 pub struct PullUp;
 
+/// Configures the GPIO as an open-drain output.
+pub struct OpenDrain;
+
 /// Not-important placeholder
 pub struct Nop;
 
-/// Configures how to drive a GPIO.
+/// Configures the GPIO as routed to the [Function] signal `F`.
+pub struct Alternate<F: ?Sized>(PhantomData<F>);
+
+/// Configures the output enable of a GPIO, through its `DOEN` muxer register.
+///
+/// The JH7110 GPIO muxer `DOEN` field selects the signal driving the active-low output enable
+/// of the pad. Selectors `0` and `1` tie the output enable to a constant level, all others
+/// route the output enable of an internal peripheral signal to the pad.
+///
+/// Only the encodings used by the HAL are represented, other values are rejected by the
+/// `TryFrom<u8>` conversion.
 #[repr(u8)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum OutputConfig {
+    /// Output enable tied low: the output driver is enabled.
     Low = 0b00,
+    /// Output enable tied high: the output driver is disabled (high-impedance).
     Neutral = 0b01,
+    /// Output enable routed from internal peripheral signal `3`.
+    ///
+    /// **NOTE**: this does not drive the pad high, the pad level is set through [OutputDrive].
     High = 0b11,
 }
 
-impl From<u8> for OutputConfig {
-    fn from(val: u8) -> Self {
+impl TryFrom<u8> for OutputConfig {
+    type Error = Error;
+
+    fn try_from(val: u8) -> Result<Self> {
         match val {
-            0b00 => Self::Low,
-            0b01 => Self::Neutral,
-            0b11 => Self::High,
-            _ => Self::Neutral,
+            0b00 => Ok(Self::Low),
+            0b01 => Ok(Self::Neutral),
+            0b11 => Ok(Self::High),
+            _ => Err(Error::InvalidOutputConfig(val)),
         }
     }
 }
@@ -105,29 +177,44 @@ impl From<OutputConfig> for u8 {
     }
 }
 
-/// Options to drive the GPIO.
+/// Options to drive the GPIO, through its `DOUT` muxer register.
+///
+/// The JH7110 GPIO muxer `DOUT` field selects the signal driven on the pad when the output is
+/// enabled (see [OutputConfig]). Selectors `0` and `1` drive a constant level, all others route
+/// the output of an internal peripheral signal to the pad.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum OutputDrive {
-    Low = 0b0,
-    High = 0b1,
+    /// Pad driven to a constant low level.
+    Low = 0b00,
+    /// Pad driven to a constant high level.
+    High = 0b01,
+    /// Pad driven by internal peripheral signal `2`.
     HighLow = 0b10,
+    /// Pad driven by internal peripheral signal `3`.
     HighHigh = 0b11,
 }
 
-impl From<u8> for OutputDrive {
-    fn from(val: u8) -> Self {
-        match val & 0b11 {
-            0b00 => Self::Low,
-            0b01 => Self::High,
-            0b10 => Self::HighLow,
-            0b11 => Self::HighHigh,
-            _ => Self::Low,
+impl TryFrom<u8> for OutputDrive {
+    type Error = Error;
+
+    fn try_from(val: u8) -> Result<Self> {
+        match val {
+            0b00 => Ok(Self::Low),
+            0b01 => Ok(Self::High),
+            0b10 => Ok(Self::HighLow),
+            0b11 => Ok(Self::HighHigh),
+            _ => Err(Error::InvalidOutputDrive(val)),
         }
     }
 }
 
 impl From<bool> for OutputDrive {
     fn from(val: bool) -> Self {
-        (val as u8).into()
+        match val {
+            false => Self::Low,
+            true => Self::High,
+        }
     }
 }
 
@@ -137,6 +224,206 @@ impl From<OutputDrive> for u8 {
     }
 }
 
+// Reads the synchronized input level of GPIO `pad` from the `IOIRQ_15`/`IOIRQ_16` registers.
+//
+// Returns [Error::InvalidPad] for pads without an input sync bit, i.e. pads above GPIO 63.
+pub(crate) fn read_pad_input(pad: u32) -> Result<bool> {
+    // SAFETY: `IOIRQ_15` and `IOIRQ_16` are read-only, their values are only changed by the
+    // hardware.
+    let pinctrl = unsafe { &*SYS_PINCTRL::ptr() };
+
+    match pad {
+        0..=31 => Ok((pinctrl.ioirq_15().read().bits() >> pad) & 0x1 != 0),
+        32..=63 => Ok((pinctrl.ioirq_16().read().bits() >> (pad - 32)) & 0x1 != 0),
+        _ => Err(Error::InvalidPad(pad)),
+    }
+}
+
+/// Number of GPIO pads sharing a `DOUT` muxer register.
+pub const GPO_DOUT_PADS: u32 = 4;
+
+/// Drives multiple GPIO pads sharing a `DOUT` muxer register with a single register write.
+///
+/// - `mask`: pads to update, bit `n` selects GPIO `n`
+/// - `value`: level of each selected pad, bit `n` drives GPIO `n` high when set
+///
+/// Each `DOUT` register holds the selectors for [GPO_DOUT_PADS] consecutive pads (GPIO 0-3,
+/// 4-7, etc.), so only pads in the same group update simultaneously. Returns
+/// [Error::InvalidPad] with the first offending pad if the selected pads span more than one
+/// group.
+///
+/// The pads should already be configured as enabled outputs, e.g. with
+/// [Gpio::into_enabled_output]. Pads not selected by `mask` are left untouched.
+///
+/// Example:
+///
+/// ```no_run
+/// use jh71xx_hal::{gpio, pac};
+///
+/// let dp = pac::Peripherals::take().unwrap();
+/// let bank = gpio::GpioBank::new(dp.SYS_PINCTRL);
+/// let _d0 = bank.gpio40.into_enabled_output();
+/// let _d1 = bank.gpio41.into_enabled_output();
+/// let _d2 = bank.gpio42.into_enabled_output();
+/// let _d3 = bank.gpio43.into_enabled_output();
+///
+/// // Put the nibble `0b1010` on GPIO 40-43 in one write
+/// let nibble = 0b1010u64;
+/// gpio::write_port(0xf << 40, nibble << 40).unwrap();
+///
+/// // GPIO 39 and 40 are in different `DOUT` registers
+/// assert!(gpio::write_port(0b11 << 39, 0).is_err());
+/// ```
+pub fn write_port(mask: u64, value: u64) -> Result<()> {
+    if mask == 0 {
+        return Ok(());
+    }
+
+    let first = mask.trailing_zeros();
+    let group = first / GPO_DOUT_PADS;
+    let group_mask = 0xfu64 << (group * GPO_DOUT_PADS);
+
+    if mask & !group_mask != 0 {
+        return Err(Error::InvalidPad((mask & !group_mask).trailing_zeros()));
+    }
+
+    let reg = (SYS_PINCTRL::ptr() as usize + GPO_DOUT_OFFSET + group as usize * 4) as *mut u32;
+
+    // SAFETY: the `DOUT` registers are within the `SYS_PINCTRL` register space, and only the
+    // selector fields of the pads selected by `mask` are modified.
+    atomic(|| unsafe {
+        let mut dout = reg.read_volatile();
+
+        for lane in 0..GPO_DOUT_PADS {
+            let pad = group * GPO_DOUT_PADS + lane;
+
+            if mask & (1 << pad) != 0 {
+                let drive = OutputDrive::from(value & (1 << pad) != 0);
+                let shift = lane * 8;
+
+                dout &= !(GPO_DOUT_MASK << shift);
+                dout |= (u8::from(drive) as u32) << shift;
+            }
+        }
+
+        reg.write_volatile(dout);
+    });
+
+    Ok(())
+}
+
+/// Samples the input levels of `count` consecutive GPIO pads, starting at `start_pad`.
+///
+/// Bit `n` of the result is the level of GPIO `start_pad + n`, so up to 32 pads are read at
+/// once. The synchronized input levels of GPIO 0-31, and 32-63 are held in the `IOIRQ_15`, and
+/// `IOIRQ_16` registers, each read once: ranges within one register are a coherent snapshot.
+///
+/// Ranges straddling GPIO 32 read both registers back-to-back with interrupts disabled (with
+/// the `rt` feature), so the two halves are sampled a few bus cycles apart.
+///
+/// Returns [Error::InvalidPad] with the first offending pad if the range extends past GPIO 63,
+/// or is wider than 32 pads.
+///
+/// The pads should already be configured as enabled inputs, e.g. with
+/// [Gpio::into_enabled_input].
+///
+/// Example:
+///
+/// ```no_run
+/// use jh71xx_hal::{gpio, pac};
+///
+/// let dp = pac::Peripherals::take().unwrap();
+/// let bank = gpio::GpioBank::new(dp.SYS_PINCTRL);
+/// let _a = bank.gpio31.into_enabled_input();
+/// let _b = bank.gpio32.into_enabled_input();
+///
+/// // Sample both rotary encoder lines, straddling the `IOIRQ_15`/`IOIRQ_16` boundary
+/// let ab = gpio::read_port(gpio::PAD_GPIO31, 2).unwrap();
+/// let (_a_high, _b_high) = (ab & 0b01 != 0, ab & 0b10 != 0);
+/// ```
+pub fn read_port(start_pad: u32, count: u32) -> Result<u32> {
+    if start_pad > PAD_GPIO63 {
+        return Err(Error::InvalidPad(start_pad));
+    }
+
+    // Checked before computing the end of the range, so large counts cannot overflow
+    if count > 32 {
+        return Err(Error::InvalidPad(start_pad + 32));
+    }
+
+    let end = start_pad + count;
+
+    if end > PAD_GPIO63 + 1 {
+        return Err(Error::InvalidPad(PAD_GPIO63 + 1));
+    }
+    if count == 0 {
+        return Ok(0);
+    }
+
+    // SAFETY: `IOIRQ_15` and `IOIRQ_16` are read-only, their values are only changed by the
+    // hardware.
+    let pinctrl = unsafe { &*SYS_PINCTRL::ptr() };
+
+    let levels = atomic(|| {
+        let lo = if start_pad < 32 {
+            pinctrl.ioirq_15().read().bits() as u64
+        } else {
+            0
+        };
+        let hi = if end > 32 {
+            (pinctrl.ioirq_16().read().bits() as u64) << 32
+        } else {
+            0
+        };
+
+        hi | lo
+    });
+
+    Ok(((levels >> start_pad) & ((1u64 << count) - 1)) as u32)
+}
+
+/// Applies the recommended [ElectricalProfile] settings to the GPIO pad configuration `gpio`.
+///
+/// Sets the [DriveStrength], [Slew], and [SchmittTrigger] of the pad, leaving the input
+/// enable, pull, and Power-on-Start settings untouched. Use after routing a peripheral
+/// function to the pad, e.g. with [set_function].
+///
+/// Example:
+///
+/// ```no_run
+/// use jh71xx_hal::gpio::{self, ElectricalProfile};
+/// use jh71xx_hal::pac;
+///
+/// let dp = pac::Peripherals::take().unwrap();
+/// let pinctrl = &dp.SYS_PINCTRL;
+///
+/// // SPI0 pads on the VisionFive 2 40-pin header
+/// gpio::apply_electrical_preset(pinctrl.gpio_48(), ElectricalProfile::SpiClock);
+/// gpio::apply_electrical_preset(pinctrl.gpio_52(), ElectricalProfile::SpiOutput);
+/// gpio::apply_electrical_preset(pinctrl.gpio_49(), ElectricalProfile::SpiOutput);
+/// gpio::apply_electrical_preset(pinctrl.gpio_53(), ElectricalProfile::SpiInput);
+/// ```
+pub fn apply_electrical_preset<GPIO: GpioCfg>(gpio: &GPIO, profile: ElectricalProfile) {
+    gpio.set_drive_strength(profile.drive_strength());
+    gpio.set_slew(profile.slew());
+    gpio.set_schmitt_trigger(profile.schmitt_trigger());
+}
+
+// Runs `f` with interrupts disabled, if interrupts are enabled by the `rt` feature.
+//
+// Used to make read-modify-writes of muxer registers shared between pads interrupt-safe.
+#[inline]
+fn atomic<R>(f: impl FnOnce() -> R) -> R {
+    #[cfg(feature = "rt")]
+    {
+        crate::interrupt::free(f)
+    }
+    #[cfg(not(feature = "rt"))]
+    {
+        f()
+    }
+}
+
 /// Represents a GPIO peripheral on a JH71xx-based board.
 pub struct Gpio<'g, GPIO: GpioCfg, ENABLED, DIRECTION, MODE> {
     periph: &'g GPIO,
@@ -186,6 +473,89 @@ impl<'g, GPIO: GpioCfg, ENABLED, DIRECTION, MODE> Gpio<'g, GPIO, ENABLED, DIRECT
         }
     }
 
+    /// Converts the [Gpio] into an enabled open-drain output.
+    ///
+    /// The pad only ever drives low: setting the pin high disables the output driver through
+    /// the `DOEN` register, releasing the line. The line level while released is set by a
+    /// pull-up, either external, or the internal pad pull-up from [Gpio::set_pull].
+    ///
+    /// The pad input stays enabled, so the actual line level can be read back, e.g. for
+    /// wired-AND buses like 1-Wire, or bit-banged I2C.
+    ///
+    /// The pin starts released.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// use embedded_hal::digital::{InputPin, OutputPin};
+    /// use jh71xx_hal::{gpio, pac};
+    ///
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let mut line = gpio::get_gpio(dp.SYS_PINCTRL.gpio_44())
+    ///     .into_open_drain_output()
+    ///     .with_pull(gpio::Pull::Up);
+    ///
+    /// line.set_low().unwrap();
+    /// line.set_high().unwrap();
+    ///
+    /// // Another device may still hold the line low
+    /// let _level = line.is_high().unwrap();
+    /// ```
+    pub fn into_open_drain_output(mut self) -> Gpio<'g, GPIO, Enabled, Output, OpenDrain> {
+        // Release the line before selecting the low level, so the pad never drives high
+        self.disable_output();
+        atomic(|| Gpio::<'g, GPIO, Enabled, Output, Nop>::drive_output_inner(OutputDrive::Low));
+        self.periph.input_enable(true);
+
+        Gpio {
+            periph: self.periph,
+            _enabled: Enabled,
+            _direction: Output,
+            _mode: OpenDrain,
+        }
+    }
+
+    /// Converts the [Gpio] into a pin routed to the [Function] signal `F`.
+    ///
+    /// Input function signals also enable the pad input.
+    ///
+    /// Returns [Error::InvalidPad] if the signal cannot be routed to the pad.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{pac, gpio};
+    /// struct I2c0SdaIn;
+    ///
+    /// impl gpio::Function for I2c0SdaIn {
+    ///     const GROUP: gpio::GpioGroup = gpio::GpioGroup::Gpi;
+    ///     const INDEX: u8 = gpio::GpiFunction::U0_DW_I2C_IC_DATA_IN_A;
+    /// }
+    ///
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let gpio5 = gpio::get_gpio(dp.SYS_PINCTRL.gpio_5());
+    /// let _sda = gpio5.into_function::<I2c0SdaIn>().unwrap();
+    /// ```
+    pub fn into_function<F: Function + ?Sized>(
+        self,
+    ) -> Result<Gpio<'g, GPIO, Enabled, Nop, Alternate<F>>>
+    where
+        GPIO: MuxPad,
+    {
+        set_function::<F>(GPIO::pad())?;
+
+        if F::GROUP == GpioGroup::Gpi {
+            self.periph.input_enable(true);
+        }
+
+        Ok(Gpio {
+            periph: self.periph,
+            _enabled: Enabled,
+            _direction: Nop,
+            _mode: Alternate(PhantomData),
+        })
+    }
+
     fn enable_output(&mut self) {
         self.config_output(OutputConfig::Low);
     }
@@ -195,16 +565,25 @@ impl<'g, GPIO: GpioCfg, ENABLED, DIRECTION, MODE> Gpio<'g, GPIO, ENABLED, DIRECT
     }
 
     fn config_output(&mut self, config: OutputConfig) {
+        if is_aon_pad(GPIO::pad()) {
+            aon_config_output(GPIO::pad(), config);
+        } else {
+            // The DOEN registers are shared by four pads, serialize the read-modify-write
+            atomic(|| Self::config_output_inner(config));
+        }
+    }
+
+    fn config_output_inner(config: OutputConfig) {
         let pinctrl = unsafe { &*SYS_PINCTRL::ptr() };
         let pad = GPIO::pad();
 
-        // StarFive uses a GPIO muxer, the lower two bits of the DOEN registers configure
-        // SET_LOW and SET_HIGH, respectively
+        // StarFive uses a GPIO muxer, the DOEN registers select the signal driving the
+        // active-low output enable of each pad
         //
         // Somewhat counter-intuitively:
-        // - setting SET_LOW to zero enables the GPIO driven low
-        // - setting SET_HIGH to one enables the GPIO driven high
-        // - setting SET_LOW(1) SET_HIGH(0) brings the GPIO to driven neutral
+        // - selector 0 (tied low) enables the output driver
+        // - selector 1 (tied high) disables the output driver, leaving the pad neutral
+        // - the pad level is selected separately by the DOUT registers
         let cfg: u8 = config.into();
         match pad {
             0 => pinctrl.gpo_doen_0().modify(|_, w| w.doen_0().variant(cfg)),
@@ -325,12 +704,110 @@ impl<'g, GPIO: GpioCfg, ENABLED, DIRECTION, MODE> Gpio<'g, GPIO, ENABLED, DIRECT
 }
 
 impl<'g, GPIO: GpioCfg> Gpio<'g, GPIO, Enabled, Output, Nop> {
+    /// Builder function that sets the [DriveStrength] of the output pad.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// use jh71xx_hal::{gpio, pac};
+    ///
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let _clk = gpio::get_gpio(dp.SYS_PINCTRL.gpio_0())
+    ///     .into_enabled_output()
+    ///     .with_drive_strength(gpio::DriveStrength::Twelve)
+    ///     .with_slew(gpio::Slew::Fast);
+    /// ```
+    pub fn with_drive_strength(self, drive_strength: DriveStrength) -> Self {
+        self.periph.set_drive_strength(drive_strength);
+        self
+    }
+
+    /// Builder function that sets the [Slew] rate of the output pad.
+    pub fn with_slew(self, slew: Slew) -> Self {
+        self.periph.set_slew(slew);
+        self
+    }
+
     /// Sets whether the [Gpio] is driven high.
     pub fn set_pin(&mut self, high: bool) {
         self.drive_output(high.into())
     }
 
+    /// Gets the [Pull] configuration of the [Gpio] output.
+    pub fn pull(&self) -> Pull {
+        if self.periph.is_pull_up() {
+            Pull::Up
+        } else if self.periph.is_pull_down() {
+            Pull::Down
+        } else {
+            Pull::None
+        }
+    }
+
+    /// Sets the [Pull] configuration of the [Gpio] output.
+    ///
+    /// A weak pull defines the line level while the output is not actively driven, e.g. for
+    /// open-drain emulation, or during direction changes.
+    pub fn set_pull(&mut self, pull: Pull) {
+        match pull {
+            Pull::None => self.periph.set_high_z(),
+            Pull::Up => self.periph.set_pull_up(),
+            Pull::Down => self.periph.set_pull_down(),
+        }
+    }
+
+    /// Converts the [Gpio] into an [InfalliblePin].
+    ///
+    /// Only available for pads routed through the GPIO muxer, where output writes cannot fail.
+    pub fn into_infallible(self) -> InfalliblePin<'g, GPIO>
+    where
+        GPIO: MuxPad,
+    {
+        InfalliblePin { pin: self }
+    }
+
+    /// Gets the [OutputDrive] selected in the `DOUT` register of the [Gpio].
+    ///
+    /// Returns [Error::InvalidOutputDrive] if the pad is driven by a peripheral signal outside
+    /// the [OutputDrive] encodings.
+    pub fn output_drive(&self) -> Result<OutputDrive> {
+        OutputDrive::try_from(Self::dout())
+    }
+
+    /// Gets whether the [Gpio] is driven high.
+    ///
+    /// Only a constant high drive ([OutputDrive::High]) is reported as high, pads driven by a
+    /// peripheral signal are reported as low.
+    pub fn is_pin_set(&self) -> bool {
+        Self::dout() == OutputDrive::High as u8
+    }
+
+    /// Toggles the level the [Gpio] is driven to.
+    ///
+    /// The read-modify-write of the shared `DOUT` register runs in a critical section, so pins
+    /// in the same register can be driven from different contexts.
+    pub fn toggle(&mut self) {
+        atomic(|| Self::drive_output_inner((Self::dout() != OutputDrive::High as u8).into()));
+    }
+
+    fn dout() -> u8 {
+        if is_aon_pad(GPIO::pad()) {
+            aon_dout(GPIO::pad())
+        } else {
+            read_fmux(GPO_DOUT_OFFSET, GPIO::pad() as usize, GPO_DOUT_MASK) as u8
+        }
+    }
+
     fn drive_output(&mut self, drive: OutputDrive) {
+        // The DOUT registers are shared by four pads, serialize the read-modify-write
+        atomic(|| Self::drive_output_inner(drive));
+    }
+
+    fn drive_output_inner(drive: OutputDrive) {
+        if is_aon_pad(GPIO::pad()) {
+            return aon_drive_output(GPIO::pad(), drive);
+        }
+
         let pinctrl = unsafe { &*SYS_PINCTRL::ptr() };
         let pad = GPIO::pad();
 
@@ -453,31 +930,193 @@ impl<'g, GPIO: GpioCfg> Gpio<'g, GPIO, Enabled, Output, Nop> {
     }
 }
 
+impl<'g, GPIO: GpioCfg> Gpio<'g, GPIO, Enabled, Output, OpenDrain> {
+    /// Builder function that sets the [DriveStrength] of the output pad.
+    pub fn with_drive_strength(self, drive_strength: DriveStrength) -> Self {
+        self.periph.set_drive_strength(drive_strength);
+        self
+    }
+
+    /// Builder function that sets the [Slew] rate of the output pad.
+    pub fn with_slew(self, slew: Slew) -> Self {
+        self.periph.set_slew(slew);
+        self
+    }
+
+    /// Builder function that sets the [Pull] configuration of the open-drain pad.
+    pub fn with_pull(mut self, pull: Pull) -> Self {
+        self.set_pull(pull);
+        self
+    }
+
+    /// Gets the [Pull] configuration of the open-drain pad.
+    pub fn pull(&self) -> Pull {
+        if self.periph.is_pull_up() {
+            Pull::Up
+        } else if self.periph.is_pull_down() {
+            Pull::Down
+        } else {
+            Pull::None
+        }
+    }
+
+    /// Sets the [Pull] configuration of the open-drain pad.
+    ///
+    /// Without an external pull-up, use [Pull::Up] to pull the released line high.
+    pub fn set_pull(&mut self, pull: Pull) {
+        match pull {
+            Pull::None => self.periph.set_high_z(),
+            Pull::Up => self.periph.set_pull_up(),
+            Pull::Down => self.periph.set_pull_down(),
+        }
+    }
+
+    /// Sets whether the open-drain [Gpio] is released (`true`), or driven low (`false`).
+    pub fn set_pin(&mut self, high: bool) {
+        if high {
+            self.disable_output();
+        } else {
+            self.enable_output();
+        }
+    }
+
+    /// Gets whether the open-drain [Gpio] is released.
+    ///
+    /// A released line may still be held low by another device, use [Gpio::bit_is_set] to
+    /// read the actual line level.
+    pub fn is_released(&self) -> bool {
+        Self::doen() != u8::from(OutputConfig::Low)
+    }
+
+    /// Toggles the open-drain [Gpio] between released, and driven low.
+    pub fn toggle(&mut self) {
+        let released = self.is_released();
+        self.set_pin(!released);
+    }
+
+    /// Gets the level of the line connected to the open-drain [Gpio].
+    ///
+    /// Returns [Error::InvalidPad] for pads without an input sync bit in the [`IOIRQ_15`] and
+    /// [`IOIRQ_16`] registers (GPIO 0-31 and 32-63 respectively), e.g. the SD card pads.
+    pub fn bit_is_set(&self) -> Result<bool> {
+        let pad = GPIO::pad();
+
+        if is_aon_pad(pad) {
+            Ok(aon_read_input(pad))
+        } else {
+            read_pad_input(pad)
+        }
+    }
+
+    fn doen() -> u8 {
+        if is_aon_pad(GPIO::pad()) {
+            aon_doen(GPIO::pad())
+        } else {
+            read_fmux(GPO_DOEN_OFFSET, GPIO::pad() as usize, GPO_DOEN_MASK) as u8
+        }
+    }
+}
+
 impl<'g, GPIO: GpioCfg, MODE> Gpio<'g, GPIO, Enabled, Input, MODE> {
-    /// Gets whether the input pin is set.
-    pub fn bit_is_set(&self) -> bool {
-        // [`IOIRQ_15`] and [`IOIRQ_16`] are the GPIO sync registers, for GPIO 0-31 and 32-63
-        // respectively.
-        //
-        // SAFETY:
-        //
-        // It is safe to access to IOIRQ15/16 because they are `read-only`.
-        // Their values are only changed by the hardware.
-        let pinctrl = unsafe { &*SYS_PINCTRL::ptr() };
+    /// Builder function that sets the [SchmittTrigger] hysteresis of the input pad.
+    pub fn with_schmitt_trigger(self, trigger: SchmittTrigger) -> Self {
+        self.periph.set_schmitt_trigger(trigger);
+        self
+    }
+
+    /// Enables, or disables the hardware debounce filter of the input pad.
+    ///
+    /// **NOTE**: `JH7110` pads have no debounce filter, so this always returns
+    /// [Error::InvalidPad]. See the [module](self) docs for debouncing in software.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{pac, gpio};
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let mut button = gpio::get_gpio(dp.SYS_PINCTRL.gpio_40()).into_enabled_input();
+    ///
+    /// assert_eq!(button.set_debounce(true), Err(gpio::Error::InvalidPad(40)));
+    /// ```
+    pub fn set_debounce(&mut self, _enable: bool) -> Result<()> {
+        Err(Error::InvalidPad(GPIO::pad()))
+    }
+
+    /// Sets the hardware debounce filter period of the input pad.
+    ///
+    /// **NOTE**: `JH7110` pads have no debounce filter, so this always returns
+    /// [Error::InvalidPad].
+    pub fn set_debounce_period(&mut self, _period: Duration) -> Result<()> {
+        Err(Error::InvalidPad(GPIO::pad()))
+    }
 
+    /// Gets whether the input pin is set.
+    ///
+    /// Returns [Error::InvalidPad] for pads without an input sync bit in the [`IOIRQ_15`] and
+    /// [`IOIRQ_16`] registers (GPIO 0-31 and 32-63 respectively), e.g. the SD card pads.
+    pub fn bit_is_set(&self) -> Result<bool> {
         let pad = GPIO::pad();
-        let pad_per_reg = u32::from(Pad::Gpio32);
 
-        if pad < pad_per_reg {
-            (pinctrl.ioirq_15().read().bits() >> pad) & 0x1 != 0
-        } else if pad < u32::from(Pad::Gpio63) {
-            let idx = pad.saturating_sub(pad_per_reg);
-            (pinctrl.ioirq_16().read().bits() >> idx) & 0x1 != 0
+        if is_aon_pad(pad) {
+            Ok(aon_read_input(pad))
         } else {
-            false
+            read_pad_input(pad)
         }
     }
 
+    /// Enables the interrupt for the input pin, firing on the [InterruptTrigger] condition.
+    ///
+    /// With the `rt` feature, use `register_handler` and `dispatch` to field the interrupt from
+    /// the PLIC GPIO interrupt handler. Otherwise, poll with [Gpio::pending].
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{pac, gpio};
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let mut button = gpio::get_gpio(dp.SYS_PINCTRL.gpio_5())
+    ///     .into_enabled_input()
+    ///     .into_input_pull_up();
+    ///
+    /// button.enable_interrupt(gpio::InterruptTrigger::FallingEdge);
+    ///
+    /// if button.pending() {
+    ///     button.clear_interrupt();
+    /// }
+    /// ```
+    pub fn enable_interrupt(&mut self, trigger: InterruptTrigger)
+    where
+        GPIO: MuxPad,
+    {
+        enable_irq_inner(GPIO::pad(), trigger);
+    }
+
+    /// Disables (masks) the interrupt for the input pin.
+    pub fn disable_interrupt(&mut self)
+    where
+        GPIO: MuxPad,
+    {
+        disable_irq_inner(GPIO::pad());
+    }
+
+    /// Clears the interrupt for the input pin.
+    ///
+    /// Idempotent, clearing an interrupt that is not pending has no effect.
+    pub fn clear_interrupt(&mut self)
+    where
+        GPIO: MuxPad,
+    {
+        clear_irqs(1 << GPIO::pad());
+    }
+
+    /// Gets whether the (unmasked) interrupt for the input pin is pending.
+    pub fn pending(&self) -> bool
+    where
+        GPIO: MuxPad,
+    {
+        pending_irqs() & (1 << GPIO::pad()) != 0
+    }
+
     /// Converts the [Gpio] into a high-impedance input.
     pub fn into_input_high_z(self) -> Gpio<'g, GPIO, Enabled, Input, HiZ> {
         self.periph.set_high_z();
@@ -515,6 +1154,36 @@ impl<'g, GPIO: GpioCfg, MODE> Gpio<'g, GPIO, Enabled, Input, MODE> {
     }
 }
 
+impl<'g, GPIO: GpioCfg, F: ?Sized> Gpio<'g, GPIO, Enabled, Nop, Alternate<F>> {
+    /// Builder function that sets the [DriveStrength] of the pad routed to the function signal.
+    pub fn with_drive_strength(self, drive_strength: DriveStrength) -> Self {
+        self.periph.set_drive_strength(drive_strength);
+        self
+    }
+
+    /// Builder function that sets the [Slew] rate of the pad routed to the function signal.
+    pub fn with_slew(self, slew: Slew) -> Self {
+        self.periph.set_slew(slew);
+        self
+    }
+
+    /// Builder function that sets the [SchmittTrigger] hysteresis of the pad routed to the
+    /// function signal.
+    pub fn with_schmitt_trigger(self, trigger: SchmittTrigger) -> Self {
+        self.periph.set_schmitt_trigger(trigger);
+        self
+    }
+
+    /// Builder function that applies the recommended [ElectricalProfile] settings to the pad
+    /// routed to the function signal.
+    ///
+    /// See [apply_electrical_preset] for details.
+    pub fn with_electrical_profile(self, profile: ElectricalProfile) -> Self {
+        apply_electrical_preset(self.periph, profile);
+        self
+    }
+}
+
 impl<'g, GPIO: GpioCfg, ENABLED, DIRECTION, MODE> ErrorType
     for Gpio<'g, GPIO, ENABLED, DIRECTION, MODE>
 {
@@ -531,6 +1200,121 @@ impl<'g, GPIO: GpioCfg> OutputPin for Gpio<'g, GPIO, Enabled, Output, Nop> {
         self.set_pin(true);
         Ok(())
     }
+
+    fn set_state(&mut self, state: PinState) -> Result<()> {
+        self.set_pin(state == PinState::High);
+        Ok(())
+    }
+}
+
+impl<'g, GPIO: GpioCfg> StatefulOutputPin for Gpio<'g, GPIO, Enabled, Output, Nop> {
+    fn is_set_high(&mut self) -> Result<bool> {
+        Ok(self.is_pin_set())
+    }
+
+    fn is_set_low(&mut self) -> Result<bool> {
+        Ok(!self.is_pin_set())
+    }
+
+    fn toggle(&mut self) -> Result<()> {
+        Self::toggle(self);
+        Ok(())
+    }
+}
+
+/// Output [Gpio] adapter with an [Infallible] error type.
+///
+/// Driving a pad routed through the GPIO muxer cannot fail, so the inherent `set_*` methods
+/// return `()` instead of a [`Result`](core::result::Result).
+///
+/// Example:
+///
+/// ```no_run
+/// use jh71xx_hal::{gpio, pac};
+///
+/// let dp = pac::Peripherals::take().unwrap();
+/// let mut led = gpio::get_gpio(dp.SYS_PINCTRL.gpio_0())
+///     .into_enabled_output()
+///     .into_infallible();
+///
+/// led.set_high();
+/// led.set_low();
+/// ```
+pub struct InfalliblePin<'g, GPIO: MuxPad> {
+    pin: Gpio<'g, GPIO, Enabled, Output, Nop>,
+}
+
+impl<'g, GPIO: MuxPad> InfalliblePin<'g, GPIO> {
+    /// Drives the pin high.
+    pub fn set_high(&mut self) {
+        self.pin.set_pin(true);
+    }
+
+    /// Drives the pin low.
+    pub fn set_low(&mut self) {
+        self.pin.set_pin(false);
+    }
+
+    /// Drives the pin to the provided [PinState].
+    pub fn set_state(&mut self, state: PinState) {
+        self.pin.set_pin(state == PinState::High);
+    }
+
+    /// Toggles the level the pin is driven to.
+    pub fn toggle(&mut self) {
+        self.pin.toggle();
+    }
+
+    /// Gets whether the pin is driven high.
+    pub fn is_set_high(&self) -> bool {
+        self.pin.is_pin_set()
+    }
+
+    /// Gets whether the pin is driven low.
+    pub fn is_set_low(&self) -> bool {
+        !self.pin.is_pin_set()
+    }
+
+    /// Converts the [InfalliblePin] back into the inner [Gpio].
+    pub fn into_inner(self) -> Gpio<'g, GPIO, Enabled, Output, Nop> {
+        self.pin
+    }
+}
+
+impl<'g, GPIO: MuxPad> ErrorType for InfalliblePin<'g, GPIO> {
+    type Error = Infallible;
+}
+
+impl<'g, GPIO: MuxPad> OutputPin for InfalliblePin<'g, GPIO> {
+    fn set_low(&mut self) -> core::result::Result<(), Infallible> {
+        InfalliblePin::set_low(self);
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> core::result::Result<(), Infallible> {
+        InfalliblePin::set_high(self);
+        Ok(())
+    }
+
+    fn set_state(&mut self, state: PinState) -> core::result::Result<(), Infallible> {
+        InfalliblePin::set_state(self, state);
+        Ok(())
+    }
+}
+
+impl<'g, GPIO: MuxPad> StatefulOutputPin for InfalliblePin<'g, GPIO> {
+    fn is_set_high(&mut self) -> core::result::Result<bool, Infallible> {
+        Ok(InfalliblePin::is_set_high(self))
+    }
+
+    fn is_set_low(&mut self) -> core::result::Result<bool, Infallible> {
+        Ok(InfalliblePin::is_set_low(self))
+    }
+
+    fn toggle(&mut self) -> core::result::Result<(), Infallible> {
+        InfalliblePin::toggle(self);
+        Ok(())
+    }
 }
 
 impl<'g, GPIO: GpioCfg, MODE> InputPin for Gpio<'g, GPIO, Enabled, Input, MODE> {
@@ -539,7 +1323,49 @@ impl<'g, GPIO: GpioCfg, MODE> InputPin for Gpio<'g, GPIO, Enabled, Input, MODE>
     }
 
     fn is_high(&mut self) -> Result<bool> {
-        Ok(self.bit_is_set())
+        self.bit_is_set()
+    }
+}
+
+impl<'g, GPIO: GpioCfg> OutputPin for Gpio<'g, GPIO, Enabled, Output, OpenDrain> {
+    fn set_low(&mut self) -> Result<()> {
+        self.set_pin(false);
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<()> {
+        self.set_pin(true);
+        Ok(())
+    }
+
+    fn set_state(&mut self, state: PinState) -> Result<()> {
+        self.set_pin(state == PinState::High);
+        Ok(())
+    }
+}
+
+impl<'g, GPIO: GpioCfg> StatefulOutputPin for Gpio<'g, GPIO, Enabled, Output, OpenDrain> {
+    fn is_set_high(&mut self) -> Result<bool> {
+        Ok(self.is_released())
+    }
+
+    fn is_set_low(&mut self) -> Result<bool> {
+        Ok(!self.is_released())
+    }
+
+    fn toggle(&mut self) -> Result<()> {
+        Self::toggle(self);
+        Ok(())
+    }
+}
+
+impl<'g, GPIO: GpioCfg> InputPin for Gpio<'g, GPIO, Enabled, Output, OpenDrain> {
+    fn is_low(&mut self) -> Result<bool> {
+        self.is_high().map(|v| !v)
+    }
+
+    fn is_high(&mut self) -> Result<bool> {
+        self.bit_is_set()
     }
 }
 
@@ -0,0 +1,351 @@
+//! Always-on (`AON`) GPIO pads
+//!
+//! The `AON_PINCTRL` block has its own muxer for the four `RGPIO` pads, which stay powered in
+//! the always-on domain (e.g. for wakeup signals, or the 32 kHz clock output).
+//!
+//! Pads can be armed as `PMU` wakeup sources with [AonGpio::arm_wakeup].
+
+use core::marker::PhantomData;
+
+use crate::pac::{AON_PINCTRL, PMU};
+
+use super::{
+    atomic, Disabled, DriveStrength, Gpio, GpioCfg, Nop, OutputConfig, OutputDrive, PowerOnStart,
+    SchmittTrigger, Slew, PADCFG_DS_MASK, PADCFG_DS_SHIFT, PADCFG_IE, PADCFG_PD, PADCFG_POS,
+    PADCFG_PU, PADCFG_SLEW, PADCFG_SMT,
+};
+
+/// Pad number of the first `AON` GPIO.
+///
+/// `AON` pads are numbered after the `SYS_PINCTRL` pads, so they can share the [GpioCfg] pad
+/// numbering without clashing.
+pub const AON_PAD_BASE: u32 = 128;
+/// Number of `AON` GPIO pads.
+pub const AON_GPIO_PADS: u32 = 4;
+
+/// GPIO pad for AON GPIO 0 (`RGPIO0`)
+pub const PAD_AON_GPIO0: u32 = AON_PAD_BASE;
+/// GPIO pad for AON GPIO 1 (`RGPIO1`)
+pub const PAD_AON_GPIO1: u32 = AON_PAD_BASE + 1;
+/// GPIO pad for AON GPIO 2 (`RGPIO2`)
+pub const PAD_AON_GPIO2: u32 = AON_PAD_BASE + 2;
+/// GPIO pad for AON GPIO 3 (`RGPIO3`)
+pub const PAD_AON_GPIO3: u32 = AON_PAD_BASE + 3;
+
+// Offsets of the `AON_PINCTRL` muxer registers, each pad selector occupies a byte lane
+const AON_DOEN_OFFSET: usize = 0x00;
+const AON_DOUT_OFFSET: usize = 0x04;
+const AON_GPI_OFFSET: usize = 0x08;
+const AON_GPIOIN_OFFSET: usize = 0x2c;
+// Widths of the `AON` muxer selector fields
+const AON_DOEN_MASK: u32 = 0x7;
+const AON_DOUT_MASK: u32 = 0xf;
+const AON_GPI_MASK: u32 = 0xf;
+// Offset of `AON` pad numbers in GPI selector values, `0` and `1` tie the input low and high
+const AON_GPI_PAD_OFFSET: u32 = 2;
+
+// Offset of the `PMU` hardware event turn-on mask register, a set bit masks the event
+const PMU_HW_EVENT_ON_MASK_OFFSET: usize = 0x04;
+// Bit of the `GPIO_WAKEUP_0` event in the `PMU` event registers, followed by wakeup 1-3
+const PMU_EVENT_GPIO_WAKEUP_SHIFT: u32 = 3;
+
+// Offset of the `RGPIO0` pad configuration register, pads are 4 bytes apart
+const AON_PADCFG_OFFSET: usize = 0x34;
+
+/// Gets whether `pad` is an `AON` GPIO pad.
+pub const fn is_aon_pad(pad: u32) -> bool {
+    pad >= AON_PAD_BASE && pad < AON_PAD_BASE + AON_GPIO_PADS
+}
+
+/// Represents the `PMU` GPIO wakeup event signals an `AON` pad can be routed to.
+///
+/// Any of the four `AON` pads (`RGPIO0-3`) can drive any of the wakeup signals, but each signal
+/// is driven by a single pad at a time.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum AonWakeup {
+    /// `U0_PMU_IO_EVENT_STUB_GPIO_WAKEUP_0`
+    #[default]
+    Wakeup0 = 0,
+    /// `U0_PMU_IO_EVENT_STUB_GPIO_WAKEUP_1`
+    Wakeup1 = 1,
+    /// `U0_PMU_IO_EVENT_STUB_GPIO_WAKEUP_2`
+    Wakeup2 = 2,
+    /// `U0_PMU_IO_EVENT_STUB_GPIO_WAKEUP_3`
+    Wakeup3 = 3,
+}
+
+impl AonWakeup {
+    /// Gets the [AonGpiFunction](super::AonGpiFunction) index of the wakeup signal.
+    pub const fn function(&self) -> u8 {
+        *self as u8
+    }
+
+    // Gets the bit of the wakeup event in the `PMU` event registers.
+    const fn pmu_event_bit(&self) -> u32 {
+        1 << (PMU_EVENT_GPIO_WAKEUP_SHIFT + *self as u32)
+    }
+}
+
+/// Represents the pad configuration register of `AON` GPIO `N`.
+pub struct AonGpio<const N: u32> {
+    _reg: PhantomData<*const ()>,
+}
+
+/// AON GPIO 0 (`RGPIO0`) pad.
+pub type AonGpio0 = AonGpio<0>;
+/// AON GPIO 1 (`RGPIO1`) pad.
+pub type AonGpio1 = AonGpio<1>;
+/// AON GPIO 2 (`RGPIO2`) pad.
+pub type AonGpio2 = AonGpio<2>;
+/// AON GPIO 3 (`RGPIO3`) pad.
+pub type AonGpio3 = AonGpio<3>;
+
+impl<const N: u32> AonGpio<N> {
+    const fn new() -> Self {
+        Self { _reg: PhantomData }
+    }
+
+    fn padcfg() -> *mut u32 {
+        (AON_PINCTRL::ptr() as usize + AON_PADCFG_OFFSET + N as usize * 4) as *mut u32
+    }
+
+    fn read(&self) -> u32 {
+        // SAFETY: the pad configuration register is within the `AON_PINCTRL` register space.
+        unsafe { Self::padcfg().read_volatile() }
+    }
+
+    fn modify(&self, f: impl FnOnce(u32) -> u32) {
+        // SAFETY: the pad configuration register is within the `AON_PINCTRL` register space,
+        // and is only used by this pad.
+        unsafe { Self::padcfg().write_volatile(f(self.read())) }
+    }
+
+    fn set_bits(&self, bits: u32, set: bool) {
+        self.modify(|v| if set { v | bits } else { v & !bits });
+    }
+
+    /// Arms the pad as a `PMU` wakeup source.
+    ///
+    /// Enables the pad input, routes it to the `trigger` wakeup signal, and unmasks the matching
+    /// `PMU` hardware event. The event is active-high, so configure the pad pull (e.g. pull-down
+    /// for a button to `VDD`) to keep the line idle low.
+    ///
+    /// Arming another pad with the same `trigger` replaces this pad as the wakeup source.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{gpio, pac};
+    /// use gpio::GpioCfg;
+    ///
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let aon = gpio::AonPins::new(dp.AON_PINCTRL);
+    ///
+    /// // Wake up on a button press on `RGPIO1`
+    /// aon.gpio1.set_pull_down();
+    /// aon.gpio1.arm_wakeup(gpio::AonWakeup::Wakeup0);
+    /// ```
+    pub fn arm_wakeup(&self, trigger: AonWakeup) {
+        self.input_enable(true);
+        write_aon_lane(
+            AON_GPI_OFFSET,
+            trigger.function() as usize,
+            AON_GPI_MASK,
+            N + AON_GPI_PAD_OFFSET,
+        );
+        set_pmu_event_mask(trigger.pmu_event_bit(), false);
+    }
+
+    /// Disarms the `trigger` wakeup signal.
+    ///
+    /// Masks the `PMU` hardware event, and ties the wakeup signal low. The pad configuration is
+    /// left unchanged.
+    pub fn disarm_wakeup(&self, trigger: AonWakeup) {
+        set_pmu_event_mask(trigger.pmu_event_bit(), true);
+        write_aon_lane(AON_GPI_OFFSET, trigger.function() as usize, AON_GPI_MASK, 0);
+    }
+}
+
+impl<const N: u32> GpioCfg for AonGpio<N> {
+    fn pad() -> u32 {
+        AON_PAD_BASE + N
+    }
+
+    fn is_input_enabled(&self) -> bool {
+        self.read() & PADCFG_IE != 0
+    }
+
+    fn input_enable(&self, enable: bool) {
+        self.set_bits(PADCFG_IE, enable);
+    }
+
+    fn drive_strength(&self) -> DriveStrength {
+        (((self.read() & PADCFG_DS_MASK) >> PADCFG_DS_SHIFT) as u8).into()
+    }
+
+    fn set_drive_strength(&self, drive_strength: DriveStrength) {
+        let ds = (u8::from(drive_strength) as u32) << PADCFG_DS_SHIFT;
+        self.modify(|v| (v & !PADCFG_DS_MASK) | ds);
+    }
+
+    fn is_high_z(&self) -> bool {
+        self.read() & (PADCFG_PU | PADCFG_PD) == 0
+    }
+
+    fn is_pull_up(&self) -> bool {
+        self.read() & PADCFG_PU != 0
+    }
+
+    fn is_pull_down(&self) -> bool {
+        self.read() & PADCFG_PD != 0
+    }
+
+    fn set_high_z(&self) {
+        self.modify(|v| v & !(PADCFG_PU | PADCFG_PD));
+    }
+
+    fn set_pull_down(&self) {
+        self.modify(|v| (v & !PADCFG_PU) | PADCFG_PD);
+    }
+
+    fn set_pull_up(&self) {
+        self.modify(|v| (v & !PADCFG_PD) | PADCFG_PU);
+    }
+
+    fn slew(&self) -> Slew {
+        (self.read() & PADCFG_SLEW != 0).into()
+    }
+
+    fn set_slew(&self, slew: Slew) {
+        self.set_bits(PADCFG_SLEW, slew == Slew::Fast);
+    }
+
+    fn schmitt_trigger(&self) -> SchmittTrigger {
+        (self.read() & PADCFG_SMT != 0).into()
+    }
+
+    fn set_schmitt_trigger(&self, trigger: SchmittTrigger) {
+        self.set_bits(PADCFG_SMT, trigger == SchmittTrigger::Enable);
+    }
+
+    fn power_on_start(&self) -> PowerOnStart {
+        (self.read() & PADCFG_POS != 0).into()
+    }
+
+    fn set_power_on_start(&self, pos: PowerOnStart) {
+        self.set_bits(PADCFG_POS, pos.into());
+    }
+}
+
+/// Marker trait for GPIO pads routed through the `AON_PINCTRL` GPIO muxer.
+pub trait AonPad: GpioCfg {}
+
+impl<const N: u32> AonPad for AonGpio<N> {}
+
+/// The `AON` GPIO pads, split from the `AON_PINCTRL` peripheral.
+pub struct AonPins {
+    pub gpio0: AonGpio0,
+    pub gpio1: AonGpio1,
+    pub gpio2: AonGpio2,
+    pub gpio3: AonGpio3,
+}
+
+impl AonPins {
+    /// Creates the [AonPins] from the `AON_PINCTRL` peripheral.
+    pub fn new(_pinctrl: AON_PINCTRL) -> Self {
+        Self {
+            gpio0: AonGpio::new(),
+            gpio1: AonGpio::new(),
+            gpio2: AonGpio::new(),
+            gpio3: AonGpio::new(),
+        }
+    }
+}
+
+/// Creates a new [Gpio] for an `AON` pad.
+///
+/// Example:
+///
+/// ```no_run
+/// use jh71xx_hal::{gpio, pac};
+/// use embedded_hal::digital::OutputPin;
+///
+/// let dp = pac::Peripherals::take().unwrap();
+/// let aon = gpio::AonPins::new(dp.AON_PINCTRL);
+///
+/// let mut wake = gpio::get_aon_gpio(&aon.gpio2).into_enabled_output();
+/// wake.set_high().unwrap();
+/// ```
+pub fn get_aon_gpio<GPIO: AonPad>(periph: &GPIO) -> Gpio<GPIO, Disabled, Nop, Nop> {
+    super::get_gpio(periph)
+}
+
+// Gets the byte lane of `AON` `pad`.
+const fn aon_idx(pad: u32) -> usize {
+    (pad - AON_PAD_BASE) as usize
+}
+
+fn write_aon_mux(offset: usize, pad: u32, mask: u32, val: u32) {
+    write_aon_lane(offset, aon_idx(pad), mask, val);
+}
+
+// Writes the selector `val` into byte lane `idx` of the `AON` muxer register array at `offset`.
+fn write_aon_lane(offset: usize, idx: usize, mask: u32, val: u32) {
+    let reg = (AON_PINCTRL::ptr() as usize + offset + (idx / 4) * 4) as *mut u32;
+    let shift = (idx % 4) * 8;
+
+    // SAFETY: the muxer registers are within the `AON_PINCTRL` register space, and only the
+    // selector field for `idx` is modified.
+    atomic(|| unsafe {
+        let cur = reg.read_volatile() & !(mask << shift);
+        reg.write_volatile(cur | ((val & mask) << shift));
+    });
+}
+
+// Masks, or unmasks the `PMU` hardware turn-on `event` bits.
+fn set_pmu_event_mask(event: u32, masked: bool) {
+    let reg = (PMU::ptr() as usize + PMU_HW_EVENT_ON_MASK_OFFSET) as *mut u32;
+
+    // SAFETY: the event mask register is within the `PMU` register space, and only the `event`
+    // bits are modified.
+    atomic(|| unsafe {
+        let cur = reg.read_volatile();
+        reg.write_volatile(if masked { cur | event } else { cur & !event });
+    });
+}
+
+fn read_aon_mux(offset: usize, pad: u32, mask: u32) -> u32 {
+    let reg = (AON_PINCTRL::ptr() as usize + offset) as *const u32;
+
+    // SAFETY: the muxer registers are within the `AON_PINCTRL` register space.
+    (unsafe { reg.read_volatile() } >> (aon_idx(pad) * 8)) & mask
+}
+
+// Selects the `DOEN` output enable of `AON` `pad`.
+pub(crate) fn aon_config_output(pad: u32, config: OutputConfig) {
+    write_aon_mux(AON_DOEN_OFFSET, pad, AON_DOEN_MASK, u8::from(config) as u32);
+}
+
+// Selects the `DOUT` output level of `AON` `pad`.
+pub(crate) fn aon_drive_output(pad: u32, drive: OutputDrive) {
+    write_aon_mux(AON_DOUT_OFFSET, pad, AON_DOUT_MASK, u8::from(drive) as u32);
+}
+
+// Gets the `DOUT` selector of `AON` `pad`.
+pub(crate) fn aon_dout(pad: u32) -> u8 {
+    read_aon_mux(AON_DOUT_OFFSET, pad, AON_DOUT_MASK) as u8
+}
+
+// Gets the `DOEN` selector of `AON` `pad`.
+pub(crate) fn aon_doen(pad: u32) -> u8 {
+    read_aon_mux(AON_DOEN_OFFSET, pad, AON_DOEN_MASK) as u8
+}
+
+// Reads the input level of `AON` `pad`.
+pub(crate) fn aon_read_input(pad: u32) -> bool {
+    let reg = (AON_PINCTRL::ptr() as usize + AON_GPIOIN_OFFSET) as *const u32;
+
+    // SAFETY: `GPIOIN` is read-only, and within the `AON_PINCTRL` register space.
+    (unsafe { reg.read_volatile() } >> aon_idx(pad)) & 0x1 != 0
+}
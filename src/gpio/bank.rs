@@ -0,0 +1,112 @@
+use crate::pac::sys_pinctrl::*;
+use crate::pac::SYS_PINCTRL;
+
+use super::{Disabled, Gpio, Nop};
+
+macro_rules! gpio_bank {
+    ($($field:ident: $reg:ident, $gpio:ident,)+) => {
+        /// Represents all GPIO pads routed through the `SYS_PINCTRL` GPIO muxer (GPIO 0-63).
+        ///
+        /// Each pad is available as a disabled [Gpio].
+        ///
+        /// Example:
+        ///
+        /// ```no_run
+        /// use jh71xx_hal::{gpio, pac};
+        ///
+        /// let dp = pac::Peripherals::take().unwrap();
+        /// let bank = gpio::GpioBank::new(dp.SYS_PINCTRL);
+        ///
+        /// let _led = bank.gpio0.into_enabled_output();
+        /// let _button = bank.gpio1.into_enabled_input();
+        /// ```
+        pub struct GpioBank {
+            $(
+                #[doc = concat!("GPIO pad `", stringify!($gpio), "`.")]
+                pub $field: Gpio<'static, $gpio, Disabled, Nop, Nop>,
+            )+
+        }
+
+        impl GpioBank {
+            /// Creates a new [GpioBank] from the `SYS_PINCTRL` peripheral.
+            ///
+            /// Takes ownership of the peripheral, so each pad can only be handed out once.
+            pub fn new(_pinctrl: SYS_PINCTRL) -> Self {
+                // SAFETY: ownership of the `SYS_PINCTRL` peripheral is consumed, so no other
+                // references to the pad configuration registers can be created safely.
+                let pinctrl = unsafe { &*SYS_PINCTRL::ptr() };
+
+                Self {
+                    $($field: super::get_gpio(pinctrl.$reg()),)+
+                }
+            }
+        }
+    };
+}
+
+gpio_bank! {
+    gpio0: gpio_0, GPIO_0,
+    gpio1: gpio_1, GPIO_1,
+    gpio2: gpio_2, GPIO_2,
+    gpio3: gpio_3, GPIO_3,
+    gpio4: gpio_4, GPIO_4,
+    gpio5: gpio_5, GPIO_5,
+    gpio6: gpio_6, GPIO_6,
+    gpio7: gpio_7, GPIO_7,
+    gpio8: gpio_8, GPIO_8,
+    gpio9: gpio_9, GPIO_9,
+    gpio10: gpio_10, GPIO_10,
+    gpio11: gpio_11, GPIO_11,
+    gpio12: gpio_12, GPIO_12,
+    gpio13: gpio_13, GPIO_13,
+    gpio14: gpio_14, GPIO_14,
+    gpio15: gpio_15, GPIO_15,
+    gpio16: gpio_16, GPIO_16,
+    gpio17: gpio_17, GPIO_17,
+    gpio18: gpio_18, GPIO_18,
+    gpio19: gpio_19, GPIO_19,
+    gpio20: gpio_20, GPIO_20,
+    gpio21: gpio_21, GPIO_21,
+    gpio22: gpio_22, GPIO_22,
+    gpio23: gpio_23, GPIO_23,
+    gpio24: gpio_24, GPIO_24,
+    gpio25: gpio_25, GPIO_25,
+    gpio26: gpio_26, GPIO_26,
+    gpio27: gpio_27, GPIO_27,
+    gpio28: gpio_28, GPIO_28,
+    gpio29: gpio_29, GPIO_29,
+    gpio30: gpio_30, GPIO_30,
+    gpio31: gpio_31, GPIO_31,
+    gpio32: gpio_32, GPIO_32,
+    gpio33: gpio_33, GPIO_33,
+    gpio34: gpio_34, GPIO_34,
+    gpio35: gpio_35, GPIO_35,
+    gpio36: gpio_36, GPIO_36,
+    gpio37: gpio_37, GPIO_37,
+    gpio38: gpio_38, GPIO_38,
+    gpio39: gpio_39, GPIO_39,
+    gpio40: gpio_40, GPIO_40,
+    gpio41: gpio_41, GPIO_41,
+    gpio42: gpio_42, GPIO_42,
+    gpio43: gpio_43, GPIO_43,
+    gpio44: gpio_44, GPIO_44,
+    gpio45: gpio_45, GPIO_45,
+    gpio46: gpio_46, GPIO_46,
+    gpio47: gpio_47, GPIO_47,
+    gpio48: gpio_48, GPIO_48,
+    gpio49: gpio_49, GPIO_49,
+    gpio50: gpio_50, GPIO_50,
+    gpio51: gpio_51, GPIO_51,
+    gpio52: gpio_52, GPIO_52,
+    gpio53: gpio_53, GPIO_53,
+    gpio54: gpio_54, GPIO_54,
+    gpio55: gpio_55, GPIO_55,
+    gpio56: gpio_56, GPIO_56,
+    gpio57: gpio_57, GPIO_57,
+    gpio58: gpio_58, GPIO_58,
+    gpio59: gpio_59, GPIO_59,
+    gpio60: gpio_60, GPIO_60,
+    gpio61: gpio_61, GPIO_61,
+    gpio62: gpio_62, GPIO_62,
+    gpio63: gpio_63, GPIO_63,
+}
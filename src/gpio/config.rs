@@ -72,6 +72,19 @@ impl From<Slew> for u8 {
     }
 }
 
+/// Configuration options for the GPIO pull resistors.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Pull {
+    /// No pull resistor (high-impedance)
+    #[default]
+    None = 0,
+    /// Enables the pull-up resistor
+    Up = 1,
+    /// Enables the pull-down resistor
+    Down = 2,
+}
+
 /// Configuration options for the GPIO Schmitt trigger hysteresis.
 #[repr(u8)]
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -109,6 +122,58 @@ impl From<SchmittTrigger> for u8 {
     }
 }
 
+/// Recommended pad electrical settings for peripheral signals routed through the GPIO muxer.
+///
+/// Applied with [apply_electrical_preset](super::apply_electrical_preset), or the
+/// [Gpio::with_electrical_profile](super::Gpio::with_electrical_profile) builder.
+///
+/// | Profile       | [DriveStrength] | [Slew] | [SchmittTrigger] |
+/// |---------------|-----------------|--------|------------------|
+/// | `SpiClock`    | 8 mA            | Fast   | Disable          |
+/// | `SpiOutput`   | 4 mA            | Fast   | Disable          |
+/// | `SpiInput`    | 2 mA            | Slow   | Enable           |
+/// | `I2c`         | 4 mA            | Slow   | Enable           |
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ElectricalProfile {
+    /// SPI clock (`SCLK`) output, sharp edges for high clock rates.
+    SpiClock,
+    /// SPI data (`MOSI`), and chip-select outputs.
+    SpiOutput,
+    /// SPI data (`MISO`) input, hysteresis to reject ringing on the line.
+    SpiInput,
+    /// I2C open-drain `SCL`/`SDA`, slow edges to limit ringing, and hysteresis on the slow
+    /// pulled-up rising edges.
+    I2c,
+}
+
+impl ElectricalProfile {
+    /// Gets the [DriveStrength] of the [ElectricalProfile].
+    pub const fn drive_strength(&self) -> DriveStrength {
+        match self {
+            Self::SpiClock => DriveStrength::Eight,
+            Self::SpiOutput | Self::I2c => DriveStrength::Four,
+            Self::SpiInput => DriveStrength::Two,
+        }
+    }
+
+    /// Gets the [Slew] rate control of the [ElectricalProfile].
+    pub const fn slew(&self) -> Slew {
+        match self {
+            Self::SpiClock | Self::SpiOutput => Slew::Fast,
+            Self::SpiInput | Self::I2c => Slew::Slow,
+        }
+    }
+
+    /// Gets the [SchmittTrigger] configuration of the [ElectricalProfile].
+    pub const fn schmitt_trigger(&self) -> SchmittTrigger {
+        match self {
+            Self::SpiClock | Self::SpiOutput => SchmittTrigger::Disable,
+            Self::SpiInput | Self::I2c => SchmittTrigger::Enable,
+        }
+    }
+}
+
 /// Configuration options for the GPIO Power-on-Start feature.
 pub enum PowerOnStart {
     /// No active pull-down on loss of core power.
@@ -144,6 +209,16 @@ impl From<PowerOnStart> for u8 {
     }
 }
 
+// Pad configuration register bits, shared by the `SYS_PINCTRL`, and `AON_PINCTRL` pads
+pub(crate) const PADCFG_IE: u32 = 1 << 0;
+pub(crate) const PADCFG_DS_SHIFT: u32 = 1;
+pub(crate) const PADCFG_DS_MASK: u32 = 0b11 << PADCFG_DS_SHIFT;
+pub(crate) const PADCFG_PU: u32 = 1 << 3;
+pub(crate) const PADCFG_PD: u32 = 1 << 4;
+pub(crate) const PADCFG_SLEW: u32 = 1 << 5;
+pub(crate) const PADCFG_SMT: u32 = 1 << 6;
+pub(crate) const PADCFG_POS: u32 = 1 << 7;
+
 /// Configuration trait for GPIO peripheral registers.
 pub trait GpioCfg {
     /// Gets the pad number for the GPIO.
@@ -189,7 +264,17 @@ pub trait GpioCfg {
     fn set_power_on_start(&self, pos: PowerOnStart);
 }
 
+/// Marker trait for GPIO pads routed through the `SYS_PINCTRL` GPIO muxer (GPIO 0-63).
+///
+/// Writes to the muxer registers cannot fail for these pads.
+pub trait MuxPad: GpioCfg {}
+
 macro_rules! gpio_cfg {
+    ($gpio:ident, $pad:expr, mux) => {
+        gpio_cfg!($gpio, $pad);
+
+        impl $crate::gpio::MuxPad for $gpio {}
+    };
     ($gpio:ident, $pad:expr) => {
         impl $crate::gpio::GpioCfg for $gpio {
             fn pad() -> u32 {
@@ -288,70 +373,70 @@ macro_rules! gpio_cfg {
     };
 }
 
-gpio_cfg!(GPIO_0, 0);
-gpio_cfg!(GPIO_1, 1);
-gpio_cfg!(GPIO_2, 2);
-gpio_cfg!(GPIO_3, 3);
-gpio_cfg!(GPIO_4, 4);
-gpio_cfg!(GPIO_5, 5);
-gpio_cfg!(GPIO_6, 6);
-gpio_cfg!(GPIO_7, 7);
-gpio_cfg!(GPIO_8, 8);
-gpio_cfg!(GPIO_9, 9);
-gpio_cfg!(GPIO_10, 10);
-gpio_cfg!(GPIO_11, 11);
-gpio_cfg!(GPIO_12, 12);
-gpio_cfg!(GPIO_13, 13);
-gpio_cfg!(GPIO_14, 14);
-gpio_cfg!(GPIO_15, 15);
-gpio_cfg!(GPIO_16, 16);
-gpio_cfg!(GPIO_17, 17);
-gpio_cfg!(GPIO_18, 18);
-gpio_cfg!(GPIO_19, 19);
-gpio_cfg!(GPIO_20, 20);
-gpio_cfg!(GPIO_21, 21);
-gpio_cfg!(GPIO_22, 22);
-gpio_cfg!(GPIO_23, 23);
-gpio_cfg!(GPIO_24, 24);
-gpio_cfg!(GPIO_25, 25);
-gpio_cfg!(GPIO_26, 26);
-gpio_cfg!(GPIO_27, 27);
-gpio_cfg!(GPIO_28, 28);
-gpio_cfg!(GPIO_29, 29);
-gpio_cfg!(GPIO_30, 30);
-gpio_cfg!(GPIO_31, 31);
-gpio_cfg!(GPIO_32, 32);
-gpio_cfg!(GPIO_33, 33);
-gpio_cfg!(GPIO_34, 34);
-gpio_cfg!(GPIO_35, 35);
-gpio_cfg!(GPIO_36, 36);
-gpio_cfg!(GPIO_37, 37);
-gpio_cfg!(GPIO_38, 38);
-gpio_cfg!(GPIO_39, 39);
-gpio_cfg!(GPIO_40, 40);
-gpio_cfg!(GPIO_41, 41);
-gpio_cfg!(GPIO_42, 42);
-gpio_cfg!(GPIO_43, 43);
-gpio_cfg!(GPIO_44, 44);
-gpio_cfg!(GPIO_45, 45);
-gpio_cfg!(GPIO_46, 46);
-gpio_cfg!(GPIO_47, 47);
-gpio_cfg!(GPIO_48, 48);
-gpio_cfg!(GPIO_49, 49);
-gpio_cfg!(GPIO_50, 50);
-gpio_cfg!(GPIO_51, 51);
-gpio_cfg!(GPIO_52, 52);
-gpio_cfg!(GPIO_53, 53);
-gpio_cfg!(GPIO_54, 54);
-gpio_cfg!(GPIO_55, 55);
-gpio_cfg!(GPIO_56, 56);
-gpio_cfg!(GPIO_57, 57);
-gpio_cfg!(GPIO_58, 58);
-gpio_cfg!(GPIO_59, 59);
-gpio_cfg!(GPIO_60, 60);
-gpio_cfg!(GPIO_61, 61);
-gpio_cfg!(GPIO_62, 62);
-gpio_cfg!(GPIO_63, 63);
+gpio_cfg!(GPIO_0, 0, mux);
+gpio_cfg!(GPIO_1, 1, mux);
+gpio_cfg!(GPIO_2, 2, mux);
+gpio_cfg!(GPIO_3, 3, mux);
+gpio_cfg!(GPIO_4, 4, mux);
+gpio_cfg!(GPIO_5, 5, mux);
+gpio_cfg!(GPIO_6, 6, mux);
+gpio_cfg!(GPIO_7, 7, mux);
+gpio_cfg!(GPIO_8, 8, mux);
+gpio_cfg!(GPIO_9, 9, mux);
+gpio_cfg!(GPIO_10, 10, mux);
+gpio_cfg!(GPIO_11, 11, mux);
+gpio_cfg!(GPIO_12, 12, mux);
+gpio_cfg!(GPIO_13, 13, mux);
+gpio_cfg!(GPIO_14, 14, mux);
+gpio_cfg!(GPIO_15, 15, mux);
+gpio_cfg!(GPIO_16, 16, mux);
+gpio_cfg!(GPIO_17, 17, mux);
+gpio_cfg!(GPIO_18, 18, mux);
+gpio_cfg!(GPIO_19, 19, mux);
+gpio_cfg!(GPIO_20, 20, mux);
+gpio_cfg!(GPIO_21, 21, mux);
+gpio_cfg!(GPIO_22, 22, mux);
+gpio_cfg!(GPIO_23, 23, mux);
+gpio_cfg!(GPIO_24, 24, mux);
+gpio_cfg!(GPIO_25, 25, mux);
+gpio_cfg!(GPIO_26, 26, mux);
+gpio_cfg!(GPIO_27, 27, mux);
+gpio_cfg!(GPIO_28, 28, mux);
+gpio_cfg!(GPIO_29, 29, mux);
+gpio_cfg!(GPIO_30, 30, mux);
+gpio_cfg!(GPIO_31, 31, mux);
+gpio_cfg!(GPIO_32, 32, mux);
+gpio_cfg!(GPIO_33, 33, mux);
+gpio_cfg!(GPIO_34, 34, mux);
+gpio_cfg!(GPIO_35, 35, mux);
+gpio_cfg!(GPIO_36, 36, mux);
+gpio_cfg!(GPIO_37, 37, mux);
+gpio_cfg!(GPIO_38, 38, mux);
+gpio_cfg!(GPIO_39, 39, mux);
+gpio_cfg!(GPIO_40, 40, mux);
+gpio_cfg!(GPIO_41, 41, mux);
+gpio_cfg!(GPIO_42, 42, mux);
+gpio_cfg!(GPIO_43, 43, mux);
+gpio_cfg!(GPIO_44, 44, mux);
+gpio_cfg!(GPIO_45, 45, mux);
+gpio_cfg!(GPIO_46, 46, mux);
+gpio_cfg!(GPIO_47, 47, mux);
+gpio_cfg!(GPIO_48, 48, mux);
+gpio_cfg!(GPIO_49, 49, mux);
+gpio_cfg!(GPIO_50, 50, mux);
+gpio_cfg!(GPIO_51, 51, mux);
+gpio_cfg!(GPIO_52, 52, mux);
+gpio_cfg!(GPIO_53, 53, mux);
+gpio_cfg!(GPIO_54, 54, mux);
+gpio_cfg!(GPIO_55, 55, mux);
+gpio_cfg!(GPIO_56, 56, mux);
+gpio_cfg!(GPIO_57, 57, mux);
+gpio_cfg!(GPIO_58, 58, mux);
+gpio_cfg!(GPIO_59, 59, mux);
+gpio_cfg!(GPIO_60, 60, mux);
+gpio_cfg!(GPIO_61, 61, mux);
+gpio_cfg!(GPIO_62, 62, mux);
+gpio_cfg!(GPIO_63, 63, mux);
 gpio_cfg!(SD0_CLK, 64);
 gpio_cfg!(SD0_CMD, 65);
 gpio_cfg!(SD0_DATA_0, 66);
@@ -0,0 +1,178 @@
+use core::convert::Infallible;
+use core::marker::PhantomData;
+
+use embedded_hal::digital::{ErrorType, InputPin, OutputPin, PinState, StatefulOutputPin};
+
+use super::{
+    atomic, read_fmux, read_pad_input, write_fmux, Enabled, Error, Gpio, Input, MuxPad, Nop,
+    Output, OutputDrive, GPO_DOUT_MASK, GPO_DOUT_OFFSET,
+};
+
+/// Output GPIO with the pad type erased, dispatching on the pad number at runtime.
+///
+/// Allows storing output pins for different pads in the same collection.
+///
+/// Example:
+///
+/// ```no_run
+/// use jh71xx_hal::{gpio, pac};
+///
+/// let dp = pac::Peripherals::take().unwrap();
+/// let pinctrl = &dp.SYS_PINCTRL;
+///
+/// let mut leds = [
+///     gpio::get_gpio(pinctrl.gpio_40()).into_enabled_output().into_dyn(),
+///     gpio::get_gpio(pinctrl.gpio_41()).into_enabled_output().into_dyn(),
+///     gpio::get_gpio(pinctrl.gpio_44()).into_enabled_output().into_dyn(),
+/// ];
+///
+/// for led in leds.iter_mut() {
+///     led.set_high();
+/// }
+/// ```
+pub struct DynOutputPin<'g> {
+    pad: u32,
+    _pin: PhantomData<&'g ()>,
+}
+
+impl<'g> DynOutputPin<'g> {
+    /// Gets the GPIO pad number.
+    pub const fn pad(&self) -> u32 {
+        self.pad
+    }
+
+    /// Drives the pin high.
+    pub fn set_high(&mut self) {
+        self.drive_output(OutputDrive::High);
+    }
+
+    /// Drives the pin low.
+    pub fn set_low(&mut self) {
+        self.drive_output(OutputDrive::Low);
+    }
+
+    /// Drives the pin to the provided [PinState].
+    pub fn set_state(&mut self, state: PinState) {
+        self.drive_output((state == PinState::High).into());
+    }
+
+    /// Toggles the level the pin is driven to.
+    pub fn toggle(&mut self) {
+        atomic(|| self.drive_output((!DynOutputPin::is_set_high(self)).into()));
+    }
+
+    /// Gets whether the pin is driven high.
+    pub fn is_set_high(&self) -> bool {
+        read_fmux(GPO_DOUT_OFFSET, self.pad as usize, GPO_DOUT_MASK) == OutputDrive::High as u32
+    }
+
+    /// Gets whether the pin is driven low.
+    pub fn is_set_low(&self) -> bool {
+        !self.is_set_high()
+    }
+
+    fn drive_output(&mut self, drive: OutputDrive) {
+        write_fmux(
+            GPO_DOUT_OFFSET,
+            self.pad as usize,
+            GPO_DOUT_MASK,
+            u8::from(drive) as u32,
+        );
+    }
+}
+
+impl<'g> ErrorType for DynOutputPin<'g> {
+    type Error = Infallible;
+}
+
+impl<'g> OutputPin for DynOutputPin<'g> {
+    fn set_low(&mut self) -> Result<(), Infallible> {
+        DynOutputPin::set_low(self);
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Infallible> {
+        DynOutputPin::set_high(self);
+        Ok(())
+    }
+
+    fn set_state(&mut self, state: PinState) -> Result<(), Infallible> {
+        DynOutputPin::set_state(self, state);
+        Ok(())
+    }
+}
+
+impl<'g> StatefulOutputPin for DynOutputPin<'g> {
+    fn is_set_high(&mut self) -> Result<bool, Infallible> {
+        Ok(DynOutputPin::is_set_high(self))
+    }
+
+    fn is_set_low(&mut self) -> Result<bool, Infallible> {
+        Ok(DynOutputPin::is_set_low(self))
+    }
+
+    fn toggle(&mut self) -> Result<(), Infallible> {
+        DynOutputPin::toggle(self);
+        Ok(())
+    }
+}
+
+/// Input GPIO with the pad type erased, dispatching on the pad number at runtime.
+///
+/// Allows storing input pins for different pads in the same collection.
+pub struct DynInputPin<'g> {
+    pad: u32,
+    _pin: PhantomData<&'g ()>,
+}
+
+impl<'g> DynInputPin<'g> {
+    /// Gets the GPIO pad number.
+    pub const fn pad(&self) -> u32 {
+        self.pad
+    }
+
+    /// Gets whether the input pin is set.
+    ///
+    /// Returns [Error::InvalidPad] for pads without an input sync bit.
+    pub fn bit_is_set(&self) -> Result<bool, Error> {
+        read_pad_input(self.pad)
+    }
+}
+
+impl<'g> ErrorType for DynInputPin<'g> {
+    type Error = Error;
+}
+
+impl<'g> InputPin for DynInputPin<'g> {
+    fn is_high(&mut self) -> Result<bool, Error> {
+        self.bit_is_set()
+    }
+
+    fn is_low(&mut self) -> Result<bool, Error> {
+        self.bit_is_set().map(|v| !v)
+    }
+}
+
+impl<'g, GPIO: MuxPad> Gpio<'g, GPIO, Enabled, Output, Nop> {
+    /// Converts the [Gpio] into a [DynOutputPin], erasing the pad type.
+    ///
+    /// Only available for pads routed through the GPIO muxer.
+    pub fn into_dyn(self) -> DynOutputPin<'g> {
+        DynOutputPin {
+            pad: GPIO::pad(),
+            _pin: PhantomData,
+        }
+    }
+}
+
+impl<'g, GPIO: MuxPad, MODE> Gpio<'g, GPIO, Enabled, Input, MODE> {
+    /// Converts the [Gpio] into a [DynInputPin], erasing the pad type.
+    ///
+    /// The pull configuration is kept. Only available for pads routed through the GPIO muxer.
+    pub fn into_dyn(self) -> DynInputPin<'g> {
+        DynInputPin {
+            pad: GPIO::pad(),
+            _pin: PhantomData,
+        }
+    }
+}
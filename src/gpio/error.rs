@@ -3,6 +3,8 @@ use core::fmt;
 use embedded_hal::digital::Error as GpioError;
 pub use embedded_hal::digital::ErrorKind;
 
+use super::GpioGroup;
+
 /// Convenience [`Result`](core::result::Result) alias for JH71xx GPIO module.
 pub type Result<T> = core::result::Result<T, Error>;
 
@@ -11,6 +13,12 @@ pub type Result<T> = core::result::Result<T, Error>;
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Error {
     InvalidPad(u32),
+    /// Unsupported `DOEN` output enable selector.
+    InvalidOutputConfig(u8),
+    /// Unsupported `DOUT` output selector.
+    InvalidOutputDrive(u8),
+    /// Function signal group not supported by the `SYS_PINCTRL` muxer.
+    InvalidGroup(GpioGroup),
 }
 
 impl GpioError for Error {
@@ -23,6 +31,9 @@ impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::InvalidPad(err) => write!(f, "invalid pad number: {err}"),
+            Self::InvalidOutputConfig(err) => write!(f, "invalid output config: {err:#04b}"),
+            Self::InvalidOutputDrive(err) => write!(f, "invalid output drive: {err:#04b}"),
+            Self::InvalidGroup(err) => write!(f, "invalid function group: {err:?}"),
         }
     }
 }
@@ -1,10 +1,93 @@
 //! GPIO function multiplexer (FMUX)
 
+use crate::pac::SYS_PINCTRL;
+
+use super::{Error, Result};
+
+// Offsets of the `SYS_PINCTRL` FMUX selector registers
+pub(crate) const GPO_DOEN_OFFSET: usize = 0x00;
+pub(crate) const GPO_DOUT_OFFSET: usize = 0x40;
+pub(crate) const GPI_OFFSET: usize = 0x80;
+
+// Widths of the FMUX selector fields, each field occupies a byte lane of its register
+pub(crate) const GPO_DOEN_MASK: u32 = 0x3f;
+pub(crate) const GPO_DOUT_MASK: u32 = 0x7f;
+pub(crate) const GPI_MASK: u32 = 0x7f;
+
+// Offset of GPIO pad numbers in GPI selector values, `0` and `1` tie the input low and high
+const GPI_PAD_OFFSET: u32 = 2;
+
+/// Represents a configurable GPIO function signal.
 pub trait Function {
     const GROUP: GpioGroup;
     const INDEX: u8;
+    /// GPIO pads the function signal can be routed to.
+    ///
+    /// Defaults to the valid pads for the function [GpioGroup].
+    const VALID_PADS: &'static [u8] = Self::GROUP.valid_pads();
+
+    /// Gets whether the function signal can be routed to the GPIO `pad`.
+    fn is_valid_pad(pad: u32) -> bool {
+        is_valid_function_pad::<Self>(pad)
+    }
+
+    /// Checks that the function signal can be routed to the GPIO `pad`.
+    ///
+    /// Returns [Error::InvalidPad] for invalid pads.
+    fn check_pad(pad: u32) -> Result<()> {
+        if Self::is_valid_pad(pad) {
+            Ok(())
+        } else {
+            Err(Error::InvalidPad(pad))
+        }
+    }
 }
 
+/// Gets whether the [Function] signal can be routed to the GPIO `pad`.
+///
+/// Usable in `const` contexts.
+pub const fn is_valid_function_pad<F: Function + ?Sized>(pad: u32) -> bool {
+    let mut i = 0;
+    while i < F::VALID_PADS.len() {
+        if F::VALID_PADS[i] as u32 == pad {
+            return true;
+        }
+        i += 1;
+    }
+    false
+}
+
+/// Asserts the [Function] signal can be routed to the GPIO `pad`.
+///
+/// Intended for compile-time checks of statically-known pads, evaluation fails for invalid pads.
+///
+/// Example:
+///
+/// ```no_build,no_run
+/// const _: () = gpio::assert_function_pad::<SomeFunction>(gpio::PAD_GPIO5);
+/// ```
+pub const fn assert_function_pad<F: Function + ?Sized>(pad: u32) {
+    assert!(
+        is_valid_function_pad::<F>(pad),
+        "invalid pad for GPIO function"
+    );
+}
+
+const fn pad_range<const N: usize>(start: u8) -> [u8; N] {
+    let mut pads = [0u8; N];
+    let mut i = 0;
+    while i < N {
+        pads[i] = start + i as u8;
+        i += 1;
+    }
+    pads
+}
+
+/// GPIO pads valid for output (GPO and GPEN) function signals: GPIO 0-63.
+pub const GPO_FUNCTION_PADS: [u8; 64] = pad_range(0);
+/// GPIO pads valid for input (GPI) function signals: GPIO 2-63.
+pub const GPI_FUNCTION_PADS: [u8; 62] = pad_range(2);
+
 /// Represents the GPIO signal group for configurable functions.
 #[repr(u8)]
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
@@ -23,6 +106,14 @@ impl GpioGroup {
     pub const fn new() -> Self {
         Self::Gpo
     }
+
+    /// Gets the GPIO pads valid for function signals in the [GpioGroup].
+    pub const fn valid_pads(self) -> &'static [u8] {
+        match self {
+            Self::Gpo | Self::Gpen | Self::AonGpo | Self::AonGpen => &GPO_FUNCTION_PADS,
+            Self::Gpi | Self::AonGpi => &GPI_FUNCTION_PADS,
+        }
+    }
 }
 
 /// Configurable GPO function indices.
@@ -333,3 +424,78 @@ impl AonGpiFunction {
     pub const U0_PMU_IO_EVENT_STUB_GPIO_WAKEUP_2: u8 = 2;
     pub const U0_PMU_IO_EVENT_STUB_GPIO_WAKEUP_3: u8 = 3;
 }
+
+/// Routes the [Function] signal `F` to the GPIO `pad`.
+///
+/// Returns [Error::InvalidPad] if the signal cannot be routed to the pad.
+///
+/// Example:
+///
+/// ```no_run
+/// # use jh71xx_hal::gpio;
+/// struct I2c0Sda;
+///
+/// impl gpio::Function for I2c0Sda {
+///     const GROUP: gpio::GpioGroup = gpio::GpioGroup::Gpo;
+///     const INDEX: u8 = gpio::GpoFunction::U0_DW_I2C_IC_DATA_OUT_A;
+/// }
+///
+/// gpio::set_function::<I2c0Sda>(gpio::PAD_GPIO5).unwrap();
+/// ```
+pub fn set_function<F: Function + ?Sized>(pad: u32) -> Result<()> {
+    F::check_pad(pad)?;
+    set_function_raw(pad, F::GROUP, F::INDEX)
+}
+
+/// Routes the function signal `index` from the [GpioGroup] `group` to the GPIO `pad`.
+///
+/// - [GpioGroup::Gpo]: selects the signal driving the pad output (`DOUT`), and enables the
+///   output driver
+/// - [GpioGroup::Gpen]: selects the signal driving the pad output enable (`DOEN`)
+/// - [GpioGroup::Gpi]: selects the pad as the input for the signal
+///
+/// Returns [Error::InvalidPad] for pads invalid for the group, and [Error::InvalidGroup] for
+/// the always-on (`AON_PINCTRL`) groups.
+pub fn set_function_raw(pad: u32, group: GpioGroup, index: u8) -> Result<()> {
+    if !group.valid_pads().iter().any(|&p| p as u32 == pad) {
+        return Err(Error::InvalidPad(pad));
+    }
+
+    let pad_idx = pad as usize;
+    let index = index as u32;
+
+    match group {
+        GpioGroup::Gpo => {
+            write_fmux(GPO_DOUT_OFFSET, pad_idx, GPO_DOUT_MASK, index);
+            // Output enable tied low: enable the output driver
+            write_fmux(GPO_DOEN_OFFSET, pad_idx, GPO_DOEN_MASK, 0);
+        }
+        GpioGroup::Gpen => write_fmux(GPO_DOEN_OFFSET, pad_idx, GPO_DOEN_MASK, index),
+        GpioGroup::Gpi => write_fmux(GPI_OFFSET, index as usize, GPI_MASK, pad + GPI_PAD_OFFSET),
+        _ => return Err(Error::InvalidGroup(group)),
+    }
+
+    Ok(())
+}
+
+// Reads the FMUX selector in byte lane `idx` of the register array at `offset`.
+pub(crate) fn read_fmux(offset: usize, idx: usize, mask: u32) -> u32 {
+    let reg = (SYS_PINCTRL::ptr() as usize + offset + (idx / 4) * 4) as *const u32;
+    let shift = (idx % 4) * 8;
+
+    // SAFETY: the selector registers are within the `SYS_PINCTRL` register space.
+    (unsafe { reg.read_volatile() } >> shift) & mask
+}
+
+// Writes the FMUX selector `val` into byte lane `idx` of the register array at `offset`.
+pub(crate) fn write_fmux(offset: usize, idx: usize, mask: u32, val: u32) {
+    let reg = (SYS_PINCTRL::ptr() as usize + offset + (idx / 4) * 4) as *mut u32;
+    let shift = (idx % 4) * 8;
+
+    // SAFETY: the selector registers are within the `SYS_PINCTRL` register space, and only the
+    // selector field for `idx` is modified.
+    super::atomic(|| unsafe {
+        let cur = reg.read_volatile() & !(mask << shift);
+        reg.write_volatile(cur | ((val & mask) << shift));
+    });
+}
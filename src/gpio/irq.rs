@@ -0,0 +1,276 @@
+//! GPIO interrupt status and handler dispatch.
+
+#[cfg(feature = "rt")]
+use core::cell::RefCell;
+
+#[cfg(feature = "rt")]
+use critical_section::Mutex;
+
+use crate::pac::SYS_PINCTRL;
+
+use super::{Error, Result};
+
+/// Number of GPIO pads with interrupt support in the `SYS_PINCTRL` GPIO muxer.
+pub const GPIO_IRQ_PADS: usize = 64;
+
+/// PLIC interrupt source of the `SYS_PINCTRL` GPIO interrupt.
+pub const GPIO_PLIC_IRQ: u32 = 86;
+
+/// Represents the condition triggering a GPIO interrupt.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum InterruptTrigger {
+    /// Low-to-high transition of the pad.
+    #[default]
+    RisingEdge,
+    /// High-to-low transition of the pad.
+    FallingEdge,
+    /// Any transition of the pad.
+    BothEdges,
+    /// Pad is at a high level.
+    HighLevel,
+    /// Pad is at a low level.
+    LowLevel,
+}
+
+impl InterruptTrigger {
+    /// Gets whether the trigger is edge-sensitive (`IS` register bit).
+    pub const fn is_edge(&self) -> bool {
+        matches!(self, Self::RisingEdge | Self::FallingEdge | Self::BothEdges)
+    }
+
+    /// Gets whether the trigger fires on both edges (`IBE` register bit).
+    pub const fn is_both_edges(&self) -> bool {
+        matches!(self, Self::BothEdges)
+    }
+
+    /// Gets whether the trigger fires on a rising edge, or high level (`IEV` register bit).
+    pub const fn is_high(&self) -> bool {
+        matches!(self, Self::RisingEdge | Self::HighLevel)
+    }
+}
+
+// Sets, or clears the bit for `pad` in the `lo`/`hi` register pair for GPIO 0-31 and 32-63.
+macro_rules! modify_pad_bit {
+    ($pinctrl:ident, $lo:ident, $hi:ident, $pad:expr, $set:expr) => {{
+        let pad = $pad;
+        let set = $set;
+        if pad < 32 {
+            let mask = 1u32 << pad;
+            $pinctrl.$lo().modify(|r, w| unsafe {
+                w.bits(if set {
+                    r.bits() | mask
+                } else {
+                    r.bits() & !mask
+                })
+            });
+        } else {
+            let mask = 1u32 << (pad - 32);
+            $pinctrl.$hi().modify(|r, w| unsafe {
+                w.bits(if set {
+                    r.bits() | mask
+                } else {
+                    r.bits() & !mask
+                })
+            });
+        }
+    }};
+}
+
+/// Enables the interrupt for GPIO `pad`, firing on the [InterruptTrigger] condition.
+///
+/// Any stale interrupt for the pad is cleared before the interrupt is unmasked.
+///
+/// Example:
+///
+/// ```no_run
+/// # use jh71xx_hal::gpio;
+/// gpio::enable_irq(gpio::PAD_GPIO5, gpio::InterruptTrigger::FallingEdge).unwrap();
+/// ```
+pub fn enable_irq(pad: u32, trigger: InterruptTrigger) -> Result<()> {
+    check_irq_pad(pad)?;
+    enable_irq_inner(pad, trigger);
+    Ok(())
+}
+
+/// Disables (masks) the interrupt for GPIO `pad`.
+pub fn disable_irq(pad: u32) -> Result<()> {
+    check_irq_pad(pad)?;
+    disable_irq_inner(pad);
+    Ok(())
+}
+
+fn check_irq_pad(pad: u32) -> Result<()> {
+    if (pad as usize) < GPIO_IRQ_PADS {
+        Ok(())
+    } else {
+        Err(Error::InvalidPad(pad))
+    }
+}
+
+// Callers must ensure `pad` is less than [GPIO_IRQ_PADS].
+pub(crate) fn enable_irq_inner(pad: u32, trigger: InterruptTrigger) {
+    // The configuration registers are shared by 32 pads, serialize the read-modify-writes
+    super::atomic(|| enable_irq_locked(pad, trigger));
+}
+
+fn enable_irq_locked(pad: u32, trigger: InterruptTrigger) {
+    // SAFETY: only the bits for `pad` are modified in the interrupt configuration registers.
+    let pinctrl = unsafe { &*SYS_PINCTRL::ptr() };
+
+    // Mask the interrupt while changing the trigger condition
+    modify_pad_bit!(pinctrl, ioirq_9, ioirq_10, pad, false);
+
+    // `IS`: interrupt sense (1: edge, 0: level)
+    modify_pad_bit!(pinctrl, ioirq_1, ioirq_2, pad, trigger.is_edge());
+    // `IBE`: interrupt on both edges
+    modify_pad_bit!(pinctrl, ioirq_5, ioirq_6, pad, trigger.is_both_edges());
+    // `IEV`: interrupt event (1: rising edge/high level, 0: falling edge/low level)
+    modify_pad_bit!(pinctrl, ioirq_7, ioirq_8, pad, trigger.is_high());
+
+    clear_irqs(1 << pad);
+
+    // Globally enable GPIO interrupts, then unmask the pad
+    pinctrl
+        .ioirq_0()
+        .modify(|r, w| unsafe { w.bits(r.bits() | 1) });
+    modify_pad_bit!(pinctrl, ioirq_9, ioirq_10, pad, true);
+}
+
+// Callers must ensure `pad` is less than [GPIO_IRQ_PADS].
+pub(crate) fn disable_irq_inner(pad: u32) {
+    // SAFETY: only the bit for `pad` is modified in the `IE` registers.
+    let pinctrl = unsafe { &*SYS_PINCTRL::ptr() };
+
+    // `IE`: interrupt enable (1: unmasked)
+    super::atomic(|| modify_pad_bit!(pinctrl, ioirq_9, ioirq_10, pad, false));
+}
+
+/// GPIO interrupt handler function.
+pub type GpioHandler = fn();
+
+#[cfg(feature = "rt")]
+static HANDLERS: Mutex<RefCell<[Option<GpioHandler>; GPIO_IRQ_PADS]>> =
+    Mutex::new(RefCell::new([None; GPIO_IRQ_PADS]));
+
+/// Gets the pending (masked) GPIO interrupts.
+///
+/// Bit `N` of the returned value is set when GPIO pad `N` has a pending interrupt.
+pub fn pending_irqs() -> u64 {
+    // SAFETY: the `MIS` registers are read-only, and only changed by the hardware.
+    let pinctrl = unsafe { &*SYS_PINCTRL::ptr() };
+
+    let lo = pinctrl.ioirq_13().read().bits() as u64;
+    let hi = pinctrl.ioirq_14().read().bits() as u64;
+
+    (hi << 32) | lo
+}
+
+/// Clears the GPIO interrupts set in `mask`.
+///
+/// Bit `N` of `mask` clears the interrupt for GPIO pad `N`. Clearing an interrupt that is not
+/// pending has no effect.
+pub fn clear_irqs(mask: u64) {
+    // SAFETY: clearing interrupt flags only affects the pads selected in `mask`.
+    let pinctrl = unsafe { &*SYS_PINCTRL::ptr() };
+
+    let lo = mask as u32;
+    let hi = (mask >> 32) as u32;
+
+    // The `IC` registers clear an interrupt on a zero-to-one transition of the pad bit.
+    super::atomic(|| {
+        if lo != 0 {
+            let val = pinctrl.ioirq_3().read().bits() & !lo;
+            pinctrl.ioirq_3().write(|w| unsafe { w.bits(val) });
+            pinctrl.ioirq_3().write(|w| unsafe { w.bits(val | lo) });
+        }
+
+        if hi != 0 {
+            let val = pinctrl.ioirq_4().read().bits() & !hi;
+            pinctrl.ioirq_4().write(|w| unsafe { w.bits(val) });
+            pinctrl.ioirq_4().write(|w| unsafe { w.bits(val | hi) });
+        }
+    });
+}
+
+/// Registers a [GpioHandler] to call when the interrupt for GPIO `pad` fires.
+///
+/// Replaces any previously registered handler for the pad.
+///
+/// Example:
+///
+/// ```no_run
+/// # use jh71xx_hal::gpio;
+/// fn on_button() {
+///     // handle the button press
+/// }
+///
+/// gpio::register_handler(gpio::PAD_GPIO0, on_button).unwrap();
+/// ```
+#[cfg(feature = "rt")]
+pub fn register_handler(pad: u32, handler: GpioHandler) -> Result<()> {
+    set_handler(pad, Some(handler))
+}
+
+/// Removes the registered [GpioHandler] for GPIO `pad`.
+#[cfg(feature = "rt")]
+pub fn unregister_handler(pad: u32) -> Result<()> {
+    set_handler(pad, None)
+}
+
+#[cfg(feature = "rt")]
+fn set_handler(pad: u32, handler: Option<GpioHandler>) -> Result<()> {
+    check_irq_pad(pad)?;
+
+    critical_section::with(|cs| HANDLERS.borrow(cs).borrow_mut()[pad as usize] = handler);
+
+    Ok(())
+}
+
+/// Dispatches pending GPIO interrupts to their registered handlers.
+///
+/// Call from the PLIC GPIO interrupt handler (source [GPIO_PLIC_IRQ]). Edge-triggered interrupts
+/// are cleared before their handler runs, so an edge arriving during the handler is dispatched
+/// again. Level-triggered interrupts are cleared after their handler runs, once the handler
+/// had the chance to deassert the level. Interrupts for pads without a registered handler are
+/// also cleared.
+///
+/// Returns the mask of dispatched GPIO interrupts.
+#[cfg(feature = "rt")]
+pub fn dispatch() -> u64 {
+    let pending = pending_irqs();
+    if pending == 0 {
+        return 0;
+    }
+
+    // Copy out the handlers, so they run outside the critical section
+    let handlers = critical_section::with(|cs| *HANDLERS.borrow(cs).borrow());
+
+    let edge = pending & edge_irqs();
+    clear_irqs(edge);
+
+    for (pad, handler) in handlers.iter().enumerate() {
+        if pending & (1 << pad) != 0 {
+            if let Some(handler) = handler {
+                handler();
+            }
+        }
+    }
+
+    clear_irqs(pending & !edge);
+
+    pending
+}
+
+// Gets the edge-triggered GPIO interrupts, bit `N` is set for GPIO pad `N`.
+#[cfg(feature = "rt")]
+fn edge_irqs() -> u64 {
+    // SAFETY: the `IS` registers are only read.
+    let pinctrl = unsafe { &*SYS_PINCTRL::ptr() };
+
+    // `IS`: interrupt sense (1: edge, 0: level)
+    let lo = pinctrl.ioirq_1().read().bits() as u64;
+    let hi = pinctrl.ioirq_2().read().bits() as u64;
+
+    (hi << 32) | lo
+}
@@ -1,3 +1,7 @@
+use core::cmp;
+
+use super::{Error, Result};
+
 /// GPIO pad for GPIO 0
 pub const PAD_GPIO0: u32 = 0;
 /// GPIO pad for GPIO 1
@@ -291,9 +295,19 @@ pub enum Pad {
     QspiData3 = PAD_QSPI_DATA3,
 }
 
-impl From<u32> for Pad {
-    fn from(val: u32) -> Self {
-        match val {
+impl TryFrom<u32> for Pad {
+    type Error = Error;
+
+    /// Converts a pad number into a [Pad].
+    ///
+    /// Returns [Error::InvalidPad] for pad numbers out of range, or without a `SYS_PINCTRL` pad
+    /// configuration (see [Pad::is_valid]).
+    fn try_from(val: u32) -> Result<Self> {
+        if !is_valid_pad(val) {
+            return Err(Error::InvalidPad(val));
+        }
+
+        Ok(match val {
             PAD_GPIO0 => Self::Gpio0,
             PAD_GPIO1 => Self::Gpio1,
             PAD_GPIO2 => Self::Gpio2,
@@ -389,11 +403,127 @@ impl From<u32> for Pad {
             PAD_QSPI_DATA1 => Self::QspiData1,
             PAD_QSPI_DATA2 => Self::QspiData2,
             PAD_QSPI_DATA3 => Self::QspiData3,
-            _ => Self::Gpio0,
+            _ => return Err(Error::InvalidPad(val)),
+        })
+    }
+}
+
+impl Pad {
+    /// First [Pad] in the pad select range.
+    pub const FIRST: Self = Self::Gpio0;
+    /// Last [Pad] in the pad select range.
+    pub const LAST: Self = Self::QspiData3;
+
+    /// Gets whether the [Pad] has a `SYS_PINCTRL` pad configuration.
+    ///
+    /// The `GMAC1` pads (75-88) between the SD0 strobe, and the QSPI pads are configured
+    /// through the `SYS_SYSCON` instead, so are not valid for GPIO configuration.
+    ///
+    /// Example:
+    ///
+    /// ```
+    /// # use jh71xx_hal::gpio::Pad;
+    /// assert!(Pad::Sd0Strb.is_valid());
+    /// assert!(!Pad::Gmac1Mdc.is_valid());
+    /// assert!(Pad::try_from(75).is_err());
+    /// assert_eq!(Pad::try_from(89), Ok(Pad::QspiSclk));
+    /// ```
+    pub const fn is_valid(&self) -> bool {
+        is_valid_pad(*self as u32)
+    }
+
+    /// Gets an iterator over every valid [Pad], in order.
+    ///
+    /// Pads without a `SYS_PINCTRL` pad configuration are skipped, see [Pad::is_valid].
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::gpio::Pad;
+    /// for _pad in Pad::iter() {
+    ///     // configure the pad
+    /// }
+    /// ```
+    pub const fn iter() -> PadIter {
+        Self::range(Self::FIRST, Self::LAST)
+    }
+
+    /// Gets an iterator over the valid [Pad]s from `start` to `end` (inclusive), in order.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::gpio::Pad;
+    /// // iterate over the muxed GPIO pads
+    /// for _pad in Pad::range(Pad::Gpio0, Pad::Gpio63) {
+    ///     // configure the pad
+    /// }
+    /// ```
+    pub const fn range(start: Self, end: Self) -> PadIter {
+        PadIter {
+            next: start as u32,
+            end: end as u32,
         }
     }
 }
 
+/// Iterator over a range of [Pad]s.
+#[derive(Clone, Debug)]
+pub struct PadIter {
+    next: u32,
+    end: u32,
+}
+
+impl Iterator for PadIter {
+    type Item = Pad;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.next <= self.end {
+            let pad = Pad::try_from(self.next);
+            self.next = self.next.saturating_add(1);
+
+            if let Ok(pad) = pad {
+                return Some(pad);
+            }
+        }
+
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let end = self.end.saturating_add(1);
+        let len = end.saturating_sub(self.next);
+
+        // Exclude the unmapped pads within the remaining range
+        let gap_start = cmp::max(self.next, PAD_GMAC1_MDC);
+        let gap_end = cmp::min(end, PAD_GMAC1_TXC + 1);
+        let gap = gap_end.saturating_sub(gap_start);
+
+        let len = len.saturating_sub(gap) as usize;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for PadIter {}
+
+/// Calls `f` on every valid [Pad], in order.
+///
+/// Example:
+///
+/// ```no_run
+/// # use jh71xx_hal::gpio;
+/// let mut count = 0;
+/// gpio::for_each_pad(|_pad| count += 1);
+/// ```
+pub fn for_each_pad<F: FnMut(Pad)>(f: F) {
+    Pad::iter().for_each(f);
+}
+
+// Gets whether the pad number has a `SYS_PINCTRL` pad configuration.
+const fn is_valid_pad(pad: u32) -> bool {
+    pad <= PAD_QSPI_DATA3 && !(pad >= PAD_GMAC1_MDC && pad <= PAD_GMAC1_TXC)
+}
+
 impl From<Pad> for u32 {
     fn from(val: Pad) -> u32 {
         val as u32
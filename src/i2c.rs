@@ -25,35 +25,64 @@
 
 use core::cmp;
 
-use embedded_hal::delay::DelayNs;
-use embedded_hal::i2c::{self, I2c as I2cHal, Operation, SevenBitAddress, TenBitAddress};
+use heapless::Vec;
 
+use embedded_hal::delay::DelayNs;
+use embedded_hal::i2c::{
+    self, I2c as I2cHal, NoAcknowledgeSource, Operation, SevenBitAddress, TenBitAddress,
+};
+
+use crate::gpio::{
+    self, OutputConfig, OutputDrive, GPO_DOEN_MASK, GPO_DOEN_OFFSET, GPO_DOUT_MASK, GPO_DOUT_OFFSET,
+};
+use crate::reset::{CrgReset, Reset};
 use crate::{bitflag_is_set, delay::u74_mdelay};
 
+#[cfg(feature = "async")]
+mod asynch;
 mod constants;
 mod error;
 mod message;
+#[cfg(feature = "mock")]
+mod mock;
 mod mode;
+mod pec;
 mod peripheral;
 mod registers;
+mod slave;
+mod smbus;
+mod snapshot;
 mod timings;
 
+#[cfg(feature = "async")]
+pub use asynch::*;
 pub use constants::*;
 pub use error::*;
 pub use message::*;
+#[cfg(feature = "mock")]
+pub use mock::*;
 pub use mode::*;
+pub use pec::*;
 pub use peripheral::*;
 pub use registers::*;
+pub use slave::*;
+pub use snapshot::*;
 pub use timings::*;
 
 bitflags! {
     /// Software status flags.
+    ///
+    /// The in-progress flags track the low-level [I2c::write_msg], and [I2c::read_msg] calls.
+    /// The `embedded-hal` transfers transmit, and receive exactly the requested bytes, and
+    /// leave them clear.
     #[repr(C)]
     #[derive(Clone, Copy, Debug, Default, PartialEq)]
     pub struct Status: u32 {
         const NONE = 0b0000;
         const ACTIVE = 0b0001;
+        /// Bytes of the last [I2c::write_msg] did not fit in the TX FIFO.
         const WRITE_IN_PROGRESS = 0b0010;
+        /// Bytes received by the last [I2c::read_msg] did not fit in the read buffer.
         const READ_IN_PROGRESS = 0b0100;
         const MASK = 0b0111;
     }
@@ -85,6 +114,11 @@ pub struct I2c<I2C: I2cPeripheral> {
     timings: I2cTimings,
     mode: I2cOpMode,
     msg_err: i32,
+    tx_abort_source: I2cTxAbortSource,
+    pec: bool,
+    auto_recover: bool,
+    timeout_us: u32,
+    recovery_pads: Option<(u32, u32)>,
 }
 
 impl<I2C: I2cPeripheral> I2c<I2C> {
@@ -98,11 +132,13 @@ impl<I2C: I2cPeripheral> I2c<I2C> {
     /// let _i2c0 = i2c::I2c::new(dp.I2C0);
     /// ```
     pub fn new(i2c: I2C) -> Self {
+        let (tx_fifo_depth, rx_fifo_depth) = fifo_depths(i2c.get_comp_param_1());
+
         Self {
             i2c,
             status: Status::default(),
-            rx_fifo_depth: 0,
-            tx_fifo_depth: 0,
+            rx_fifo_depth,
+            tx_fifo_depth,
             tx_buf_len: 0,
             rx_buf_len: 0,
             tx_outstanding: 0,
@@ -121,24 +157,469 @@ impl<I2C: I2cPeripheral> I2c<I2C> {
             timings: I2cTimings::default(),
             mode: I2cOpMode::default(),
             msg_err: 0,
+            tx_abort_source: I2cTxAbortSource::NONE,
+            pec: false,
+            auto_recover: false,
+            timeout_us: TIMEOUT_US,
+            recovery_pads: None,
         }
     }
 
+    /// Gets the MMIO base address of the wrapped I2C peripheral.
+    pub fn base_addr(&self) -> usize {
+        self.i2c.base_addr()
+    }
+
+    /// Captures an [I2cRegisterSnapshot] of the peripheral registers.
+    ///
+    /// Read-only: only registers without read side effects are read, so interrupts are not
+    /// cleared, and transfer state is not disturbed.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{pac, i2c};
+    /// use embedded_hal::i2c::I2c as _;
+    ///
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let mut i2c0 = i2c::I2c::new(dp.I2C0);
+    ///
+    /// if i2c0.write(0x50u8, &[0x00]).is_err() {
+    ///     let _snapshot = i2c0.dump_registers();
+    ///     // print the snapshot, e.g. `writeln!(console, "{_snapshot}")`
+    /// }
+    /// ```
+    pub fn dump_registers(&self) -> I2cRegisterSnapshot {
+        I2cRegisterSnapshot::read(&self.i2c)
+    }
+
+    /// Releases the I2C peripheral.
+    ///
+    /// The adapter is disabled first, aborting any transfer left on hold, and its interrupts are
+    /// masked, so the peripheral can be cleanly reconfigured, or wrapped in a new [I2c].
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{pac, i2c};
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let i2c0 = i2c::I2c::new(dp.I2C0);
+    ///
+    /// // ... talk to devices ...
+    ///
+    /// let periph = i2c0.free();
+    /// let _slave = i2c::I2c::new(periph);
+    /// ```
+    pub fn free(mut self) -> I2C {
+        self.__disable();
+        self.i2c.set_interrupt_mask(I2cInterruptMask::NONE);
+        let _ci = self.i2c.get_clear_interrupt();
+        self.i2c
+    }
+
     /// Gets the [Status].
     pub const fn status(&self) -> Status {
         self.status
     }
 
+    /// Gets whether SMBus PEC (packet error checking) is enabled.
+    pub const fn pec(&self) -> bool {
+        self.pec
+    }
+
+    /// Sets whether SMBus PEC (packet error checking) is enabled.
+    ///
+    /// When enabled, polled transfers append a CRC-8 PEC byte to writes, and verify the
+    /// trailing PEC byte on reads.
+    pub fn set_pec(&mut self, val: bool) {
+        self.pec = val;
+    }
+
+    /// Builder function that sets whether SMBus PEC (packet error checking) is enabled.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{pac, i2c};
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let mut i2c0 = i2c::I2c::new(dp.I2C0).with_pec(true);
+    ///
+    /// let mut voltage = [0u8; 2];
+    /// i2c0.write_read(0x0b, &[0x09], &mut voltage).unwrap();
+    /// ```
+    pub fn with_pec(mut self, val: bool) -> Self {
+        self.set_pec(val);
+        self
+    }
+
+    /// Gets whether the controller is automatically re-initialized after a failed transfer.
+    pub const fn auto_recover(&self) -> bool {
+        self.auto_recover
+    }
+
+    /// Sets whether the controller is automatically re-initialized after a failed transfer.
+    ///
+    /// When enabled, an `embedded-hal` transfer failing with [Error::Bus], or a timeout
+    /// ([Error::Other]), disables the adapter and runs [I2c::init_master] before returning the
+    /// error. The next transfer then starts from a clean controller state.
+    pub fn set_auto_recover(&mut self, val: bool) {
+        self.auto_recover = val;
+    }
+
+    /// Builder function that sets whether the controller is automatically re-initialized after a
+    /// failed transfer.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{pac, i2c};
+    /// use embedded_hal::i2c::I2c;
+    ///
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let mut i2c0 = i2c::I2c::new(dp.I2C0).with_auto_recover(true);
+    ///
+    /// // A bus glitch here no longer poisons subsequent transfers
+    /// let _ = i2c0.write(0x50u8, &[0x00]);
+    /// ```
+    pub fn with_auto_recover(mut self, val: bool) -> Self {
+        self.set_auto_recover(val);
+        self
+    }
+
+    /// Gets the [I2cMsgFlag] protocol options applied to write messages.
+    pub const fn tx_flag(&self) -> I2cMsgFlag {
+        self.tx_flag
+    }
+
+    /// Sets the [I2cMsgFlag] protocol options applied to write messages.
+    ///
+    /// With [I2cMsgFlag::IGNORE_NAK], a data NAK from the target is treated as an ACK. The
+    /// controller flushes the TX FIFO on a NAK, so any bytes queued after the NAKed byte are
+    /// dropped, but the NAK is cleared, and does not abort subsequent messages.
+    ///
+    /// **NOTE**: protocol mangling flags are only for use with broken/nonconforming targets.
+    pub fn set_tx_flag(&mut self, val: I2cMsgFlag) {
+        self.tx_flag = val;
+    }
+
+    /// Builder function that sets the [I2cMsgFlag] protocol options applied to write messages.
+    pub fn with_tx_flag(mut self, val: I2cMsgFlag) -> Self {
+        self.set_tx_flag(val);
+        self
+    }
+
+    /// Gets the [I2cMsgFlag] protocol options applied to read messages.
+    pub const fn rx_flag(&self) -> I2cMsgFlag {
+        self.rx_flag
+    }
+
+    /// Sets the [I2cMsgFlag] protocol options applied to read messages.
+    ///
+    /// With [I2cMsgFlag::NO_RD_ACK], the read command is issued with a STOP, so the controller
+    /// NAKs the received byte instead of ACKing it.
+    ///
+    /// **NOTE**: protocol mangling flags are only for use with broken/nonconforming targets.
+    pub fn set_rx_flag(&mut self, val: I2cMsgFlag) {
+        self.rx_flag = val;
+    }
+
+    /// Builder function that sets the [I2cMsgFlag] protocol options applied to read messages.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{pac, i2c};
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let _i2c0 = i2c::I2c::new(dp.I2C0)
+    ///     .with_tx_flag(i2c::I2cMsgFlag::IGNORE_NAK)
+    ///     .with_rx_flag(i2c::I2cMsgFlag::NO_RD_ACK);
+    /// ```
+    pub fn with_rx_flag(mut self, val: I2cMsgFlag) -> Self {
+        self.set_rx_flag(val);
+        self
+    }
+
+    /// Gets the timeout (in microseconds).
+    ///
+    /// Used when waiting for received data, and for the adapter to abort a transfer.
+    ///
+    /// Each stage of the `embedded-hal` transfers (FIFO space, received data, `STOP`) waits for
+    /// the larger of the timeout, and [XFER_TIMEOUT_US], since a stage spans several byte times.
+    pub const fn timeout(&self) -> u32 {
+        self.timeout_us
+    }
+
+    /// Sets the timeout (in microseconds).
+    ///
+    /// Increase the timeout for slow, clock-stretching devices.
+    ///
+    /// **NOTE**: `timeout_us` must be greater than zero, no-op otherwise.
+    pub fn set_timeout(&mut self, timeout_us: u32) {
+        if timeout_us > 0 {
+            self.timeout_us = timeout_us;
+        }
+    }
+
+    /// Builder function that sets the timeout (in microseconds).
+    ///
+    /// **NOTE**: `timeout_us` must be greater than zero, no-op otherwise.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{pac, i2c};
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let _i2c0 = i2c::I2c::new(dp.I2C0).with_timeout(10_000);
+    /// ```
+    pub fn with_timeout(mut self, timeout_us: u32) -> Self {
+        self.set_timeout(timeout_us);
+        self
+    }
+
+    /// Gets the `(SCL, SDA)` GPIO pads used for bus recovery.
+    pub const fn recovery_pads(&self) -> Option<(u32, u32)> {
+        self.recovery_pads
+    }
+
+    /// Sets the `SCL` and `SDA` GPIO pads used for bus recovery.
+    ///
+    /// Returns [Error::InvalidPad] for pads past [PAD_GPIO63](gpio::PAD_GPIO63), leaving the
+    /// recovery pads unchanged.
+    ///
+    /// See [I2c::recover_bus] for details.
+    pub fn set_recovery_pads(&mut self, scl_pad: u32, sda_pad: u32) -> Result<()> {
+        if let Some(pad) = [scl_pad, sda_pad]
+            .into_iter()
+            .find(|&pad| pad > gpio::PAD_GPIO63)
+        {
+            return Err(Error::InvalidPad(pad));
+        }
+
+        self.recovery_pads = Some((scl_pad, sda_pad));
+        Ok(())
+    }
+
+    /// Builder function that sets the `SCL` and `SDA` GPIO pads used for bus recovery.
+    ///
+    /// See [I2c::set_recovery_pads] for details.
+    pub fn with_recovery_pads(mut self, scl_pad: u32, sda_pad: u32) -> Result<Self> {
+        self.set_recovery_pads(scl_pad, sda_pad)?;
+        Ok(self)
+    }
+
+    /// Recovers a bus where a slave holds `SDA` low, e.g. after a reset in the middle of a read.
+    ///
+    /// Mirrors the Linux `i2c_generic_scl_recovery` mechanism: the `SCL` and `SDA` pads are
+    /// switched to GPIO through the FMUX, `SCL` is clocked up to nine times until `SDA` is
+    /// released, and a STOP condition is issued. The I2C function routing is then restored, and
+    /// the controller is re-initialized.
+    ///
+    /// Callable on init, or after a transfer fails with [Error::Bus].
+    ///
+    /// **NOTE**: requires the recovery pads (see [I2c::with_recovery_pads]), and that the pads
+    /// are routed through `SYS_PINCTRL` with their inputs enabled. Pads in `AON_PINCTRL` are not
+    /// supported.
+    ///
+    /// Returns:
+    ///
+    /// - `Ok(())` if `SDA` is released
+    /// - `Err(Error::Other)` if no recovery pads are set
+    /// - `Err(Error::Bus)` if `SDA` is still held low
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{pac, gpio, i2c};
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let mut i2c0 = i2c::I2c::new(dp.I2C0)
+    ///     .with_recovery_pads(gpio::PAD_GPIO57, gpio::PAD_GPIO58)
+    ///     .unwrap();
+    ///
+    /// i2c0.recover_bus().unwrap();
+    /// ```
+    pub fn recover_bus(&mut self) -> Result<()> {
+        let (scl, sda) = self.recovery_pads.ok_or(Error::Other)?;
+        let (scl_idx, sda_idx) = (scl as usize, sda as usize);
+
+        // Only pads with an input sync bit can be used for recovery
+        if gpio::read_pad_input(scl).is_err() || gpio::read_pad_input(sda).is_err() {
+            return Err(Error::Other);
+        }
+
+        // Save the I2C function routing
+        let scl_dout = gpio::read_fmux(GPO_DOUT_OFFSET, scl_idx, GPO_DOUT_MASK);
+        let scl_doen = gpio::read_fmux(GPO_DOEN_OFFSET, scl_idx, GPO_DOEN_MASK);
+        let sda_dout = gpio::read_fmux(GPO_DOUT_OFFSET, sda_idx, GPO_DOUT_MASK);
+        let sda_doen = gpio::read_fmux(GPO_DOEN_OFFSET, sda_idx, GPO_DOEN_MASK);
+
+        self.__disable();
+
+        // Emulate open-drain outputs: drive low through the output enable, release otherwise
+        let set_line = |pad: usize, high: bool| {
+            let doen = if high {
+                OutputConfig::Neutral
+            } else {
+                OutputConfig::Low
+            };
+            gpio::write_fmux(GPO_DOEN_OFFSET, pad, GPO_DOEN_MASK, u8::from(doen) as u32);
+        };
+        let mut delay = u74_mdelay();
+
+        for idx in [scl_idx, sda_idx] {
+            set_line(idx, true);
+            gpio::write_fmux(
+                GPO_DOUT_OFFSET,
+                idx,
+                GPO_DOUT_MASK,
+                u8::from(OutputDrive::Low) as u32,
+            );
+        }
+        delay.delay_us(RECOVERY_DELAY_US);
+
+        for _ in 0..RECOVERY_CLOCKS {
+            if gpio::read_pad_input(sda).unwrap_or(false) {
+                break;
+            }
+
+            set_line(scl_idx, false);
+            delay.delay_us(RECOVERY_DELAY_US);
+            set_line(scl_idx, true);
+            delay.delay_us(RECOVERY_DELAY_US);
+        }
+
+        // STOP condition: SDA rising while SCL is high
+        set_line(sda_idx, false);
+        delay.delay_us(RECOVERY_DELAY_US);
+        set_line(sda_idx, true);
+        delay.delay_us(RECOVERY_DELAY_US);
+
+        let released = gpio::read_pad_input(sda).unwrap_or(false);
+
+        // Restore the I2C function routing
+        gpio::write_fmux(GPO_DOUT_OFFSET, scl_idx, GPO_DOUT_MASK, scl_dout);
+        gpio::write_fmux(GPO_DOEN_OFFSET, scl_idx, GPO_DOEN_MASK, scl_doen);
+        gpio::write_fmux(GPO_DOUT_OFFSET, sda_idx, GPO_DOUT_MASK, sda_dout);
+        gpio::write_fmux(GPO_DOEN_OFFSET, sda_idx, GPO_DOEN_MASK, sda_doen);
+
+        self.status = Status::NONE;
+        self.init_master();
+
+        if released {
+            Ok(())
+        } else {
+            Err(Error::Bus)
+        }
+    }
+
+    /// Gets the `TX_ABRT_SOURCE` of the last aborted transfer.
+    ///
+    /// Useful for debugging, the value is kept until the next transfer abort.
+    pub const fn tx_abort_source(&self) -> I2cTxAbortSource {
+        self.tx_abort_source
+    }
+
+    /// Gets the [I2cTimings].
+    pub const fn timings(&self) -> I2cTimings {
+        self.timings
+    }
+
+    /// Sets the [I2cTimings].
+    ///
+    /// Call [I2c::compute_timings] afterwards to derive the SCL counts.
+    pub fn set_timings(&mut self, val: I2cTimings) {
+        self.timings = val;
+    }
+
+    /// Builder function that sets the [I2cTimings].
+    pub fn with_timings(mut self, val: I2cTimings) -> Self {
+        self.set_timings(val);
+        self
+    }
+
+    /// Computes the SCL high/low counts, and SDA hold time from the [I2cTimings].
+    ///
+    /// Mirrors the Linux DesignWare driver: fall times default to [DEFAULT_FALL_NS], and the
+    /// fast-mode counts use the Fast-mode Plus periods when the bus frequency is 1 MHz.
+    /// High-speed counts are only computed for high-speed bus frequencies.
+    ///
+    /// The counts are written to the peripheral by the next [I2c::init_master] call.
+    ///
+    /// - `ic_clk_hz`: `IC_CLK` input clock rate in Hz
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{pac, i2c};
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let mut i2c0 = i2c::I2c::new(dp.I2C0)
+    ///     .with_timings(i2c::I2cTimings::for_speed(i2c::I2cSpeedMode::Fast));
+    ///
+    /// i2c0.compute_timings(50_000_000);
+    /// i2c0.init_master();
+    /// ```
+    pub fn compute_timings(&mut self, ic_clk_hz: u32) {
+        let ic_clk_khz = ic_clk_hz / 1000;
+        let sda_fall_ns = match self.timings.sda_fall_ns() {
+            0 => DEFAULT_FALL_NS,
+            ns => ns,
+        };
+        let scl_fall_ns = match self.timings.scl_fall_ns() {
+            0 => DEFAULT_FALL_NS,
+            ns => ns,
+        };
+
+        self.ss_hcnt = scl_hcnt(ic_clk_khz, SS_HIGH_NS, sda_fall_ns);
+        self.ss_lcnt = scl_lcnt(ic_clk_khz, SS_LOW_NS, scl_fall_ns);
+
+        let (fs_high_ns, fs_low_ns) = match self.timings.bus_freq_hz() {
+            I2cSpeedMode::FastPlus => (FP_HIGH_NS, FP_LOW_NS),
+            _ => (FS_HIGH_NS, FS_LOW_NS),
+        };
+        self.fs_hcnt = scl_hcnt(ic_clk_khz, fs_high_ns, sda_fall_ns);
+        self.fs_lcnt = scl_lcnt(ic_clk_khz, fs_low_ns, scl_fall_ns);
+
+        if self.timings.bus_freq_hz() == I2cSpeedMode::High {
+            self.hs_hcnt = scl_hcnt(ic_clk_khz, HS_HIGH_NS, sda_fall_ns);
+            self.hs_lcnt = scl_lcnt(ic_clk_khz, HS_LOW_NS, scl_fall_ns);
+        } else {
+            self.hs_hcnt = 0;
+            self.hs_lcnt = 0;
+        }
+
+        self.sda_hold_time = ns_to_ic_clk(ic_clk_khz, self.timings.sda_hold_ns());
+    }
+
     /// Gets the TX FIFO depth.
+    ///
+    /// Detected from the `IC_COMP_PARAM_1` register when the [I2c] is created.
     pub const fn tx_fifo_depth(&self) -> u32 {
         self.tx_fifo_depth
     }
 
     /// Gets the RX FIFO depth.
+    ///
+    /// Detected from the `IC_COMP_PARAM_1` register when the [I2c] is created.
     pub const fn rx_fifo_depth(&self) -> u32 {
         self.rx_fifo_depth
     }
 
+    /// Gets the number of bytes of the last [I2c::write_msg] that did not fit in the TX FIFO.
+    ///
+    /// Non-zero while [Status::WRITE_IN_PROGRESS] is set. Not updated by the `embedded-hal`
+    /// transfers.
+    pub const fn tx_buf_len(&self) -> usize {
+        self.tx_buf_len
+    }
+
+    /// Gets the number of received bytes left in the RX FIFO after the last [I2c::read_msg].
+    ///
+    /// Non-zero while [Status::READ_IN_PROGRESS] is set, i.e. when the read buffer was smaller
+    /// than the number of bytes received. Not updated by the `embedded-hal` transfers, which
+    /// receive exactly the requested bytes.
+    pub const fn rx_buf_len(&self) -> usize {
+        self.rx_buf_len
+    }
+
     /// Configures Tx/Rx FIFO thresholds, and sets the device to `master` mode.
     pub fn configure_fifo_master(&mut self) {
         let depth = self.tx_fifo_depth / 2;
@@ -167,6 +648,11 @@ impl<I2C: I2cPeripheral> I2c<I2C> {
         };
     }
 
+    // Gets the timeout for each stage of a transfer, see [I2c::timeout].
+    fn xfer_timeout_us(&self) -> u32 {
+        cmp::max(self.timeout_us, XFER_TIMEOUT_US)
+    }
+
     fn read_poll_timeout(
         &mut self,
         poll_fn: impl Fn(&mut dyn I2cPeripheral) -> bool,
@@ -206,9 +692,12 @@ impl<I2C: I2cPeripheral> I2c<I2C> {
 
         if abort_needed {
             self.i2c.set_enable(I2cEnable::ABORT);
-            if let Err(_err) =
-                self.read_poll_timeout(|i2c| i2c.get_enable().is_set(I2cEnable::ABORT), 10, 100)
-            {
+            if let Err(_err) = self.read_poll_timeout(
+                // `ABORT` is cleared by hardware once the abort completes
+                |i2c| !i2c.get_enable().is_set(I2cEnable::ABORT),
+                10,
+                self.timeout_us,
+            ) {
                 // FIXME: implement uart logging
                 // defmt_log!("timeout while trying to abort current transfer");
                 return;
@@ -220,7 +709,8 @@ impl<I2C: I2cPeripheral> I2c<I2C> {
 
         for _ in (0..timeout).rev() {
             self.__disable_nowait();
-            if self
+            // `IC_EN` is cleared once the adapter is disabled
+            if !self
                 .i2c
                 .get_enable_status()
                 .is_set(I2cEnableStatus::ACTIVITY)
@@ -273,16 +763,35 @@ impl<I2C: I2cPeripheral> I2c<I2C> {
         self.configure_fifo_master();
     }
 
+    /// Sets the target address for subsequent transfers.
+    ///
+    /// The adapter must be disabled to change the `TAR` register. If the adapter is enabled, and
+    /// `tar` is unchanged since the last transfer, the disable/re-enable sequence is skipped.
+    pub fn set_target_address(&mut self, tar: I2cTar) {
+        self.xfer_init(tar);
+    }
+
     /// Prepares the I2C peripheral for transfer(s).
+    ///
+    /// Skips re-initialization if the adapter is enabled, and `tar` is unchanged.
     pub fn xfer_init(&mut self, tar: I2cTar) {
+        if self.i2c.get_enable().is_set(I2cEnable::ENABLE)
+            && (self.i2c.get_tar() & I2cTar::MASK) == (tar & I2cTar::MASK)
+        {
+            // Clear stale interrupts from the previous transfer
+            let _ci = self.i2c.get_clear_interrupt();
+            return;
+        }
+
         // Disable the adapter.
         self.__disable();
 
-        let con = if tar.is_set(I2cTar::MODE_10BIT) {
-            I2cCon::MASTER_10BIT
-        } else {
-            I2cCon::NONE
-        };
+        // Preserve the master configuration (speed, RESTART_EN, SLAVE_DISABLE), only the
+        // addressing mode changes per target
+        let mut con = self.master_cfg.difference(I2cCon::MASTER_10BIT);
+        if tar.is_set(I2cTar::MODE_10BIT) {
+            con |= I2cCon::MASTER_10BIT;
+        }
 
         self.i2c.set_con(con);
         self.i2c.set_tar(tar);
@@ -334,6 +843,26 @@ impl<I2C: I2cPeripheral> I2c<I2C> {
             self.i2c.set_data_cmd(cmd | I2cDataCmd::from(data_byte));
         }
 
+        if self.tx_flag.is_set(I2cMsgFlag::IGNORE_NAK) {
+            // Wait for the queued bytes to go out, so a data NAK is cleared before it can abort
+            // the next message
+            self.read_poll_timeout(
+                |i2c| {
+                    let stat = i2c.get_raw_interrupt_stat();
+                    stat.is_set(I2cRawInterruptStatus::TX_EMPTY)
+                        || stat.is_set(I2cRawInterruptStatus::TX_ABRT)
+                },
+                10,
+                self.xfer_timeout_us(),
+            )?;
+
+            match self.check_abort() {
+                // The data NAK is cleared, and does not abort subsequent messages
+                Err(Error::NoAcknowledge(NoAcknowledgeSource::Data)) => (),
+                res => res?,
+            }
+        }
+
         if len > tx_limit || self.tx_flag.is_set(I2cMsgFlag::RECV_LEN) {
             self.status |= Status::WRITE_IN_PROGRESS;
             self.tx_outstanding = self.tx_outstanding.saturating_add(1);
@@ -355,10 +884,325 @@ impl<I2C: I2cPeripheral> I2c<I2C> {
         Ok(())
     }
 
+    /// Writes bytes to a device, then reads bytes back after a repeated START.
+    ///
+    /// The `write` bytes are followed by a RESTART, the `read` bytes, and a final STOP. This is
+    /// the common "write register address, then read register data" pattern.
+    ///
+    /// If SMBus PEC is enabled (see [I2c::with_pec]), the trailing PEC byte is read and verified.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{pac, i2c};
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let mut i2c0 = i2c::I2c::new(dp.I2C0);
+    ///
+    /// let addr = 0x50;
+    /// let reg = [0x00];
+    /// let mut data = [0u8; 4];
+    /// i2c0.write_read(addr, &reg, &mut data).unwrap();
+    /// ```
+    pub fn write_read(&mut self, addr: u8, write: &[u8], read: &mut [u8]) -> Result<()> {
+        self.xfer_polled(addr, write, read)
+    }
+
+    /// Probes whether a device responds at the 7-bit address `addr`.
+    ///
+    /// Performs a single-byte read, and checks for an address ACK.
+    ///
+    /// Returns:
+    ///
+    /// - `Ok(true)` if the device acknowledged its address
+    /// - `Ok(false)` if the address was not acknowledged
+    /// - `Err(Error)` on any other bus error
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{pac, i2c};
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let mut i2c0 = i2c::I2c::new(dp.I2C0);
+    ///
+    /// if i2c0.probe(0x50).unwrap() {
+    ///     // found an EEPROM
+    /// }
+    /// ```
+    pub fn probe(&mut self, addr: u8) -> Result<bool> {
+        // Probe with a plain read, without a PEC byte
+        let pec = self.pec;
+        self.pec = false;
+
+        let mut buf = [0u8; 1];
+        let res = self.xfer_polled(addr, &[], &mut buf);
+
+        self.pec = pec;
+
+        match res {
+            Ok(()) => Ok(true),
+            Err(Error::NoAcknowledge(NoAcknowledgeSource::Address)) => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Scans the bus for devices, probing the 7-bit addresses `0x08` to `0x77`.
+    ///
+    /// Returns the addresses of all devices that acknowledged a probe, like `i2cdetect`.
+    ///
+    /// The `DesignWare` adapter cannot issue a zero-length (address-only) write, so each address
+    /// is probed with a single-byte read, see [I2c::probe]. A NACK leaves the adapter ready for
+    /// the next probe. Addresses that fail with any other error are treated as not
+    /// responding, and the adapter is re-initialized before probing the next address.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{pac, i2c};
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let mut i2c0 = i2c::I2c::new(dp.I2C0);
+    ///
+    /// for _addr in i2c0.scan().iter() {
+    ///     // report responding addresses
+    /// }
+    /// ```
+    pub fn scan(&mut self) -> Vec<u8, 128> {
+        let mut found = Vec::new();
+
+        for addr in SCAN_ADDR_START..=SCAN_ADDR_END {
+            match self.probe(addr) {
+                Ok(true) => {
+                    // at most 112 addresses are probed, so this cannot overflow
+                    found.push(addr).ok();
+                }
+                Ok(false) => (),
+                // Don't let a stuck probe poison the rest of the scan
+                Err(_) => self.reinit(),
+            }
+        }
+
+        found
+    }
+
+    /// Sends a general call (address `0x00`) followed by the `data` bytes.
+    ///
+    /// Every device that supports general calls on the bus receives the data, e.g. `[0x06]`
+    /// requests a reset, and a reload of the programmable address bits. The general call always
+    /// uses 7-bit addressing.
+    ///
+    /// Returns [Error::NoAcknowledge] if no device acknowledged the general call.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{pac, i2c};
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let mut i2c0 = i2c::I2c::new(dp.I2C0);
+    ///
+    /// // Reset all devices supporting the general call
+    /// i2c0.general_call(&[0x06]).unwrap();
+    /// ```
+    pub fn general_call(&mut self, data: &[u8]) -> Result<()> {
+        let res = self.xfer_polled_tar(I2cTar::SPECIAL, data, &mut []);
+        self.recover_on_err(res)
+    }
+
+    // Performs a polled write, read, or write-then-read transfer.
+    //
+    // Appends a PEC byte to writes, and verifies the trailing PEC byte of reads, if enabled.
+    fn xfer_polled(&mut self, addr: u8, write: &[u8], read: &mut [u8]) -> Result<()> {
+        self.xfer_polled_tar(I2cTar::from(addr as u32), write, read)
+    }
+
+    // Performs a polled transfer to the 7-bit target `tar`, see [I2c::xfer_polled].
+    fn xfer_polled_tar(&mut self, tar: I2cTar, write: &[u8], read: &mut [u8]) -> Result<()> {
+        if write.is_empty() && read.is_empty() {
+            return Ok(());
+        }
+
+        let pec_len = usize::from(self.pec);
+        let (write_len, read_len) = if read.is_empty() {
+            (write.len().saturating_add(pec_len), 0)
+        } else {
+            (write.len(), read.len().saturating_add(pec_len))
+        };
+        let total = write_len.saturating_add(read_len);
+        let addr = tar.address_7bit();
+
+        // PEC covers every byte on the bus, including the address bytes
+        let mut pec = 0u8;
+        if self.pec {
+            if !write.is_empty() {
+                pec = smbus_pec(pec, &[addr << 1]);
+                pec = smbus_pec(pec, write);
+            }
+            if !read.is_empty() {
+                pec = smbus_pec(pec, &[(addr << 1) | 1]);
+            }
+        }
+
+        self.xfer_init(tar);
+
+        let depth = cmp::max(self.tx_fifo_depth, 1);
+        let mut rx_pec = 0u8;
+        let mut rx_idx = 0usize;
+
+        for i in 0..total {
+            let mut cmd = if let Some(data_byte) = write.get(i) {
+                I2cDataCmd::from(data_byte)
+            } else if i < write_len {
+                I2cDataCmd::from(pec)
+            } else {
+                I2cDataCmd::READ
+            };
+
+            // Switch direction with a repeated START
+            if i != 0 && i == write_len {
+                cmd |= I2cDataCmd::RESTART;
+            }
+
+            if i == total.saturating_sub(1) {
+                cmd |= I2cDataCmd::STOP;
+            }
+
+            let timeout_us = self.xfer_timeout_us();
+            self.read_poll_timeout(|i2c| i2c.get_txflr() < depth, 10, timeout_us)?;
+            self.i2c.set_data_cmd(cmd);
+
+            self.check_abort()?;
+            rx_idx = self.drain_rx(read, &mut rx_pec, rx_idx, read_len);
+        }
+
+        while rx_idx < read_len {
+            self.wait_rx()?;
+            rx_idx = self.drain_rx(read, &mut rx_pec, rx_idx, read_len);
+        }
+
+        self.wait_stop()?;
+
+        if self.pec && !read.is_empty() {
+            let expected = smbus_pec(pec, read);
+            if expected != rx_pec {
+                return Err(Error::Pec {
+                    expected,
+                    found: rx_pec,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    // Pushes a command into the TX FIFO, waiting for free space.
+    fn push_data_cmd(&mut self, cmd: I2cDataCmd) -> Result<()> {
+        let depth = cmp::max(self.tx_fifo_depth, 1);
+
+        let timeout_us = self.xfer_timeout_us();
+        self.read_poll_timeout(|i2c| i2c.get_txflr() < depth, 10, timeout_us)?;
+        self.i2c.set_data_cmd(cmd);
+
+        self.check_abort()
+    }
+
+    // Waits for, and pops a single byte from the RX FIFO.
+    fn pop_data(&mut self) -> Result<u8> {
+        self.wait_rx()?;
+        Ok(self.i2c.get_data_cmd().data())
+    }
+
+    // Waits for data in the RX FIFO, or an aborted transfer.
+    fn wait_rx(&mut self) -> Result<()> {
+        self.read_poll_timeout(
+            |i2c| {
+                i2c.get_rxflr() > 0
+                    || i2c
+                        .get_raw_interrupt_stat()
+                        .is_set(I2cRawInterruptStatus::TX_ABRT)
+            },
+            10,
+            self.xfer_timeout_us(),
+        )?;
+
+        self.check_abort()
+    }
+
+    // Waits for the STOP condition to complete a polled transfer.
+    fn wait_stop(&mut self) -> Result<()> {
+        self.read_poll_timeout(
+            |i2c| {
+                let stat = i2c.get_raw_interrupt_stat();
+                stat.is_set(I2cRawInterruptStatus::STOP_DET)
+                    || stat.is_set(I2cRawInterruptStatus::TX_ABRT)
+            },
+            10,
+            self.xfer_timeout_us(),
+        )?;
+        self.check_abort()?;
+        self.i2c.get_clear_stop_det();
+
+        Ok(())
+    }
+
+    // Reads available bytes from the RX FIFO into `buf`, starting at `idx`.
+    //
+    // Bytes past the end of `buf`, up to `len`, are stored in `tail`.
+    //
+    // Returns the index of the next byte to read.
+    fn drain_rx(&mut self, buf: &mut [u8], tail: &mut u8, mut idx: usize, len: usize) -> usize {
+        let avail = self.i2c.get_rxflr() as usize;
+
+        for _ in 0..cmp::min(avail, len.saturating_sub(idx)) {
+            let data = self.i2c.get_data_cmd().data();
+            match buf.get_mut(idx) {
+                Some(dst) => *dst = data,
+                None => *tail = data,
+            }
+            idx = idx.saturating_add(1);
+        }
+
+        idx
+    }
+
+    // Checks for an aborted transfer, clearing the abort interrupt.
+    fn check_abort(&mut self) -> Result<()> {
+        if self
+            .i2c
+            .get_raw_interrupt_stat()
+            .is_set(I2cRawInterruptStatus::TX_ABRT)
+        {
+            // The `TX_ABRT_SOURCE` register is cleared by reading `CLR_TX_ABRT`, read it first.
+            let src = self.i2c.get_tx_abort_source();
+            self.i2c.get_clear_tx_abort();
+
+            self.abort_result(src)
+        } else {
+            Ok(())
+        }
+    }
+
+    // Stores the abort source, and maps it to an [Error].
+    fn abort_result(&mut self, src: I2cTxAbortSource) -> Result<()> {
+        self.tx_abort_source = src;
+
+        Err(if src.is_set(I2cTxAbortSource::ARB_LOST) {
+            Error::ArbitrationLoss
+        } else if src.is_set(I2cTxAbortSource::B7_ADDR_NOACK)
+            || src.is_set(I2cTxAbortSource::B10_ADDR1_NOACK)
+            || src.is_set(I2cTxAbortSource::B10_ADDR2_NOACK)
+            || src.is_set(I2cTxAbortSource::GCALL_NOACK)
+        {
+            Error::NoAcknowledge(NoAcknowledgeSource::Address)
+        } else if src.is_set(I2cTxAbortSource::TXDATA_NOACK) {
+            Error::NoAcknowledge(NoAcknowledgeSource::Data)
+        } else {
+            Error::Bus
+        })
+    }
+
     /// Reads a message from the RX FIFO buffer.
     ///
-    /// **NOTE**: HAL users should check [I2c::status()] and [I2c::rx_fifo_depth()]
-    /// for any additional bytes that remain on the bus.
+    /// **NOTE**: HAL users should check [I2c::status()] and [I2c::rx_buf_len()]
+    /// for any additional bytes that remain on the bus. The `embedded-hal` transfers do not
+    /// use this function, and read exactly the requested bytes.
     ///
     /// Users should set [`Operation::Read`] buffers to have a length at least
     /// the RX FIFO buffer depth to avoid making multiple read calls.
@@ -368,27 +1212,38 @@ impl<I2C: I2cPeripheral> I2c<I2C> {
             return Err(Error::Overrun);
         }
 
-        let cmd = if self.master_cfg.is_set(I2cCon::RESTART_EN) {
+        let mut cmd = if self.master_cfg.is_set(I2cCon::RESTART_EN) {
             I2cDataCmd::READ | I2cDataCmd::RESTART
         } else {
             I2cDataCmd::READ
         };
 
+        // The controller only NAKs a received byte at the end of a transfer
+        if self.rx_flag.is_set(I2cMsgFlag::NO_RD_ACK) {
+            cmd |= I2cDataCmd::STOP;
+        }
+
         self.i2c.set_data_cmd(cmd);
         // Actual read happens in the interrupt handler I2c::isr() that calls
         // I2c::read(). This is because the peripheral fills an RX FIFO,
         // and interrupts when the FIFO is full.
         //
-        // Wait until the interrupt register indicates a full FIFO buffer.
+        // Wait until the interrupt register indicates a full FIFO buffer, or an aborted transfer.
         self.read_poll_timeout(
             |i2c| {
-                let (stat, _) = i2c.read_clear_interrupt();
-                stat.is_set(I2cInterruptStatus::RX_FULL)
+                let stat = i2c.get_raw_interrupt_stat();
+                stat.is_set(I2cRawInterruptStatus::RX_FULL)
+                    || stat.is_set(I2cRawInterruptStatus::TX_ABRT)
             },
             10,
-            100,
+            self.timeout_us,
         )?;
 
+        let (_stat, src) = self.i2c.read_clear_interrupt();
+        if src != I2cTxAbortSource::NONE {
+            self.abort_result(src)?;
+        }
+
         let rx_valid = self.i2c.get_rxflr() as usize;
 
         let len = cmp::min(buf.len(), rx_valid);
@@ -415,8 +1270,8 @@ impl<I2C: I2cPeripheral> I2c<I2C> {
 
         if rx_valid > len {
             // NOTE: HAL users should check I2c::status() and I2c::rx_buf_len()
-            // after I2c::read() calls for additional bytes that remain on the bus.
-            self.status &= Status::READ_IN_PROGRESS;
+            // after I2c::read_msg() calls for additional bytes that remain on the bus.
+            self.status |= Status::READ_IN_PROGRESS;
             self.rx_buf_len = rx_valid.saturating_sub(len);
         } else {
             self.status &= !Status::READ_IN_PROGRESS;
@@ -427,6 +1282,118 @@ impl<I2C: I2cPeripheral> I2c<I2C> {
     }
 }
 
+impl<I2C: I2cPeripheral> I2c<I2C> {
+    // Performs the `embedded-hal` transaction operations to the target address `tar`.
+    //
+    // Adjacent operations of the same direction are merged, a direction change issues a
+    // repeated START, and the last byte of the last (non-empty) operation issues the STOP.
+    fn transaction_ops(&mut self, tar: I2cTar, operations: &mut [Operation<'_>]) -> Result<()> {
+        self.xfer_init(tar);
+
+        let last = operations.iter().rposition(|op| match op {
+            Operation::Read(buf) => !buf.is_empty(),
+            Operation::Write(buf) => !buf.is_empty(),
+        });
+        let mut prev_read = None;
+
+        for (n, op) in operations.iter_mut().enumerate() {
+            let is_read = matches!(op, Operation::Read(_));
+            let len = match op {
+                Operation::Read(buf) => buf.len(),
+                Operation::Write(buf) => buf.len(),
+            };
+
+            let mut rx_idx = 0;
+            for i in 0..len {
+                let mut cmd = match op {
+                    Operation::Read(_) => I2cDataCmd::READ,
+                    Operation::Write(buf) => I2cDataCmd::from(&buf[i]),
+                };
+
+                // Switch direction with a repeated START
+                if i == 0 && prev_read.is_some_and(|prev| prev != is_read) {
+                    cmd |= I2cDataCmd::RESTART;
+                }
+                if Some(n) == last && i == len - 1 {
+                    cmd |= I2cDataCmd::STOP;
+                }
+
+                self.push_data_cmd(cmd)?;
+
+                if let Operation::Read(buf) = op {
+                    rx_idx = self.drain_rx(buf, &mut 0, rx_idx, len);
+                }
+            }
+
+            if let Operation::Read(buf) = op {
+                while rx_idx < len {
+                    self.wait_rx()?;
+                    rx_idx = self.drain_rx(buf, &mut 0, rx_idx, len);
+                }
+            }
+
+            if len > 0 {
+                prev_read = Some(is_read);
+            }
+        }
+
+        if last.is_none() {
+            return Ok(());
+        }
+
+        // Wait for the STOP condition, so a late NACK, or arbitration loss is reported
+        self.wait_stop()
+    }
+
+    // Re-initializes the controller after a bus error, or timeout, if auto-recovery is enabled.
+    fn recover_on_err<T>(&mut self, res: Result<T>) -> Result<T> {
+        if self.auto_recover && matches!(res, Err(Error::Bus | Error::Other)) {
+            self.reinit();
+        }
+
+        res
+    }
+
+    // Resets the transfer state, and re-initializes the adapter as a master.
+    fn reinit(&mut self) {
+        self.status = Status::NONE;
+        self.tx_outstanding = 0;
+        self.rx_outstanding = 0;
+        self.msg_err = 0;
+
+        self.init_master();
+    }
+}
+
+// Decodes the (TX, RX) FIFO depths from the `IC_COMP_PARAM_1` register value.
+//
+// Falls back to [DEFAULT_FIFO_DEPTH] if the component parameters are not implemented.
+const fn fifo_depths(param: u32) -> (u32, u32) {
+    if param == 0 {
+        (DEFAULT_FIFO_DEPTH, DEFAULT_FIFO_DEPTH)
+    } else {
+        (
+            ((param >> COMP_PARAM_1_TX_DEPTH_SHIFT) & COMP_PARAM_1_DEPTH_MASK) + 1,
+            ((param >> COMP_PARAM_1_RX_DEPTH_SHIFT) & COMP_PARAM_1_DEPTH_MASK) + 1,
+        )
+    }
+}
+
+impl<I2C: I2cPeripheral + CrgReset> Reset for I2c<I2C> {
+    fn reset(&mut self) {
+        I2C::pulse_reset();
+
+        self.status = Status::NONE;
+        self.tx_buf_len = 0;
+        self.rx_buf_len = 0;
+        self.tx_outstanding = 0;
+        self.rx_outstanding = 0;
+        self.msg_err = 0;
+
+        self.init_master();
+    }
+}
+
 impl<I2C: I2cPeripheral> i2c::ErrorType for I2c<I2C> {
     type Error = Error;
 }
@@ -434,47 +1401,30 @@ impl<I2C: I2cPeripheral> i2c::ErrorType for I2c<I2C> {
 impl<I2C: I2cPeripheral> I2cHal<SevenBitAddress> for I2c<I2C> {
     fn transaction(&mut self, address: u8, operations: &mut [Operation<'_>]) -> Result<()> {
         let tar = I2cTar::from(address as u32);
-        self.xfer_init(tar);
+        let res = self.transaction_ops(tar, operations);
+        self.recover_on_err(res)
+    }
 
-        let mut writes = operations
-            .iter()
-            .filter(|o| matches!(o, Operation::Write(_)))
-            .count();
-
-        for op in operations.iter_mut() {
-            match op {
-                Operation::Read(xfer) => self.read_msg(xfer)?,
-                Operation::Write(xfer) => {
-                    writes = writes.saturating_sub(1);
-                    self.write_msg(xfer, writes == 0)?;
-                }
-            }
-        }
+    fn read(&mut self, address: u8, read: &mut [u8]) -> Result<()> {
+        let res = self.xfer_polled(address, &[], read);
+        self.recover_on_err(res)
+    }
 
-        Ok(())
+    fn write(&mut self, address: u8, write: &[u8]) -> Result<()> {
+        let res = self.xfer_polled(address, write, &mut []);
+        self.recover_on_err(res)
+    }
+
+    fn write_read(&mut self, address: u8, write: &[u8], read: &mut [u8]) -> Result<()> {
+        let res = self.xfer_polled(address, write, read);
+        self.recover_on_err(res)
     }
 }
 
 impl<I2C: I2cPeripheral> I2cHal<TenBitAddress> for I2c<I2C> {
     fn transaction(&mut self, address: u16, operations: &mut [Operation<'_>]) -> Result<()> {
-        let tar = I2cTar::from(address as u32) | I2cTar::MODE_10BIT;
-        self.xfer_init(tar);
-
-        let mut writes = operations
-            .iter()
-            .filter(|o| matches!(o, Operation::Write(_)))
-            .count();
-
-        for op in operations.iter_mut() {
-            match op {
-                Operation::Read(xfer) => self.read_msg(xfer)?,
-                Operation::Write(xfer) => {
-                    writes = writes.saturating_sub(1);
-                    self.write_msg(xfer, writes == 0)?;
-                }
-            }
-        }
-
-        Ok(())
+        let tar = (I2cTar::from(address as u32) & I2cTar::ADDR_MASK_10BIT) | I2cTar::MODE_10BIT;
+        let res = self.transaction_ops(tar, operations);
+        self.recover_on_err(res)
     }
 }
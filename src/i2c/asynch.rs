@@ -0,0 +1,237 @@
+use core::cell::RefCell;
+use core::future::poll_fn;
+use core::task::{Poll, Waker};
+
+use critical_section::Mutex;
+use embedded_hal::i2c::{Operation, SevenBitAddress, TenBitAddress};
+use embedded_hal_async::i2c::I2c as I2cAsync;
+
+use crate::pac;
+
+use super::{
+    I2c, I2cDataCmd, I2cInterruptMask, I2cPeripheral, I2cRawInterruptStatus, I2cTar, Result,
+};
+
+/// Number of I2C peripherals serviced by [on_interrupt].
+pub const I2C_ASYNC_PERIPHERALS: usize = 7;
+
+static WAKERS: Mutex<RefCell<[Option<Waker>; I2C_ASYNC_PERIPHERALS]>> =
+    Mutex::new(RefCell::new([const { None }; I2C_ASYNC_PERIPHERALS]));
+
+// Gets the index of the I2C peripheral at `base_addr`, e.g. `0` for `I2C0`.
+//
+// Returns `None` for peripherals without an interrupt, e.g. `MockI2c`.
+fn peripheral_index(base_addr: usize) -> Option<usize> {
+    [
+        pac::I2C0::ptr() as usize,
+        pac::I2C1::ptr() as usize,
+        pac::I2C2::ptr() as usize,
+        pac::I2C3::ptr() as usize,
+        pac::I2C4::ptr() as usize,
+        pac::I2C5::ptr() as usize,
+        pac::I2C6::ptr() as usize,
+    ]
+    .iter()
+    .position(|&addr| addr == base_addr)
+}
+
+// Masks all interrupts of the I2C peripheral.
+fn mask_interrupts<I2C: I2cPeripheral>(mut i2c: I2C) {
+    i2c.set_interrupt_mask(I2cInterruptMask::NONE);
+}
+
+/// Services the interrupt of the I2C peripheral `n` (e.g. `0` for `I2C0`) for async transfers.
+///
+/// Masks the peripheral interrupts, and wakes the task waiting on the async transfer. The
+/// transfer unmasks the interrupts it waits on when polled again.
+///
+/// Call from the PLIC external interrupt handler for the I2C interrupt sources, e.g.
+/// [I2C0](crate::interrupt::plic::source::I2C0). Indices past [I2C_ASYNC_PERIPHERALS] are
+/// ignored.
+///
+/// Example:
+///
+/// ```no_run
+/// use jh71xx_hal::{i2c, interrupt::plic};
+///
+/// // Route the I2C0 interrupt to HART 1
+/// plic::set_priority(plic::source::I2C0, 1).unwrap();
+/// plic::set_threshold(1, 0).unwrap();
+/// plic::enable_source_on(1, plic::source::I2C0).unwrap();
+///
+/// // In the external interrupt handler
+/// if let Some(id) = plic::claim_on(1) {
+///     if id == plic::source::I2C0 {
+///         i2c::on_interrupt(0);
+///     }
+///     plic::complete_on(1, id).unwrap();
+/// }
+/// ```
+pub fn on_interrupt(n: usize) {
+    // SAFETY: only the interrupt mask is written, the waiting transfer unmasks the interrupts
+    // it waits on again.
+    unsafe {
+        match n {
+            0 => mask_interrupts(pac::I2C0::steal()),
+            1 => mask_interrupts(pac::I2C1::steal()),
+            2 => mask_interrupts(pac::I2C2::steal()),
+            3 => mask_interrupts(pac::I2C3::steal()),
+            4 => mask_interrupts(pac::I2C4::steal()),
+            5 => mask_interrupts(pac::I2C5::steal()),
+            6 => mask_interrupts(pac::I2C6::steal()),
+            _ => return,
+        }
+    }
+
+    if let Some(waker) = critical_section::with(|cs| WAKERS.borrow(cs).borrow_mut()[n].take()) {
+        waker.wake();
+    }
+}
+
+impl<I2C: I2cPeripheral> I2c<I2C> {
+    // Yields until the `irq` interrupt status is raised, or a transfer abort is raised.
+    //
+    // Registers the task waker, and unmasks the `irq`, and `TX_ABRT` interrupts, so the task is
+    // woken by [on_interrupt]. Peripherals without an interrupt (e.g. `MockI2c`) are polled
+    // instead.
+    async fn wait_async(&mut self, irq: I2cInterruptMask) -> Result<()> {
+        let index = peripheral_index(self.i2c.base_addr());
+        let done =
+            I2cRawInterruptStatus::from_bits_retain(irq.bits()) | I2cRawInterruptStatus::TX_ABRT;
+
+        poll_fn(|cx| {
+            if self.i2c.get_raw_interrupt_stat().intersects(done) {
+                return Poll::Ready(());
+            }
+
+            match index {
+                Some(n) => {
+                    critical_section::with(|cs| {
+                        WAKERS.borrow(cs).borrow_mut()[n] = Some(cx.waker().clone())
+                    });
+                    // Unmask after registering the waker, a status raised in between fires
+                    // the (level-triggered) interrupt once unmasked
+                    self.i2c.set_interrupt_mask(irq | I2cInterruptMask::TX_ABRT);
+                }
+                None => cx.waker().wake_by_ref(),
+            }
+
+            Poll::Pending
+        })
+        .await;
+
+        self.i2c.set_interrupt_mask(I2cInterruptMask::NONE);
+
+        self.check_abort()
+    }
+
+    // Performs the `embedded-hal-async` transaction operations to the target address `tar`.
+    async fn transaction_async(
+        &mut self,
+        tar: I2cTar,
+        operations: &mut [Operation<'_>],
+    ) -> Result<()> {
+        self.xfer_init(tar);
+        // Only the interrupts of the current wait are unmasked, see `wait_async`
+        self.i2c.set_interrupt_mask(I2cInterruptMask::NONE);
+
+        let depth = self.tx_fifo_depth.max(1);
+        let last = operations.iter().rposition(|op| match op {
+            Operation::Read(buf) => !buf.is_empty(),
+            Operation::Write(buf) => !buf.is_empty(),
+        });
+        let mut prev_read = None;
+
+        for (n, op) in operations.iter_mut().enumerate() {
+            let is_read = matches!(op, Operation::Read(_));
+            let len = match op {
+                Operation::Read(buf) => buf.len(),
+                Operation::Write(buf) => buf.len(),
+            };
+
+            let mut rx_idx = 0;
+            for i in 0..len {
+                let mut cmd = match op {
+                    Operation::Read(_) => I2cDataCmd::READ,
+                    Operation::Write(buf) => I2cDataCmd::from(&buf[i]),
+                };
+
+                // Switch direction with a repeated START
+                if i == 0 && prev_read.is_some_and(|prev| prev != is_read) {
+                    cmd |= I2cDataCmd::RESTART;
+                }
+                if Some(n) == last && i == len - 1 {
+                    cmd |= I2cDataCmd::STOP;
+                }
+
+                if self.i2c.get_txflr() >= depth {
+                    self.wait_async(I2cInterruptMask::TX_EMPTY).await?;
+                }
+                self.i2c.set_data_cmd(cmd);
+
+                if let Operation::Read(buf) = op {
+                    rx_idx = self.drain_rx(buf, &mut 0, rx_idx, len);
+                }
+            }
+
+            if let Operation::Read(buf) = op {
+                while rx_idx < len {
+                    self.wait_async(I2cInterruptMask::RX_FULL).await?;
+                    rx_idx = self.drain_rx(buf, &mut 0, rx_idx, len);
+                }
+            }
+
+            if len > 0 {
+                prev_read = Some(is_read);
+            }
+        }
+
+        if last.is_none() {
+            return Ok(());
+        }
+
+        // Wait for the STOP condition to complete the transfer
+        self.wait_async(I2cInterruptMask::STOP_DET).await?;
+        self.i2c.get_clear_stop_det();
+
+        Ok(())
+    }
+}
+
+/// Async I2C transfers, available with the `async` feature.
+///
+/// Waits on the `RX_FULL`, `TX_EMPTY`, and `STOP_DET` interrupt status yield to the executor
+/// until the I2C interrupt fires, so the hart can sleep (e.g. `wfi`) during transfers. Route
+/// the I2C interrupt through the PLIC, and call [on_interrupt] from its handler.
+///
+/// **NOTE**: the waits have no timeout, a transfer ends on `STOP`, or a transfer abort. Bound
+/// transfers on a stuck bus with the executor's timeouts.
+///
+/// Example:
+///
+/// ```no_run
+/// # use jh71xx_hal::{pac, i2c};
+/// use embedded_hal_async::i2c::I2c;
+///
+/// async fn read_temp(i2c0: &mut i2c::I2c<pac::I2C0>) -> [u8; 2] {
+///     let mut data = [0u8; 2];
+///     // Disambiguate from the blocking `I2c::write_read`
+///     I2c::write_read(i2c0, 0x48u8, &[0x00], &mut data).await.unwrap();
+///     data
+/// }
+/// ```
+impl<I2C: I2cPeripheral> I2cAsync<SevenBitAddress> for I2c<I2C> {
+    async fn transaction(&mut self, address: u8, operations: &mut [Operation<'_>]) -> Result<()> {
+        let tar = I2cTar::from(address as u32);
+        let res = self.transaction_async(tar, operations).await;
+        self.recover_on_err(res)
+    }
+}
+
+impl<I2C: I2cPeripheral> I2cAsync<TenBitAddress> for I2c<I2C> {
+    async fn transaction(&mut self, address: u16, operations: &mut [Operation<'_>]) -> Result<()> {
+        let tar = (I2cTar::from(address as u32) & I2cTar::ADDR_MASK_10BIT) | I2cTar::MODE_10BIT;
+        let res = self.transaction_async(tar, operations).await;
+        self.recover_on_err(res)
+    }
+}
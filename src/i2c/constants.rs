@@ -5,3 +5,29 @@ pub const MAX_HIGH_SPEED_MODE_FREQ: u32 = 3_400_000;
 
 /// Maximum byte value defined by the SMBus standard.
 pub const I2C_SMBUS_BLOCK_MAX: u8 = 32;
+
+/// Default timeout (in microseconds) for receiving data, and aborting transfers.
+pub const TIMEOUT_US: u32 = 100;
+
+/// Minimum timeout (in microseconds) for each stage of a transfer, longer timeouts are set with
+/// [I2c::set_timeout](super::I2c::set_timeout).
+pub const XFER_TIMEOUT_US: u32 = 10_000;
+
+/// FIFO depth used when `IC_COMP_PARAM_1` does not report the FIFO depths.
+pub const DEFAULT_FIFO_DEPTH: u32 = 16;
+/// `IC_COMP_PARAM_1` RX buffer depth field shift, the field holds the depth minus one.
+pub const COMP_PARAM_1_RX_DEPTH_SHIFT: u32 = 8;
+/// `IC_COMP_PARAM_1` TX buffer depth field shift, the field holds the depth minus one.
+pub const COMP_PARAM_1_TX_DEPTH_SHIFT: u32 = 16;
+/// `IC_COMP_PARAM_1` buffer depth field mask.
+pub const COMP_PARAM_1_DEPTH_MASK: u32 = 0xff;
+
+/// Maximum number of `SCL` pulses issued by [I2c::recover_bus](super::I2c::recover_bus).
+pub const RECOVERY_CLOCKS: usize = 9;
+/// Half-period (in microseconds) of the `SCL` pulses issued during bus recovery (~100 kHz).
+pub const RECOVERY_DELAY_US: u32 = 5;
+
+/// First 7-bit address probed by a bus scan, lower addresses are reserved.
+pub const SCAN_ADDR_START: u8 = 0x08;
+/// Last 7-bit address probed by a bus scan, higher addresses are reserved.
+pub const SCAN_ADDR_END: u8 = 0x77;
@@ -17,6 +17,10 @@ pub enum Error {
     NoAcknowledge(NoAcknowledgeSource),
     /// The peripheral receive buffer was overrun.
     Overrun,
+    /// SMBus PEC (packet error checking) mismatch on a read.
+    Pec { expected: u8, found: u8 },
+    /// Invalid GPIO pad number.
+    InvalidPad(u32),
     /// A different error occurred. The original error may contain more information.
     Other,
 }
@@ -28,6 +32,8 @@ impl From<&Error> for ErrorKind {
             Error::ArbitrationLoss => Self::ArbitrationLoss,
             Error::NoAcknowledge(src) => Self::NoAcknowledge(*src),
             Error::Overrun => Self::Overrun,
+            Error::Pec { .. } => Self::Other,
+            Error::InvalidPad(_) => Self::Other,
             Error::Other => Self::Other,
         }
     }
@@ -2,6 +2,8 @@ use heapless::Vec;
 
 use crate::bitflag_is_set;
 
+use super::{Error, I2c, I2cDataCmd, I2cPeripheral, I2cTar, Result, I2C_SMBUS_BLOCK_MAX};
+
 bitflags! {
     /// Flags for supported and optional features for [I2cMessage] transfers.
     ///
@@ -67,3 +69,304 @@ pub struct I2cMessage {
     flags: I2cMsgFlag,
     buf: I2cBuffer,
 }
+
+impl I2cMessage {
+    /// Creates a new, empty [I2cMessage].
+    pub const fn new() -> Self {
+        Self {
+            addr: 0,
+            flags: I2cMsgFlag::NONE,
+            buf: Vec::new(),
+        }
+    }
+
+    /// Creates a new write [I2cMessage] to the device at `addr`.
+    ///
+    /// `data` is truncated to [I2C_MSG_MAX] bytes.
+    pub fn write(addr: u16, data: &[u8]) -> Self {
+        let len = data.len().min(I2C_MSG_MAX);
+        let mut buf = I2cBuffer::new();
+        // the length is truncated above, so this cannot overflow
+        buf.extend_from_slice(&data[..len]).ok();
+
+        Self {
+            addr,
+            flags: I2cMsgFlag::NONE,
+            buf,
+        }
+    }
+
+    /// Creates a new read [I2cMessage] of `len` bytes from the device at `addr`.
+    ///
+    /// `len` is truncated to [I2C_MSG_MAX] bytes.
+    pub fn read(addr: u16, len: usize) -> Self {
+        let mut buf = I2cBuffer::new();
+        // the length is truncated, so this cannot overflow
+        buf.resize(len.min(I2C_MSG_MAX), 0).ok();
+
+        Self {
+            addr,
+            flags: I2cMsgFlag::RD,
+            buf,
+        }
+    }
+
+    /// Gets the device address.
+    pub const fn addr(&self) -> u16 {
+        self.addr
+    }
+
+    /// Sets the device address.
+    pub fn set_addr(&mut self, addr: u16) {
+        self.addr = addr;
+    }
+
+    /// Builder function that sets the device address.
+    pub fn with_addr(mut self, addr: u16) -> Self {
+        self.set_addr(addr);
+        self
+    }
+
+    /// Gets the [I2cMsgFlag] message flags.
+    pub const fn flags(&self) -> I2cMsgFlag {
+        self.flags
+    }
+
+    /// Sets the [I2cMsgFlag] message flags.
+    pub fn set_flags(&mut self, flags: I2cMsgFlag) {
+        self.flags = flags;
+    }
+
+    /// Builder function that sets the [I2cMsgFlag] message flags.
+    pub fn with_flags(mut self, flags: I2cMsgFlag) -> Self {
+        self.set_flags(flags);
+        self
+    }
+
+    /// Gets a reference to the message buffer.
+    pub fn buf(&self) -> &[u8] {
+        self.buf.as_slice()
+    }
+
+    /// Gets a mutable reference to the message buffer.
+    pub fn buf_mut(&mut self) -> &mut I2cBuffer {
+        &mut self.buf
+    }
+
+    /// Sets the message buffer.
+    pub fn set_buf(&mut self, buf: I2cBuffer) {
+        self.buf = buf;
+    }
+
+    /// Builder function that sets the message buffer.
+    pub fn with_buf(mut self, buf: I2cBuffer) -> Self {
+        self.set_buf(buf);
+        self
+    }
+
+    /// Gets the length of the message buffer.
+    pub fn len(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Gets whether the message buffer is empty.
+    pub fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+
+    /// Gets whether the message reads data from the device.
+    pub fn is_read(&self) -> bool {
+        self.flags.is_set(I2cMsgFlag::RD)
+    }
+
+    // Gets the `TAR` register value for the message address.
+    fn tar(&self) -> I2cTar {
+        let tar = I2cTar::from(self.addr as u32) & I2cTar::ADDR_MASK_10BIT;
+
+        if self.flags.is_set(I2cMsgFlag::TEN) {
+            tar | I2cTar::MODE_10BIT
+        } else {
+            tar
+        }
+    }
+}
+
+impl<I2C: I2cPeripheral> I2c<I2C> {
+    /// Transfers a sequence of [I2cMessage]s.
+    ///
+    /// Messages are joined by repeated START conditions, until a message with
+    /// [I2cMsgFlag::STOP], or the last message, ends the transfer with a STOP. Per-message
+    /// flags:
+    ///
+    /// - [I2cMsgFlag::RD]: read into the message buffer, otherwise write the buffer
+    /// - [I2cMsgFlag::TEN]: the message address is a 10-bit address
+    /// - [I2cMsgFlag::NOSTART]: continue the previous message without a repeated START
+    /// - [I2cMsgFlag::STOP]: end the transfer with a STOP after the message
+    /// - [I2cMsgFlag::RECV_LEN]: the first received byte is the SMBus block length, the buffer
+    ///   is resized to hold the length byte, and the block data
+    ///
+    /// Messages joined by repeated START must have the same address, otherwise
+    /// [Error::Other] is returned. The controller cannot transfer empty messages, these also
+    /// return [Error::Other]. Messages are checked before anything is sent on the bus.
+    ///
+    /// **NOTE** each [I2cMessage] holds an [I2C_MSG_MAX] byte buffer (64 KiB), so the two
+    /// messages in the example below take ~128 KiB of stack. Place larger message arrays in
+    /// `static` storage, or make sure the stack is sized to hold them.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{pac, i2c};
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let mut i2c0 = i2c::I2c::new(dp.I2C0);
+    ///
+    /// // Write the register address, then read the register data after a repeated START
+    /// let mut msgs = [
+    ///     i2c::I2cMessage::write(0x50, &[0x00]),
+    ///     i2c::I2cMessage::read(0x50, 4),
+    /// ];
+    /// i2c0.transfer_messages(msgs.as_mut()).unwrap();
+    ///
+    /// let _data = msgs[1].buf();
+    /// ```
+    pub fn transfer_messages(&mut self, msgs: &mut [I2cMessage]) -> Result<()> {
+        let res = self.transfer_messages_inner(msgs);
+        self.recover_on_err(res)
+    }
+
+    fn transfer_messages_inner(&mut self, msgs: &mut [I2cMessage]) -> Result<()> {
+        // Reject invalid messages up front, so the bus is never left mid-transfer
+        Self::validate_messages(msgs)?;
+
+        let last = msgs.len().saturating_sub(1);
+        // Target of the transfer in progress, if any
+        let mut current: Option<I2cTar> = None;
+
+        for (n, msg) in msgs.iter_mut().enumerate() {
+            let tar = msg.tar();
+            let restart = match current {
+                Some(_) => !msg.flags.is_set(I2cMsgFlag::NOSTART),
+                None => {
+                    self.xfer_init(tar);
+                    false
+                }
+            };
+            let stop = n == last || msg.flags.is_set(I2cMsgFlag::STOP);
+
+            if msg.is_read() {
+                self.read_message(msg, restart, stop)?;
+            } else {
+                self.write_message(msg, restart, stop)?;
+            }
+
+            if stop {
+                self.wait_stop()?;
+                current = None;
+            } else {
+                current = Some(tar);
+            }
+        }
+
+        Ok(())
+    }
+
+    // Checks that no message is empty, and that messages joined by repeated START share a target
+    fn validate_messages(msgs: &[I2cMessage]) -> Result<()> {
+        let last = msgs.len().saturating_sub(1);
+        let mut current: Option<I2cTar> = None;
+
+        for (n, msg) in msgs.iter().enumerate() {
+            if msg.is_empty() && !msg.flags.is_set(I2cMsgFlag::RECV_LEN) {
+                return Err(Error::Other);
+            }
+
+            let tar = msg.tar();
+            if current.is_some_and(|cur| cur != tar) {
+                return Err(Error::Other);
+            }
+
+            current = if n == last || msg.flags.is_set(I2cMsgFlag::STOP) {
+                None
+            } else {
+                Some(tar)
+            };
+        }
+
+        Ok(())
+    }
+
+    fn write_message(&mut self, msg: &I2cMessage, restart: bool, stop: bool) -> Result<()> {
+        let last = msg.len().saturating_sub(1);
+
+        for (i, data_byte) in msg.buf.iter().enumerate() {
+            let mut cmd = I2cDataCmd::from(data_byte);
+            if i == 0 && restart {
+                cmd |= I2cDataCmd::RESTART;
+            }
+            if i == last && stop {
+                cmd |= I2cDataCmd::STOP;
+            }
+
+            self.push_data_cmd(cmd)?;
+        }
+
+        Ok(())
+    }
+
+    fn read_message(&mut self, msg: &mut I2cMessage, restart: bool, stop: bool) -> Result<()> {
+        let mut restart = restart;
+        let mut start = 0usize;
+
+        if msg.flags.is_set(I2cMsgFlag::RECV_LEN) {
+            let cmd = if restart {
+                I2cDataCmd::READ | I2cDataCmd::RESTART
+            } else {
+                I2cDataCmd::READ
+            };
+            self.push_data_cmd(cmd)?;
+            let count = self.pop_data()?;
+
+            if count == 0 || count > I2C_SMBUS_BLOCK_MAX {
+                // Read another byte with STOP set to complete the transfer
+                self.push_data_cmd(I2cDataCmd::READ | I2cDataCmd::STOP)?;
+                self.pop_data()?;
+                self.wait_stop()?;
+
+                return Err(Error::Other);
+            }
+
+            msg.buf.clear();
+            // at most `I2C_SMBUS_BLOCK_MAX + 1` bytes, so this cannot overflow
+            msg.buf.resize(count as usize + 1, 0).ok();
+            msg.buf[0] = count;
+
+            restart = false;
+            start = 1;
+        }
+
+        let len = msg.len().saturating_sub(start);
+        let buf = &mut msg.buf[start..];
+        let mut tail = 0u8;
+        let mut rx_idx = 0usize;
+
+        for i in 0..len {
+            let mut cmd = I2cDataCmd::READ;
+            if i == 0 && restart {
+                cmd |= I2cDataCmd::RESTART;
+            }
+            if i == len.saturating_sub(1) && stop {
+                cmd |= I2cDataCmd::STOP;
+            }
+
+            self.push_data_cmd(cmd)?;
+            rx_idx = self.drain_rx(buf, &mut tail, rx_idx, len);
+        }
+
+        while rx_idx < len {
+            self.wait_rx()?;
+            rx_idx = self.drain_rx(buf, &mut tail, rx_idx, len);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,601 @@
+//! In-memory [I2cPeripheral] for testing [I2c](super::I2c) logic off-target.
+
+use core::cell::{Cell, RefCell};
+
+use heapless::{Deque, Vec};
+
+use super::{
+    I2cClearInterrupt, I2cCon, I2cDataCmd, I2cEnable, I2cEnableStatus, I2cInterruptMask,
+    I2cInterruptStatus, I2cPeripheral, I2cRawInterruptStatus, I2cSar, I2cStatus, I2cTar,
+    I2cTxAbortSource,
+};
+
+/// Maximum number of register writes recorded by [MockI2c].
+pub const MOCK_LOG_LEN: usize = 256;
+/// Depth of the [MockI2c] receive FIFO, and the queue of canned receive data.
+pub const MOCK_FIFO_DEPTH: usize = 64;
+
+/// Represents the `DesignWare` I2C registers written through [MockI2c].
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MockReg {
+    Con,
+    Tar,
+    Sar,
+    DataCmd,
+    SsSclHcnt,
+    SsSclLcnt,
+    FsSclHcnt,
+    FsSclLcnt,
+    HsSclHcnt,
+    HsSclLcnt,
+    IntrMask,
+    RxTl,
+    TxTl,
+    Enable,
+    SdaHold,
+}
+
+/// In-memory I2C peripheral, implementing [I2cPeripheral].
+///
+/// Register writes are recorded, and the `DesignWare` adapter behavior needed by the
+/// [I2c](super::I2c) driver is emulated:
+///
+/// - `DATA_CMD` commands transmit instantly, the TX FIFO is always empty
+/// - `READ` commands receive the next byte queued with [MockI2c::push_rx] (or `0`)
+/// - `STOP` commands raise `STOP_DET`
+/// - enabling, disabling, and aborting complete instantly
+///
+/// Bus errors are injected with [MockI2c::abort_next], and other interrupt conditions with
+/// [MockI2c::set_raw_interrupt_stat].
+///
+/// Only available with the `mock` feature.
+///
+/// Example:
+///
+/// ```
+/// use embedded_hal::i2c::{I2c as _, Operation};
+/// use jh71xx_hal::i2c::{self, I2cDataCmd};
+///
+/// let mock = i2c::MockI2c::new();
+/// mock.push_rx(&[0xaa, 0x55]);
+///
+/// let mut i2c = i2c::I2c::new(mock);
+/// let mut data = [0u8; 2];
+/// i2c.transaction(0x50u8, &mut [Operation::Write(&[0x10]), Operation::Read(&mut data)])
+///     .unwrap();
+/// assert_eq!(data, [0xaa, 0x55]);
+///
+/// let mock = i2c.free();
+/// assert_eq!(
+///     mock.data_cmds().as_slice(),
+///     [
+///         I2cDataCmd::from(0x10u8),
+///         I2cDataCmd::READ | I2cDataCmd::RESTART,
+///         I2cDataCmd::READ | I2cDataCmd::STOP,
+///     ]
+/// );
+///
+/// // Adjacent writes are merged, and a trailing empty read does not move the STOP
+/// mock.clear_log();
+/// let mut i2c = i2c::I2c::new(mock);
+/// i2c.transaction(
+///     0x50u8,
+///     &mut [
+///         Operation::Write(&[0x01]),
+///         Operation::Write(&[0x02]),
+///         Operation::Read(&mut []),
+///     ],
+/// )
+/// .unwrap();
+///
+/// let mock = i2c.free();
+/// assert_eq!(
+///     mock.data_cmds().as_slice(),
+///     [
+///         I2cDataCmd::from(0x01u8),
+///         I2cDataCmd::from(0x02u8) | I2cDataCmd::STOP,
+///     ]
+/// );
+/// ```
+pub struct MockI2c {
+    con: Cell<u32>,
+    tar: Cell<u32>,
+    sar: Cell<u32>,
+    tx_tl: Cell<u32>,
+    rx_tl: Cell<u32>,
+    ss_scl_hcnt: Cell<u32>,
+    ss_scl_lcnt: Cell<u32>,
+    fs_scl_hcnt: Cell<u32>,
+    fs_scl_lcnt: Cell<u32>,
+    hs_scl_hcnt: Cell<u32>,
+    hs_scl_lcnt: Cell<u32>,
+    sda_hold: Cell<u32>,
+    raw_intr: Cell<I2cRawInterruptStatus>,
+    intr_mask: Cell<I2cInterruptMask>,
+    enable: Cell<I2cEnable>,
+    abort_source: Cell<I2cTxAbortSource>,
+    next_abort: Cell<I2cTxAbortSource>,
+    comp_param_1: Cell<u32>,
+    rx_fifo: RefCell<Deque<u8, MOCK_FIFO_DEPTH>>,
+    rx_data: RefCell<Deque<u8, MOCK_FIFO_DEPTH>>,
+    data_cmds: RefCell<Vec<I2cDataCmd, MOCK_LOG_LEN>>,
+    writes: RefCell<Vec<(MockReg, u32), MOCK_LOG_LEN>>,
+}
+
+impl MockI2c {
+    /// Creates a new [MockI2c] with all registers cleared.
+    pub const fn new() -> Self {
+        Self {
+            con: Cell::new(0),
+            tar: Cell::new(0),
+            sar: Cell::new(0),
+            tx_tl: Cell::new(0),
+            rx_tl: Cell::new(0),
+            ss_scl_hcnt: Cell::new(0),
+            ss_scl_lcnt: Cell::new(0),
+            fs_scl_hcnt: Cell::new(0),
+            fs_scl_lcnt: Cell::new(0),
+            hs_scl_hcnt: Cell::new(0),
+            hs_scl_lcnt: Cell::new(0),
+            sda_hold: Cell::new(0),
+            raw_intr: Cell::new(I2cRawInterruptStatus::NONE),
+            intr_mask: Cell::new(I2cInterruptMask::NONE),
+            enable: Cell::new(I2cEnable::NONE),
+            abort_source: Cell::new(I2cTxAbortSource::NONE),
+            next_abort: Cell::new(I2cTxAbortSource::NONE),
+            comp_param_1: Cell::new(0),
+            rx_fifo: RefCell::new(Deque::new()),
+            rx_data: RefCell::new(Deque::new()),
+            data_cmds: RefCell::new(Vec::new()),
+            writes: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Builder function that sets the `IC_COMP_PARAM_1` register value, e.g. to emulate
+    /// different FIFO depths.
+    ///
+    /// Example:
+    ///
+    /// ```
+    /// use jh71xx_hal::i2c::{self, DEFAULT_FIFO_DEPTH};
+    ///
+    /// // Unimplemented component parameters fall back to the default depth
+    /// let i2c = i2c::I2c::new(i2c::MockI2c::new());
+    /// assert_eq!(i2c.tx_fifo_depth(), DEFAULT_FIFO_DEPTH);
+    /// assert_eq!(i2c.rx_fifo_depth(), DEFAULT_FIFO_DEPTH);
+    ///
+    /// // 8-byte TX / RX FIFOs, as reported by the JH7110 I2C controllers
+    /// let i2c = i2c::I2c::new(i2c::MockI2c::new().with_comp_param_1(0x0007_07ee));
+    /// assert_eq!(i2c.tx_fifo_depth(), 8);
+    /// assert_eq!(i2c.rx_fifo_depth(), 8);
+    ///
+    /// // The TX and RX depths are decoded from separate fields
+    /// let i2c = i2c::I2c::new(i2c::MockI2c::new().with_comp_param_1(0x001f_03ee));
+    /// assert_eq!(i2c.tx_fifo_depth(), 32);
+    /// assert_eq!(i2c.rx_fifo_depth(), 4);
+    /// ```
+    pub fn with_comp_param_1(self, val: u32) -> Self {
+        self.comp_param_1.set(val);
+        self
+    }
+
+    /// Queues `data` to be received by subsequent `READ` commands.
+    ///
+    /// Bytes past [MOCK_FIFO_DEPTH] queued bytes are dropped.
+    pub fn push_rx(&self, data: &[u8]) {
+        let mut rx_data = self.rx_data.borrow_mut();
+        for &b in data {
+            rx_data.push_back(b).ok();
+        }
+    }
+
+    /// Aborts the next `DATA_CMD` command with the [I2cTxAbortSource] `src`.
+    ///
+    /// The command is not transmitted, `TX_ABRT` is raised, and `TX_ABRT_SOURCE` reports `src`
+    /// until the abort is cleared.
+    pub fn abort_next(&self, src: I2cTxAbortSource) {
+        self.next_abort.set(src);
+    }
+
+    /// Sets additional raw interrupt status bits, e.g. `MST_ON_HOLD`.
+    pub fn set_raw_interrupt_stat(&self, stat: I2cRawInterruptStatus) {
+        self.raw_intr.set(self.raw_intr.get() | stat);
+    }
+
+    /// Gets the recorded `DATA_CMD` commands.
+    pub fn data_cmds(&self) -> Vec<I2cDataCmd, MOCK_LOG_LEN> {
+        self.data_cmds.borrow().clone()
+    }
+
+    /// Gets the recorded register writes, in order.
+    ///
+    /// Only the first [MOCK_LOG_LEN] writes are recorded.
+    ///
+    /// Example:
+    ///
+    /// ```
+    /// use jh71xx_hal::i2c::{self, MockReg};
+    ///
+    /// let mut i2c = i2c::I2c::new(i2c::MockI2c::new())
+    ///     .with_timings(i2c::I2cTimings::for_speed(i2c::I2cSpeedMode::Fast));
+    /// i2c.compute_timings(50_000_000);
+    /// i2c.init_master();
+    ///
+    /// let mock = i2c.free();
+    /// let written = |reg| {
+    ///     mock.writes()
+    ///         .iter()
+    ///         .rev()
+    ///         .find(|&&(r, _)| r == reg)
+    ///         .map(|&(_, val)| val)
+    /// };
+    ///
+    /// // SCL counts for a 50 MHz `IC_CLK`, matching the Linux `DesignWare` driver
+    /// assert_eq!(written(MockReg::SsSclHcnt), Some(212));
+    /// assert_eq!(written(MockReg::SsSclLcnt), Some(249));
+    /// assert_eq!(written(MockReg::FsSclHcnt), Some(42));
+    /// assert_eq!(written(MockReg::FsSclLcnt), Some(79));
+    /// ```
+    pub fn writes(&self) -> Vec<(MockReg, u32), MOCK_LOG_LEN> {
+        self.writes.borrow().clone()
+    }
+
+    /// Clears the recorded `DATA_CMD` commands, and register writes.
+    ///
+    /// Example:
+    ///
+    /// ```
+    /// use embedded_hal::i2c::{I2c as _, SevenBitAddress, TenBitAddress};
+    /// use jh71xx_hal::i2c::{self, I2cCon, MockReg};
+    ///
+    /// let mut i2c = i2c::I2c::new(i2c::MockI2c::new())
+    ///     .with_timings(i2c::I2cTimings::for_speed(i2c::I2cSpeedMode::Standard));
+    /// i2c.configure_master();
+    /// i2c.init_master();
+    ///
+    /// let mock = i2c.free();
+    /// mock.clear_log();
+    ///
+    /// let mut i2c = i2c::I2c::new(mock);
+    /// i2c.configure_master();
+    /// i2c.write(0x150 as TenBitAddress, &[0x00]).unwrap();
+    /// i2c.write(0x50 as SevenBitAddress, &[0x00]).unwrap();
+    ///
+    /// // Each `CON` write keeps the configured speed, only the addressing mode changes
+    /// let mock = i2c.free();
+    /// let cons = mock
+    ///     .writes()
+    ///     .iter()
+    ///     .filter(|&&(reg, _)| reg == MockReg::Con)
+    ///     .map(|&(_, val)| I2cCon::from_bits_retain(val))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(cons.len(), 2);
+    /// assert!(cons.iter().all(|con| con.contains(I2cCon::SPEED_STD | I2cCon::MASTER)));
+    /// assert!(cons[0].contains(I2cCon::MASTER_10BIT));
+    /// assert!(!cons[1].contains(I2cCon::MASTER_10BIT));
+    /// ```
+    pub fn clear_log(&self) {
+        self.data_cmds.borrow_mut().clear();
+        self.writes.borrow_mut().clear();
+    }
+
+    // Records a write of `val` to the register `reg`.
+    fn record(&self, reg: MockReg, val: u32) {
+        self.writes.borrow_mut().push((reg, val)).ok();
+    }
+
+    // Clears the raw interrupt status bits `stat`.
+    fn clear_raw(&self, stat: I2cRawInterruptStatus) {
+        self.raw_intr.set(self.raw_intr.get().difference(stat));
+    }
+}
+
+impl Default for MockI2c {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl I2cPeripheral for MockI2c {
+    fn base_addr(&self) -> usize {
+        0
+    }
+
+    fn get_con(&self) -> I2cCon {
+        I2cCon::from(self.con.get())
+    }
+
+    fn set_con(&mut self, val: I2cCon) {
+        self.record(MockReg::Con, val.bits());
+        self.con.set(val.bits());
+    }
+
+    fn get_tar(&self) -> I2cTar {
+        I2cTar::from(self.tar.get())
+    }
+
+    fn set_tar(&mut self, val: I2cTar) {
+        self.record(MockReg::Tar, val.bits());
+        self.tar.set(val.bits());
+    }
+
+    fn get_sar(&self) -> I2cSar {
+        I2cSar::from(self.sar.get())
+    }
+
+    fn set_sar(&mut self, val: I2cSar) {
+        self.record(MockReg::Sar, val.bits());
+        self.sar.set(val.bits());
+    }
+
+    fn get_tx_tl(&self) -> u32 {
+        self.tx_tl.get()
+    }
+
+    fn set_tx_tl(&mut self, val: u32) {
+        self.record(MockReg::TxTl, val);
+        self.tx_tl.set(val);
+    }
+
+    fn get_rx_tl(&self) -> u32 {
+        self.rx_tl.get()
+    }
+
+    fn set_rx_tl(&mut self, val: u32) {
+        self.record(MockReg::RxTl, val);
+        self.rx_tl.set(val);
+    }
+
+    fn get_ss_scl_hcnt(&self) -> u32 {
+        self.ss_scl_hcnt.get()
+    }
+
+    fn set_ss_scl_hcnt(&mut self, val: u32) {
+        self.record(MockReg::SsSclHcnt, val);
+        self.ss_scl_hcnt.set(val);
+    }
+
+    fn get_ss_scl_lcnt(&self) -> u32 {
+        self.ss_scl_lcnt.get()
+    }
+
+    fn set_ss_scl_lcnt(&mut self, val: u32) {
+        self.record(MockReg::SsSclLcnt, val);
+        self.ss_scl_lcnt.set(val);
+    }
+
+    fn get_fs_scl_hcnt(&self) -> u32 {
+        self.fs_scl_hcnt.get()
+    }
+
+    fn set_fs_scl_hcnt(&mut self, val: u32) {
+        self.record(MockReg::FsSclHcnt, val);
+        self.fs_scl_hcnt.set(val);
+    }
+
+    fn get_fs_scl_lcnt(&self) -> u32 {
+        self.fs_scl_lcnt.get()
+    }
+
+    fn set_fs_scl_lcnt(&mut self, val: u32) {
+        self.record(MockReg::FsSclLcnt, val);
+        self.fs_scl_lcnt.set(val);
+    }
+
+    fn get_hs_scl_hcnt(&self) -> u32 {
+        self.hs_scl_hcnt.get()
+    }
+
+    fn set_hs_scl_hcnt(&mut self, val: u32) {
+        self.record(MockReg::HsSclHcnt, val);
+        self.hs_scl_hcnt.set(val);
+    }
+
+    fn get_hs_scl_lcnt(&self) -> u32 {
+        self.hs_scl_lcnt.get()
+    }
+
+    fn set_hs_scl_lcnt(&mut self, val: u32) {
+        self.record(MockReg::HsSclLcnt, val);
+        self.hs_scl_lcnt.set(val);
+    }
+
+    fn get_sda_hold(&self) -> u32 {
+        self.sda_hold.get()
+    }
+
+    fn set_sda_hold(&mut self, val: u32) {
+        self.record(MockReg::SdaHold, val);
+        self.sda_hold.set(val);
+    }
+
+    fn get_raw_interrupt_stat(&self) -> I2cRawInterruptStatus {
+        let mut stat = self.raw_intr.get() | I2cRawInterruptStatus::TX_EMPTY;
+        if !self.rx_fifo.borrow().is_empty() {
+            stat |= I2cRawInterruptStatus::RX_FULL;
+        }
+        stat
+    }
+
+    fn get_interrupt_stat(&self) -> I2cInterruptStatus {
+        let stat = self.get_raw_interrupt_stat().bits() & self.intr_mask.get().bits();
+        I2cInterruptStatus::from(stat)
+    }
+
+    fn get_interrupt_mask(&self) -> I2cInterruptMask {
+        self.intr_mask.get()
+    }
+
+    fn set_interrupt_mask(&mut self, val: I2cInterruptMask) {
+        self.record(MockReg::IntrMask, val.bits());
+        self.intr_mask.set(val);
+    }
+
+    fn get_clear_interrupt(&self) -> I2cClearInterrupt {
+        let stat = self.raw_intr.get();
+        self.raw_intr.set(I2cRawInterruptStatus::NONE);
+        self.abort_source.set(I2cTxAbortSource::NONE);
+        I2cClearInterrupt::from(stat.bits())
+    }
+
+    fn set_clear_interrupt(&mut self, _val: I2cClearInterrupt) {}
+
+    fn get_clear_rx_under(&self) -> u32 {
+        self.clear_raw(I2cRawInterruptStatus::RX_UNDER);
+        0
+    }
+
+    fn get_clear_rx_over(&self) -> u32 {
+        self.clear_raw(I2cRawInterruptStatus::RX_OVER);
+        0
+    }
+
+    fn get_clear_tx_over(&self) -> u32 {
+        self.clear_raw(I2cRawInterruptStatus::TX_OVER);
+        0
+    }
+
+    fn get_clear_rd_req(&self) -> u32 {
+        self.clear_raw(I2cRawInterruptStatus::RD_REQ);
+        0
+    }
+
+    fn get_clear_tx_abort(&self) -> u32 {
+        self.clear_raw(I2cRawInterruptStatus::TX_ABRT);
+        self.abort_source.set(I2cTxAbortSource::NONE);
+        0
+    }
+
+    fn get_clear_rx_done(&self) -> u32 {
+        self.clear_raw(I2cRawInterruptStatus::RX_DONE);
+        0
+    }
+
+    fn get_clear_activity(&self) -> u32 {
+        self.clear_raw(I2cRawInterruptStatus::ACTIVITY);
+        0
+    }
+
+    fn get_clear_stop_det(&self) -> u32 {
+        self.clear_raw(I2cRawInterruptStatus::STOP_DET);
+        0
+    }
+
+    fn get_clear_start_det(&self) -> u32 {
+        self.clear_raw(I2cRawInterruptStatus::START_DET);
+        0
+    }
+
+    fn get_clear_gen_call(&self) -> u32 {
+        self.clear_raw(I2cRawInterruptStatus::GEN_CALL);
+        0
+    }
+
+    fn get_enable(&self) -> I2cEnable {
+        self.enable.get()
+    }
+
+    fn set_enable(&mut self, val: I2cEnable) {
+        self.record(MockReg::Enable, val.bits());
+
+        // Aborts complete instantly, and flush the TX FIFO
+        if val.is_set(I2cEnable::ABORT) {
+            self.clear_raw(I2cRawInterruptStatus::MST_ON_HOLD);
+            self.raw_intr
+                .set(self.raw_intr.get() | I2cRawInterruptStatus::TX_ABRT);
+        }
+        if !val.is_set(I2cEnable::ENABLE) {
+            self.rx_fifo.borrow_mut().clear();
+        }
+
+        self.enable.set(val.difference(I2cEnable::ABORT));
+    }
+
+    fn get_enable_status(&self) -> I2cEnableStatus {
+        if self.enable.get().is_set(I2cEnable::ENABLE) {
+            I2cEnableStatus::ACTIVITY
+        } else {
+            I2cEnableStatus::NONE
+        }
+    }
+
+    fn set_enable_status(&mut self, val: I2cEnableStatus) {
+        self.set_enable(I2cEnable::from(val.bits()));
+    }
+
+    fn get_txflr(&self) -> u32 {
+        0
+    }
+
+    fn set_txflr(&mut self, _val: u32) {}
+
+    fn get_rxflr(&self) -> u32 {
+        self.rx_fifo.borrow().len() as u32
+    }
+
+    fn set_rxflr(&mut self, _val: u32) {}
+
+    fn get_data_cmd(&self) -> I2cDataCmd {
+        let data = self.rx_fifo.borrow_mut().pop_front();
+
+        match data {
+            Some(b) => I2cDataCmd::from(b),
+            None => {
+                self.set_raw_interrupt_stat(I2cRawInterruptStatus::RX_UNDER);
+                I2cDataCmd::NONE
+            }
+        }
+    }
+
+    fn set_data_cmd(&mut self, val: I2cDataCmd) {
+        self.record(MockReg::DataCmd, val.bits());
+        self.data_cmds.borrow_mut().push(val).ok();
+
+        let abort = self.next_abort.replace(I2cTxAbortSource::NONE);
+        if abort != I2cTxAbortSource::NONE {
+            self.abort_source.set(abort);
+            self.set_raw_interrupt_stat(
+                I2cRawInterruptStatus::TX_ABRT | I2cRawInterruptStatus::STOP_DET,
+            );
+            return;
+        }
+
+        if val.is_set(I2cDataCmd::READ) {
+            let data = self.rx_data.borrow_mut().pop_front().unwrap_or(0);
+            if self.rx_fifo.borrow_mut().push_back(data).is_err() {
+                self.set_raw_interrupt_stat(I2cRawInterruptStatus::RX_OVER);
+            }
+        }
+        if val.is_set(I2cDataCmd::STOP) {
+            self.set_raw_interrupt_stat(I2cRawInterruptStatus::STOP_DET);
+        }
+    }
+
+    fn get_tx_abort_source(&self) -> I2cTxAbortSource {
+        self.abort_source.get()
+    }
+
+    fn get_status(&self) -> I2cStatus {
+        // The TX FIFO drains instantly
+        let mut status = I2cStatus::TFNF | I2cStatus::TFE;
+        let rx_fifo = self.rx_fifo.borrow();
+
+        if self.enable.get().is_set(I2cEnable::ENABLE) {
+            status |= I2cStatus::ACTIVITY | I2cStatus::MASTER_ACTIVITY;
+        }
+        if !rx_fifo.is_empty() {
+            status |= I2cStatus::RFNE;
+        }
+        if rx_fifo.is_full() {
+            status |= I2cStatus::RFF;
+        }
+
+        status
+    }
+
+    fn get_comp_param_1(&self) -> u32 {
+        self.comp_param_1.get()
+    }
+}
@@ -0,0 +1,42 @@
+/// SMBus PEC CRC-8 polynomial: `x^8 + x^2 + x + 1`
+pub const SMBUS_PEC_POLY: u8 = 0x07;
+
+/// Computes the SMBus PEC (packet error checking) CRC-8 over `data`.
+///
+/// `crc` is the running PEC value, use `0` to start a new computation.
+///
+/// Example:
+///
+/// ```
+/// # use jh71xx_hal::i2c;
+/// // CRC-8/SMBUS check value
+/// assert_eq!(i2c::smbus_pec(0, b"123456789"), 0xf4);
+///
+/// // PEC can be computed incrementally
+/// let pec = i2c::smbus_pec(0, b"1234");
+/// assert_eq!(i2c::smbus_pec(pec, b"56789"), 0xf4);
+///
+/// let addr = 0x0b;
+/// let pec = i2c::smbus_pec(0, &[addr << 1, 0x09]);
+/// let _pec = i2c::smbus_pec(pec, &[(addr << 1) | 1]);
+/// ```
+pub const fn smbus_pec(mut crc: u8, data: &[u8]) -> u8 {
+    let mut i = 0;
+    while i < data.len() {
+        crc ^= data[i];
+
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 0x80 != 0 {
+                (crc << 1) ^ SMBUS_PEC_POLY
+            } else {
+                crc << 1
+            };
+            bit += 1;
+        }
+
+        i += 1;
+    }
+
+    crc
+}
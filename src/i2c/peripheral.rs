@@ -5,6 +5,9 @@ use super::registers::*;
 /// Generic access for Synopsis Designware I2C peripherals.
 // FIXME: add `modify_*` methods to only modify set bitfields.
 pub trait I2cPeripheral {
+    /// Gets the MMIO base address of the I2C peripheral.
+    fn base_addr(&self) -> usize;
+
     fn get_con(&self) -> I2cCon;
     fn set_con(&mut self, val: I2cCon);
 
@@ -38,6 +41,7 @@ pub trait I2cPeripheral {
     fn get_hs_scl_lcnt(&self) -> u32;
     fn set_hs_scl_lcnt(&mut self, val: u32);
 
+    /// Gets the `IC_SDA_HOLD` register value.
     fn get_sda_hold(&self) -> u32;
     fn set_sda_hold(&mut self, val: u32);
 
@@ -62,6 +66,9 @@ pub trait I2cPeripheral {
     fn get_clear_start_det(&self) -> u32;
     fn get_clear_gen_call(&self) -> u32;
 
+    /// Gets the `IC_ENABLE` register value.
+    ///
+    /// The `ABORT` bit is self-clearing, and is polled while disabling the adapter.
     fn get_enable(&self) -> I2cEnable;
     fn set_enable(&mut self, val: I2cEnable);
 
@@ -79,6 +86,11 @@ pub trait I2cPeripheral {
 
     fn get_tx_abort_source(&self) -> I2cTxAbortSource;
 
+    /// Gets the I2C `STATUS` register, FIFO, and activity status flags.
+    fn get_status(&self) -> I2cStatus;
+
+    fn get_comp_param_1(&self) -> u32;
+
     fn read_clear_interrupt(&self) -> (I2cInterruptStatus, I2cTxAbortSource) {
         // The `INTR_STAT` register just indicates "enabled" interrupts.
         // The unmasked raw version of interrupt status bits is available
@@ -141,6 +153,10 @@ pub trait I2cPeripheral {
 macro_rules! impl_i2c_peripheral {
     ($i2c:ident) => {
         impl $crate::i2c::I2cPeripheral for $i2c {
+            fn base_addr(&self) -> usize {
+                Self::ptr() as usize
+            }
+
             fn get_con(&self) -> I2cCon {
                 I2cCon::from(self.con().read().bits())
             }
@@ -236,7 +252,7 @@ macro_rules! impl_i2c_peripheral {
             }
 
             fn get_sda_hold(&self) -> u32 {
-                self.hs_scl_lcnt().read().bits()
+                self.sda_hold().read().bits()
             }
 
             fn set_sda_hold(&mut self, val: u32) {
@@ -312,7 +328,7 @@ macro_rules! impl_i2c_peripheral {
             }
 
             fn get_enable(&self) -> I2cEnable {
-                I2cEnable::from(self.clr_gen_call().read().bits())
+                I2cEnable::from(self.enable().read().bits())
             }
 
             /// SAFETY: setting register bits is safe because of guarantees made by the
@@ -360,6 +376,14 @@ macro_rules! impl_i2c_peripheral {
             fn get_tx_abort_source(&self) -> I2cTxAbortSource {
                 I2cTxAbortSource::from(self.tx_abrt_source().read().bits())
             }
+
+            fn get_status(&self) -> I2cStatus {
+                I2cStatus::from(self.status().read().bits())
+            }
+
+            fn get_comp_param_1(&self) -> u32 {
+                self.comp_param_1().read().bits()
+            }
         }
     };
 }
@@ -229,8 +229,13 @@ bitflags! {
         const NONE = 0b0000_0000_0000_0000;
         const ADDR_MASK_7BIT = 0b0000_0000_0111_1111;
         const ADDR_MASK_10BIT = 0b0000_0011_1111_1111;
+        /// With [I2cTar::SPECIAL], sends a START BYTE instead of a general call.
+        const GC_OR_START = 0b0000_0100_0000_0000;
+        /// Sends a general call, or START BYTE (see [I2cTar::GC_OR_START]) instead of the
+        /// target address.
+        const SPECIAL = 0b0000_1000_0000_0000;
         const MODE_10BIT = 0b0001_0000_0000_0000;
-        const MASK = 0b0001_0011_1111_1111;
+        const MASK = 0b0001_1111_1111_1111;
     }
 }
 
@@ -315,6 +320,28 @@ bitflags! {
 bitflag_is_set!(I2cEnableStatus);
 bitflag_from_u32!(I2cEnableStatus);
 
+/// Represents I2C `STATUS` register bitfield.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct I2cStatus(u32);
+
+bitflags! {
+    impl I2cStatus: u32 {
+        const NONE = 0b0000_0000;
+        const ACTIVITY = 0b0000_0001;
+        const TFNF = 0b0000_0010;
+        const TFE = 0b0000_0100;
+        const RFNE = 0b0000_1000;
+        const RFF = 0b0001_0000;
+        const MASTER_ACTIVITY = 0b0010_0000;
+        const SLAVE_ACTIVITY = 0b0100_0000;
+        const MASK = 0b0111_1111;
+    }
+}
+
+bitflag_is_set!(I2cStatus);
+bitflag_from_u32!(I2cStatus);
+
 /// Represents I2C functionality bitfield.
 #[repr(C)]
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -401,6 +428,26 @@ impl I2cDataCmd {
         self.set_data(val);
         self
     }
+
+    /// Builder function that sets the `READ` command bit.
+    pub const fn read(self) -> Self {
+        self.union(Self::READ)
+    }
+
+    /// Builder function that sets the `STOP` command bit.
+    pub const fn stop(self) -> Self {
+        self.union(Self::STOP)
+    }
+
+    /// Builder function that sets the `RESTART` command bit.
+    pub const fn restart(self) -> Self {
+        self.union(Self::RESTART)
+    }
+
+    /// Builder function that sets the `FIRST_DATA_BYTE` command bit.
+    pub const fn first_data_byte(self) -> Self {
+        self.union(Self::FIRST_DATA_BYTE)
+    }
 }
 
 impl From<u8> for I2cDataCmd {
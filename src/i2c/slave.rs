@@ -0,0 +1,164 @@
+use crate::bitflag_is_set;
+
+use super::{
+    I2c, I2cCon, I2cDataCmd, I2cEnableStatus, I2cFunc, I2cInterruptMask, I2cInterruptStatus,
+    I2cOpMode, I2cPeripheral, I2cSar, I2cSpeedMode,
+};
+
+/// Highest 7-bit slave address.
+pub const I2C_SLAVE_ADDR_7BIT_MAX: u16 = 0x7f;
+
+bitflags! {
+    /// Events serviced by [I2c::slave_poll].
+    #[repr(C)]
+    #[derive(Clone, Copy, Debug, Default, PartialEq)]
+    pub struct I2cSlaveEvent: u32 {
+        const NONE = 0b0000_0000;
+        /// The master wrote bytes to the slave.
+        const WRITE_RECEIVED = 0b0000_0001;
+        /// The master requested a byte, respond with [I2c::slave_write].
+        const READ_REQUESTED = 0b0000_0010;
+        /// The master did not acknowledge the last transmitted byte, ending the read.
+        const READ_DONE = 0b0000_0100;
+        /// A STOP condition was detected on the bus.
+        const STOP = 0b0000_1000;
+        /// The receive FIFO overflowed, and received bytes were lost.
+        const OVERRUN = 0b0001_0000;
+        /// The transmit FIFO was flushed by a slave transmit abort.
+        const TX_ABORT = 0b0010_0000;
+        const MASK = 0b0011_1111;
+    }
+}
+
+bitflag_is_set!(I2cSlaveEvent);
+
+impl<I2C: I2cPeripheral> I2c<I2C> {
+    /// Configures, and enables the I2C peripheral for `slave` operation mode.
+    ///
+    /// Addresses above [I2C_SLAVE_ADDR_7BIT_MAX] are configured as 10-bit addresses. Use
+    /// [I2c::configure_slave_10bit] for 10-bit addresses in the 7-bit range.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{pac, i2c};
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let mut i2c0 = i2c::I2c::new(dp.I2C0);
+    ///
+    /// // Emulate an EEPROM at address 0x50
+    /// i2c0.configure_slave(0x50);
+    ///
+    /// let mut buf = [0u8; 16];
+    /// loop {
+    ///     let (event, len) = i2c0.slave_poll(buf.as_mut());
+    ///     if event.is_set(i2c::I2cSlaveEvent::READ_REQUESTED) {
+    ///         i2c0.slave_write(0xff);
+    ///     }
+    ///     // ... handle the `len` received bytes ...
+    /// #   let _ = len;
+    /// }
+    /// ```
+    pub fn configure_slave(&mut self, addr: u16) {
+        self.configure_slave_inner(addr, addr > I2C_SLAVE_ADDR_7BIT_MAX);
+    }
+
+    /// Configures, and enables the I2C peripheral for `slave` operation mode with a 10-bit
+    /// address.
+    pub fn configure_slave_10bit(&mut self, addr: u16) {
+        self.configure_slave_inner(addr, true);
+    }
+
+    fn configure_slave_inner(&mut self, addr: u16, ten_bit: bool) {
+        self.functionality = I2cFunc::SLAVE;
+        self.mode = I2cOpMode::Slave;
+
+        let (mut con, sar) = if ten_bit {
+            (
+                I2cCon::SLAVE_10BIT,
+                I2cSar::from(addr as u32) & I2cSar::ADDR_MASK_10BIT,
+            )
+        } else {
+            (
+                I2cCon::NONE,
+                I2cSar::from(addr as u32) & I2cSar::ADDR_MASK_7BIT,
+            )
+        };
+
+        // `MASTER`, and `SLAVE_DISABLE` are left clear to enable slave mode
+        con |= I2cCon::RX_FIFO_FULL_HLD_CTRL | I2cCon::RESTART_EN | I2cCon::STOP_DET_IFADDRESSED;
+        con |= match self.timings.bus_freq_hz() {
+            I2cSpeedMode::Standard => I2cCon::SPEED_STD,
+            I2cSpeedMode::High => I2cCon::SPEED_HIGH,
+            _ => I2cCon::SPEED_FAST,
+        };
+
+        // Disable the adapter
+        self.__disable();
+
+        // Write SDA hold time if supported
+        if self.sda_hold_time != 0 {
+            self.i2c.set_sda_hold(self.sda_hold_time);
+        }
+
+        // Interrupt on every received byte, and when the transmit FIFO is empty
+        self.i2c.set_tx_tl(0);
+        self.i2c.set_rx_tl(0);
+
+        self.i2c.set_con(con);
+        self.i2c.set_sar(sar);
+        self.i2c.set_interrupt_mask(I2cInterruptMask::slave());
+
+        self.__enable();
+    }
+
+    /// Services pending `slave` mode events.
+    ///
+    /// Received bytes are copied into `buf`, and the number of bytes copied is returned with the
+    /// serviced events. Bytes that do not fit are kept in the RX FIFO for the next call.
+    ///
+    /// On [I2cSlaveEvent::READ_REQUESTED], the bus is stretched until a response byte is pushed
+    /// with [I2c::slave_write].
+    pub fn slave_poll(&mut self, buf: &mut [u8]) -> (I2cSlaveEvent, usize) {
+        let (stat, _tx_abort_source) = self.i2c.read_clear_interrupt();
+        let mut event = I2cSlaveEvent::NONE;
+        let mut len = 0;
+
+        while len < buf.len() && self.i2c.get_rxflr() > 0 {
+            buf[len] = self.i2c.get_data_cmd().data();
+            len += 1;
+        }
+
+        if len > 0 {
+            event |= I2cSlaveEvent::WRITE_RECEIVED;
+        }
+        if stat.is_set(I2cInterruptStatus::RD_REQ) {
+            event |= I2cSlaveEvent::READ_REQUESTED;
+        }
+        if stat.is_set(I2cInterruptStatus::RX_DONE) {
+            event |= I2cSlaveEvent::READ_DONE;
+        }
+        if stat.is_set(I2cInterruptStatus::STOP_DET) {
+            event |= I2cSlaveEvent::STOP;
+        }
+        if stat.is_set(I2cInterruptStatus::RX_OVER) {
+            event |= I2cSlaveEvent::OVERRUN;
+        }
+        if stat.is_set(I2cInterruptStatus::TX_ABRT) {
+            event |= I2cSlaveEvent::TX_ABORT;
+        }
+
+        (event, len)
+    }
+
+    /// Pushes a response byte into the `DATA_CMD` register for the current master read.
+    pub fn slave_write(&mut self, val: u8) {
+        self.i2c.set_data_cmd(I2cDataCmd::new().with_data(val));
+    }
+
+    /// Gets whether the slave state machine is active, i.e. addressed by a master.
+    pub fn slave_active(&self) -> bool {
+        self.i2c
+            .get_enable_status()
+            .is_set(I2cEnableStatus::SLAVE_ACTIVITY)
+    }
+}
@@ -0,0 +1,123 @@
+use heapless::Vec;
+
+use super::{
+    smbus_pec, Error, I2c, I2cBuffer, I2cDataCmd, I2cPeripheral, I2cTar, Result,
+    I2C_SMBUS_BLOCK_MAX,
+};
+
+// Command byte, byte count, and block data.
+const SMBUS_BLOCK_WRITE_MAX: usize = I2C_SMBUS_BLOCK_MAX as usize + 2;
+
+impl<I2C: I2cPeripheral> I2c<I2C> {
+    /// Performs an SMBus block read from the device at the 7-bit address `addr`.
+    ///
+    /// Writes the `command` byte, then reads the byte count, and the block data after a repeated
+    /// START. If SMBus PEC is enabled (see [I2c::with_pec]), the trailing PEC byte is read and
+    /// verified.
+    ///
+    /// Returns [Error::Other] if the device reports a byte count of zero, or above
+    /// [I2C_SMBUS_BLOCK_MAX].
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{pac, i2c};
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let mut i2c0 = i2c::I2c::new(dp.I2C0);
+    ///
+    /// // Smart battery `ManufacturerName`
+    /// let _name = i2c0.smbus_read_block(0x0b, 0x20).unwrap();
+    /// ```
+    pub fn smbus_read_block(&mut self, addr: u8, command: u8) -> Result<I2cBuffer> {
+        self.xfer_init(I2cTar::from(addr as u32));
+
+        self.push_data_cmd(I2cDataCmd::from(&command))?;
+        self.push_data_cmd(I2cDataCmd::READ | I2cDataCmd::RESTART)?;
+        // The first received byte is the message length
+        let count = self.pop_data()?;
+
+        if count == 0 || count > I2C_SMBUS_BLOCK_MAX {
+            // Read another byte with STOP set to complete the transfer
+            self.push_data_cmd(I2cDataCmd::READ | I2cDataCmd::STOP)?;
+            self.pop_data()?;
+            self.wait_stop()?;
+
+            return Err(Error::Other);
+        }
+
+        let mut data = [0u8; I2C_SMBUS_BLOCK_MAX as usize];
+        let data_len = count as usize;
+        let read_len = data_len.saturating_add(usize::from(self.pec));
+
+        let mut rx_pec = 0u8;
+        let mut rx_idx = 0usize;
+
+        for i in 0..read_len {
+            let cmd = if i == read_len.saturating_sub(1) {
+                I2cDataCmd::READ | I2cDataCmd::STOP
+            } else {
+                I2cDataCmd::READ
+            };
+
+            self.push_data_cmd(cmd)?;
+            rx_idx = self.drain_rx(&mut data[..data_len], &mut rx_pec, rx_idx, read_len);
+        }
+
+        while rx_idx < read_len {
+            self.wait_rx()?;
+            rx_idx = self.drain_rx(&mut data[..data_len], &mut rx_pec, rx_idx, read_len);
+        }
+
+        self.wait_stop()?;
+
+        if self.pec {
+            let mut expected = smbus_pec(0, &[addr << 1, command, (addr << 1) | 1, count]);
+            expected = smbus_pec(expected, &data[..data_len]);
+
+            if expected != rx_pec {
+                return Err(Error::Pec {
+                    expected,
+                    found: rx_pec,
+                });
+            }
+        }
+
+        let mut buf = I2cBuffer::new();
+        // at most `I2C_SMBUS_BLOCK_MAX` bytes, so this cannot overflow
+        buf.extend_from_slice(&data[..data_len]).ok();
+
+        Ok(buf)
+    }
+
+    /// Performs an SMBus block write to the device at the 7-bit address `addr`.
+    ///
+    /// Writes the `command` byte, the byte count, and the block `data`. If SMBus PEC is enabled
+    /// (see [I2c::with_pec]), a PEC byte is appended.
+    ///
+    /// Returns [Error::Other] if `data` is longer than [I2C_SMBUS_BLOCK_MAX].
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{pac, i2c};
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let mut i2c0 = i2c::I2c::new(dp.I2C0);
+    ///
+    /// // PMBus `MFR_ID`
+    /// i2c0.smbus_write_block(0x40, 0x99, b"JH71xx").unwrap();
+    /// ```
+    pub fn smbus_write_block(&mut self, addr: u8, command: u8, data: &[u8]) -> Result<()> {
+        let len = u8::try_from(data.len())
+            .ok()
+            .filter(|&len| len <= I2C_SMBUS_BLOCK_MAX)
+            .ok_or(Error::Other)?;
+
+        let mut buf: Vec<u8, SMBUS_BLOCK_WRITE_MAX> = Vec::new();
+        // the length is checked above, so these cannot overflow
+        buf.push(command).ok();
+        buf.push(len).ok();
+        buf.extend_from_slice(data).ok();
+
+        self.xfer_polled(addr, &buf, &mut [])
+    }
+}
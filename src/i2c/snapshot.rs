@@ -0,0 +1,66 @@
+use core::fmt;
+
+use super::{
+    I2cCon, I2cEnable, I2cEnableStatus, I2cPeripheral, I2cRawInterruptStatus, I2cStatus,
+    I2cTxAbortSource,
+};
+
+/// Snapshot of the I2C peripheral registers, for debugging the adapter state machine.
+///
+/// Captured with [I2c::dump_registers](super::I2c::dump_registers). The [Display](fmt::Display)
+/// implementation prints one register per line, suitable for pasting into a bug report.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct I2cRegisterSnapshot {
+    /// MMIO base address of the I2C peripheral.
+    pub base_addr: usize,
+    /// `CON` register.
+    pub con: I2cCon,
+    /// `ENABLE` register.
+    pub enable: I2cEnable,
+    /// `ENABLE_STATUS` register.
+    pub enable_status: I2cEnableStatus,
+    /// `RAW_INTR_STAT` register.
+    pub raw_intr_stat: I2cRawInterruptStatus,
+    /// `TXFLR` register: number of entries in the TX FIFO.
+    pub txflr: u32,
+    /// `RXFLR` register: number of entries in the RX FIFO.
+    pub rxflr: u32,
+    /// `TX_ABRT_SOURCE` register.
+    pub tx_abrt_source: I2cTxAbortSource,
+    /// `STATUS` register.
+    pub status: I2cStatus,
+}
+
+impl I2cRegisterSnapshot {
+    // Reads the registers of `i2c`.
+    //
+    // Only registers without read side effects are read, so transfer state is not disturbed.
+    pub(crate) fn read<I2C: I2cPeripheral>(i2c: &I2C) -> Self {
+        Self {
+            base_addr: i2c.base_addr(),
+            con: i2c.get_con(),
+            enable: i2c.get_enable(),
+            enable_status: i2c.get_enable_status(),
+            raw_intr_stat: i2c.get_raw_interrupt_stat(),
+            txflr: i2c.get_txflr(),
+            rxflr: i2c.get_rxflr(),
+            tx_abrt_source: i2c.get_tx_abort_source(),
+            status: i2c.get_status(),
+        }
+    }
+}
+
+impl fmt::Display for I2cRegisterSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "I2C @ {:#010x}", self.base_addr)?;
+        writeln!(f, "  CON:            {:#010x}", self.con.bits())?;
+        writeln!(f, "  ENABLE:         {:#010x}", self.enable.bits())?;
+        writeln!(f, "  ENABLE_STATUS:  {:#010x}", self.enable_status.bits())?;
+        writeln!(f, "  RAW_INTR_STAT:  {:#010x}", self.raw_intr_stat.bits())?;
+        writeln!(f, "  TXFLR:          {}", self.txflr)?;
+        writeln!(f, "  RXFLR:          {}", self.rxflr)?;
+        writeln!(f, "  TX_ABRT_SOURCE: {:#010x}", self.tx_abrt_source.bits())?;
+        write!(f, "  STATUS:         {:#010x}", self.status.bits())
+    }
+}
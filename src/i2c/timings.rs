@@ -1,5 +1,78 @@
 use super::I2cSpeedMode;
 
+/// Default SCL/SDA fall time (in ns), used when [I2cTimings] does not provide one.
+pub const DEFAULT_FALL_NS: u32 = 300;
+
+/// Standard-mode minimum SCL high period, and START hold time (in ns): tHIGH = tHD;STA.
+pub const SS_HIGH_NS: u32 = 4000;
+/// Standard-mode minimum SCL low period (in ns): tLOW.
+pub const SS_LOW_NS: u32 = 4700;
+/// Fast-mode minimum SCL high period, and START hold time (in ns): tHIGH = tHD;STA.
+pub const FS_HIGH_NS: u32 = 600;
+/// Fast-mode minimum SCL low period (in ns): tLOW.
+pub const FS_LOW_NS: u32 = 1300;
+/// Fast-mode Plus minimum SCL high period, and START hold time (in ns): tHIGH = tHD;STA.
+pub const FP_HIGH_NS: u32 = 260;
+/// Fast-mode Plus minimum SCL low period (in ns): tLOW.
+pub const FP_LOW_NS: u32 = 500;
+/// High-speed mode minimum SCL high period, and START hold time (in ns): tHIGH = tHD;STA.
+pub const HS_HIGH_NS: u32 = 160;
+/// High-speed mode minimum SCL low period (in ns): tLOW.
+pub const HS_LOW_NS: u32 = 320;
+
+const fn div_round_closest(num: u64, den: u64) -> u64 {
+    (num + den / 2) / den
+}
+
+/// Converts a duration in nanoseconds to a number of `IC_CLK` cycles (rounded to the closest).
+pub const fn ns_to_ic_clk(ic_clk_khz: u32, ns: u32) -> u32 {
+    div_round_closest(ic_clk_khz as u64 * ns as u64, 1_000_000) as u32
+}
+
+/// Computes the `IC_*_SCL_HCNT` count for the SCL high period.
+///
+/// Mirrors the DesignWare default condition from the Linux driver:
+/// `IC_[FS]S_SCL_HCNT + 3 >= IC_CLK * (tHD;STA + tf)`.
+///
+/// - `ic_clk_khz`: `IC_CLK` rate in kHz
+/// - `t_symbol_ns`: tHD;STA = tHIGH in ns
+/// - `tf_ns`: SDA fall time in ns
+///
+/// Example:
+///
+/// ```
+/// use jh71xx_hal::i2c::{scl_hcnt, DEFAULT_FALL_NS, FS_HIGH_NS, SS_HIGH_NS};
+///
+/// // 50 MHz `IC_CLK`, same counts as the Linux `DesignWare` driver
+/// assert_eq!(scl_hcnt(50_000, SS_HIGH_NS, DEFAULT_FALL_NS), 212);
+/// assert_eq!(scl_hcnt(50_000, FS_HIGH_NS, DEFAULT_FALL_NS), 42);
+/// ```
+pub const fn scl_hcnt(ic_clk_khz: u32, t_symbol_ns: u32, tf_ns: u32) -> u32 {
+    ns_to_ic_clk(ic_clk_khz, t_symbol_ns + tf_ns).saturating_sub(3)
+}
+
+/// Computes the `IC_*_SCL_LCNT` count for the SCL low period.
+///
+/// Mirrors the DesignWare condition from the Linux driver:
+/// `IC_[FS]S_SCL_LCNT + 1 >= IC_CLK * (tLOW + tf)`.
+///
+/// - `ic_clk_khz`: `IC_CLK` rate in kHz
+/// - `t_low_ns`: tLOW in ns
+/// - `tf_ns`: SCL fall time in ns
+///
+/// Example:
+///
+/// ```
+/// use jh71xx_hal::i2c::{scl_lcnt, DEFAULT_FALL_NS, FS_LOW_NS, SS_LOW_NS};
+///
+/// // 50 MHz `IC_CLK`, same counts as the Linux `DesignWare` driver
+/// assert_eq!(scl_lcnt(50_000, SS_LOW_NS, DEFAULT_FALL_NS), 249);
+/// assert_eq!(scl_lcnt(50_000, FS_LOW_NS, DEFAULT_FALL_NS), 79);
+/// ```
+pub const fn scl_lcnt(ic_clk_khz: u32, t_low_ns: u32, tf_ns: u32) -> u32 {
+    ns_to_ic_clk(ic_clk_khz, t_low_ns + tf_ns).saturating_sub(1)
+}
+
 /// I2C timing information
 #[repr(C)]
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
@@ -29,6 +102,40 @@ impl I2cTimings {
         }
     }
 
+    /// Creates a new [I2cTimings] for the [I2cSpeedMode], with the maximum SCL/SDA rise and
+    /// fall times allowed by the I2C specification.
+    ///
+    /// Other timings (SDA hold, filters, etc.) are left as zero.
+    ///
+    /// Example:
+    ///
+    /// ```
+    /// # use jh71xx_hal::i2c;
+    /// let timings = i2c::I2cTimings::for_speed(i2c::I2cSpeedMode::Fast);
+    ///
+    /// assert_eq!(timings.bus_freq_hz(), i2c::I2cSpeedMode::Fast);
+    /// assert_eq!(timings.scl_rise_ns(), 300);
+    /// assert_eq!(timings.scl_fall_ns(), 300);
+    /// ```
+    pub const fn for_speed(mode: I2cSpeedMode) -> Self {
+        let (rise_ns, fall_ns) = match mode {
+            I2cSpeedMode::Standard => (1000, 300),
+            I2cSpeedMode::Fast => (300, 300),
+            I2cSpeedMode::FastPlus | I2cSpeedMode::Turbo => (120, 120),
+            // SCLH/SDAH with a 100 pF bus load
+            I2cSpeedMode::High => (40, 40),
+            I2cSpeedMode::UltraFast => (50, 50),
+        };
+
+        Self {
+            bus_freq_hz: mode,
+            scl_rise_ns: rise_ns,
+            scl_fall_ns: fall_ns,
+            sda_fall_ns: fall_ns,
+            ..Self::new()
+        }
+    }
+
     /// Gets the bus frequency in Hz.
     pub const fn bus_freq_hz(&self) -> I2cSpeedMode {
         self.bus_freq_hz
@@ -1,5 +1,7 @@
 use critical_section::RawRestoreState;
 
+pub mod plic;
+
 /// Disables all interrupts on all cores.
 ///
 /// # Safety
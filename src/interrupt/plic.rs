@@ -0,0 +1,245 @@
+//! Driver for the JH7110 platform-level interrupt controller (`PLIC`).
+//!
+//! Routes peripheral interrupt sources to the external interrupt line of each HART context.
+//!
+//! With the `rt` feature, contexts are the machine mode (`M`) contexts. With the `rts` feature,
+//! contexts are the supervisor mode (`S`) contexts. HART 0 (the `S7` monitor core) only has an
+//! `M` mode context.
+//!
+//! Example:
+//!
+//! ```no_run
+//! use jh71xx_hal::interrupt::plic;
+//!
+//! // Route the source, and accept all priorities above zero on HART 1
+//! plic::set_priority(plic::source::I2C0, 1).unwrap();
+//! plic::set_threshold(1, 0).unwrap();
+//! plic::enable_source_on(1, plic::source::I2C0).unwrap();
+//!
+//! // In the external interrupt handler
+//! if let Some(id) = plic::claim_on(1) {
+//!     // ... service the interrupt ...
+//!     plic::complete_on(1, id).unwrap();
+//! }
+//! ```
+
+/// Base address of the `PLIC` register space.
+pub const PLIC_BASE: usize = 0x0c00_0000;
+/// Highest valid interrupt source ID, source `0` is reserved.
+pub const PLIC_MAX_SOURCE: u32 = 136;
+/// Highest interrupt priority, priority `0` never interrupts.
+pub const PLIC_MAX_PRIORITY: u32 = 7;
+/// Highest valid HART ID.
+pub const PLIC_MAX_HART: usize = 4;
+
+// Register offsets
+const PRIORITY: usize = 0x0;
+const PENDING: usize = 0x1000;
+const ENABLE: usize = 0x2000;
+const ENABLE_STRIDE: usize = 0x80;
+const CONTEXT: usize = 0x20_0000;
+const CONTEXT_STRIDE: usize = 0x1000;
+const CONTEXT_THRESHOLD: usize = 0x0;
+const CONTEXT_CLAIM: usize = 0x4;
+
+/// Interrupt source IDs for JH7110 peripherals.
+pub mod source {
+    pub const GPIO_AON: u32 = 85;
+    pub const GPIO_SYS: u32 = 86;
+    pub const I2C0: u32 = 35;
+    pub const I2C1: u32 = 36;
+    pub const I2C2: u32 = 37;
+    pub const I2C3: u32 = 48;
+    pub const I2C4: u32 = 49;
+    pub const I2C5: u32 = 50;
+    pub const I2C6: u32 = 51;
+    pub const SPI0: u32 = 38;
+    pub const SPI1: u32 = 39;
+    pub const SPI2: u32 = 40;
+    pub const SPI3: u32 = 52;
+    pub const SPI4: u32 = 53;
+    pub const SPI5: u32 = 54;
+    pub const SPI6: u32 = 55;
+    pub const UART0: u32 = 32;
+    pub const UART1: u32 = 33;
+    pub const UART2: u32 = 34;
+    pub const UART3: u32 = 45;
+    pub const UART4: u32 = 46;
+    pub const UART5: u32 = 47;
+}
+
+/// Represents `PLIC` errors.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Error {
+    /// Interrupt source ID outside `[1, PLIC_MAX_SOURCE]`.
+    InvalidSource(u32),
+    /// Priority above [PLIC_MAX_PRIORITY].
+    InvalidPriority(u32),
+    /// HART without a context for the configured privilege mode.
+    InvalidHart(usize),
+}
+
+/// Convenience alias for the `PLIC` result type.
+pub type Result<T> = core::result::Result<T, Error>;
+
+// SAFETY: callers provide offsets within the `PLIC` register space.
+unsafe fn read_reg(offset: usize) -> u32 {
+    ((PLIC_BASE + offset) as *const u32).read_volatile()
+}
+
+// SAFETY: callers provide offsets within the `PLIC` register space.
+unsafe fn write_reg(offset: usize, val: u32) {
+    ((PLIC_BASE + offset) as *mut u32).write_volatile(val)
+}
+
+const fn check_source(id: u32) -> Result<u32> {
+    if id == 0 || id > PLIC_MAX_SOURCE {
+        Err(Error::InvalidSource(id))
+    } else {
+        Ok(id)
+    }
+}
+
+const fn check_priority(prio: u32) -> Result<u32> {
+    if prio > PLIC_MAX_PRIORITY {
+        Err(Error::InvalidPriority(prio))
+    } else {
+        Ok(prio)
+    }
+}
+
+// Gets the `PLIC` context of `hart` for the configured privilege mode.
+const fn context(hart: usize) -> Result<usize> {
+    match hart {
+        #[cfg(not(feature = "rts"))]
+        0 => Ok(0),
+        #[cfg(not(feature = "rts"))]
+        1..=PLIC_MAX_HART => Ok(hart * 2 - 1),
+        #[cfg(feature = "rts")]
+        1..=PLIC_MAX_HART => Ok(hart * 2),
+        _ => Err(Error::InvalidHart(hart)),
+    }
+}
+
+// Sets, or clears the enable bit for source `id` in the context of `hart`.
+fn set_enable(hart: usize, id: u32, enable: bool) -> Result<()> {
+    let id = check_source(id)?;
+    let offset = ENABLE + context(hart)? * ENABLE_STRIDE + (id as usize / 32) * 4;
+    let bit = 1u32 << (id % 32);
+
+    crate::interrupt::free(|| {
+        // SAFETY: the enable word for `id` is within the `PLIC` register space, and the
+        // read-modify-write is done with interrupts disabled.
+        unsafe {
+            let val = read_reg(offset);
+            write_reg(offset, if enable { val | bit } else { val & !bit });
+        }
+    });
+
+    Ok(())
+}
+
+/// Sets the priority of interrupt source `id`.
+///
+/// A priority of `0` disables the source.
+pub fn set_priority(id: u32, prio: u32) -> Result<()> {
+    let id = check_source(id)?;
+    let prio = check_priority(prio)?;
+
+    // SAFETY: the priority register for `id` is within the `PLIC` register space.
+    unsafe { write_reg(PRIORITY + id as usize * 4, prio) };
+
+    Ok(())
+}
+
+/// Gets the priority of interrupt source `id`.
+pub fn priority(id: u32) -> Result<u32> {
+    let id = check_source(id)?;
+
+    // SAFETY: the priority register for `id` is within the `PLIC` register space.
+    Ok(unsafe { read_reg(PRIORITY + id as usize * 4) })
+}
+
+/// Gets whether interrupt source `id` is pending.
+pub fn is_pending(id: u32) -> Result<bool> {
+    let id = check_source(id)?;
+
+    // SAFETY: the pending word for `id` is within the `PLIC` register space.
+    let val = unsafe { read_reg(PENDING + (id as usize / 32) * 4) };
+
+    Ok(val & (1 << (id % 32)) != 0)
+}
+
+/// Sets the priority threshold for the context of `hart`.
+///
+/// Only sources with a priority above the threshold interrupt the HART.
+pub fn set_threshold(hart: usize, prio: u32) -> Result<()> {
+    let prio = check_priority(prio)?;
+    let offset = CONTEXT + context(hart)? * CONTEXT_STRIDE + CONTEXT_THRESHOLD;
+
+    // SAFETY: the threshold register is within the `PLIC` register space.
+    unsafe { write_reg(offset, prio) };
+
+    Ok(())
+}
+
+/// Enables interrupt source `id` in the context of `hart`.
+pub fn enable_source_on(hart: usize, id: u32) -> Result<()> {
+    set_enable(hart, id, true)
+}
+
+/// Disables interrupt source `id` in the context of `hart`.
+pub fn disable_source_on(hart: usize, id: u32) -> Result<()> {
+    set_enable(hart, id, false)
+}
+
+/// Claims the highest priority pending interrupt in the context of `hart`.
+///
+/// Returns `None` if no interrupt is pending, or `hart` is invalid.
+pub fn claim_on(hart: usize) -> Option<u32> {
+    let offset = CONTEXT + context(hart).ok()? * CONTEXT_STRIDE + CONTEXT_CLAIM;
+
+    // SAFETY: the claim register is within the `PLIC` register space.
+    match unsafe { read_reg(offset) } {
+        0 => None,
+        id => Some(id),
+    }
+}
+
+/// Signals completion of the claimed interrupt source `id` in the context of `hart`.
+pub fn complete_on(hart: usize, id: u32) -> Result<()> {
+    let id = check_source(id)?;
+    let offset = CONTEXT + context(hart)? * CONTEXT_STRIDE + CONTEXT_CLAIM;
+
+    // SAFETY: the complete register is within the `PLIC` register space.
+    unsafe { write_reg(offset, id) };
+
+    Ok(())
+}
+
+/// Enables interrupt source `id` for the current HART.
+///
+/// **NOTE**: only available in machine mode, where the HART ID is readable from `mhartid`.
+#[cfg(not(feature = "rts"))]
+pub fn enable_source(id: u32) -> Result<()> {
+    enable_source_on(riscv::register::mhartid::read(), id)
+}
+
+/// Disables interrupt source `id` for the current HART.
+#[cfg(not(feature = "rts"))]
+pub fn disable_source(id: u32) -> Result<()> {
+    disable_source_on(riscv::register::mhartid::read(), id)
+}
+
+/// Claims the highest priority pending interrupt for the current HART.
+#[cfg(not(feature = "rts"))]
+pub fn claim() -> Option<u32> {
+    claim_on(riscv::register::mhartid::read())
+}
+
+/// Signals completion of the claimed interrupt source `id` for the current HART.
+#[cfg(not(feature = "rts"))]
+pub fn complete(id: u32) -> Result<()> {
+    complete_on(riscv::register::mhartid::read(), id)
+}
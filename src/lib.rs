@@ -20,11 +20,13 @@ pub extern crate jh71xx_pac as pac;
 #[cfg(feature = "rt")]
 pub mod critical_section;
 pub mod delay;
+pub mod dma;
 pub mod gpio;
 pub mod i2c;
 #[cfg(feature = "rt")]
 pub mod interrupt;
 mod macros;
 pub mod pwm;
+pub mod reset;
 pub mod spi;
 pub mod uart;
@@ -13,23 +13,53 @@
 //! let max_cycle = pwm0.max_duty_cycle();
 //! // Sets the PWM peripheral to a ~50% duty cycle
 //! pwm0.set_duty_cycle(max_cycle / 2).unwrap();
+//!
+//! // Drive the eight PTC channels independently
+//! let mut channels = pwm0.split();
+//! channels.ch3.enable(true);
+//! channels.ch3.set_duty_cycle(max_cycle / 4).unwrap();
 //! ```
 
 use embedded_hal::pwm::{ErrorType, SetDutyCycle};
 
+use crate::reset::{CrgReset, Reset};
+
 mod error;
 mod peripheral;
 
 pub use error::*;
 pub use peripheral::*;
 
-/// Represents the PWM PTC peripheral on JH71xx-based SoCs.
-pub struct Pwm<PWM: PwmPeripheral> {
+// Converts nanoseconds to PWM clock cycles, rounded to the nearest cycle.
+const fn ns_to_cycles(clk_hz: u32, ns: u32) -> u64 {
+    (clk_hz as u64 * ns as u64 + 500_000_000) / 1_000_000_000
+}
+
+/// Represents a channel of the PWM PTC peripheral on JH71xx-based SoCs.
+///
+/// The `CH` parameter selects one of the [PWM_CHANNELS] channel register blocks.
+pub struct Pwm<PWM: PwmPeripheral, const CH: usize = 0> {
     periph: PWM,
 }
 
+/// The independent channels of a PWM PTC peripheral.
+///
+/// Owns every channel, so it also provides the operations affecting all channels.
+pub struct PwmChannels<PWM: PwmPeripheral> {
+    pub ch0: Pwm<PWM, 0>,
+    pub ch1: Pwm<PWM, 1>,
+    pub ch2: Pwm<PWM, 2>,
+    pub ch3: Pwm<PWM, 3>,
+    pub ch4: Pwm<PWM, 4>,
+    pub ch5: Pwm<PWM, 5>,
+    pub ch6: Pwm<PWM, 6>,
+    pub ch7: Pwm<PWM, 7>,
+}
+
 impl<PWM: PwmPeripheral> Pwm<PWM> {
-    /// Creates a new [Pwm] from a PWM peripheral.
+    /// Creates a new [Pwm] for channel 0 from a PWM peripheral.
+    ///
+    /// Use [Pwm::split] to access the other channels.
     ///
     /// Example:
     ///
@@ -38,13 +68,67 @@ impl<PWM: PwmPeripheral> Pwm<PWM> {
     /// let dp = pac::Peripherals::take().unwrap();
     /// let _pwm = pwm::Pwm::new(dp.PWM);
     /// ```
-    pub fn new(mut periph: PWM) -> Self {
-        if periph.period() > MAX_PERIOD {
-            periph.set_period(MAX_PERIOD);
+    pub fn new(periph: PWM) -> Self {
+        Self::new_channel(periph)
+    }
+
+    /// Splits the PWM peripheral into its independent channels.
+    ///
+    /// Operations affecting every channel, [PwmChannels::enable_channels], and [Reset], are only
+    /// available on the [PwmChannels].
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{pac, pwm};
+    /// use embedded_hal::pwm::SetDutyCycle;
+    ///
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let mut leds = pwm::Pwm::new(dp.PWM).split();
+    ///
+    /// leds.ch0.set_duty_cycle_percent(25).unwrap();
+    /// leds.ch1.set_duty_cycle_percent(50).unwrap();
+    /// leds.ch2.set_duty_cycle_percent(75).unwrap();
+    /// leds.ch3.set_duty_cycle_fully_on().unwrap();
+    /// ```
+    pub fn split(self) -> PwmChannels<PWM> {
+        // SAFETY: each channel only accesses its own channel register block.
+        unsafe {
+            PwmChannels {
+                ch0: self,
+                ch1: Pwm::new_channel(PWM::steal()),
+                ch2: Pwm::new_channel(PWM::steal()),
+                ch3: Pwm::new_channel(PWM::steal()),
+                ch4: Pwm::new_channel(PWM::steal()),
+                ch5: Pwm::new_channel(PWM::steal()),
+                ch6: Pwm::new_channel(PWM::steal()),
+                ch7: Pwm::new_channel(PWM::steal()),
+            }
+        }
+    }
+}
+
+impl<PWM: PwmPeripheral, const CH: usize> Pwm<PWM, CH> {
+    fn new_channel(mut periph: PWM) -> Self {
+        // Compile-time check for a valid channel index
+        const { assert!(CH < PWM_CHANNELS, "invalid PWM channel") };
+
+        if periph.period(CH) > MAX_PERIOD {
+            periph.set_period(CH, MAX_PERIOD);
         }
         Self { periph }
     }
 
+    /// Gets the channel index of the [Pwm].
+    pub const fn channel(&self) -> usize {
+        CH
+    }
+
+    /// Gets the MMIO base address of the wrapped PWM peripheral.
+    pub fn base_addr(&self) -> usize {
+        self.periph.base_addr()
+    }
+
     /// Gets the period of the [Pwm] peripheral.
     ///
     /// Example:
@@ -56,7 +140,7 @@ impl<PWM: PwmPeripheral> Pwm<PWM> {
     /// let _period = pwm.period();
     /// ```
     pub fn period(&self) -> u16 {
-        (self.periph.period() & 0xffff) as u16
+        (self.periph.period(CH) & 0xffff) as u16
     }
 
     /// Sets the period of the [Pwm] peripheral.
@@ -70,10 +154,77 @@ impl<PWM: PwmPeripheral> Pwm<PWM> {
     /// pwm.set_period(pwm::MAX_PERIOD as u16);
     /// ```
     pub fn set_period(&mut self, period: u16) {
-        self.periph.set_period(period as u32);
+        self.periph.set_period(CH, period as u32);
+    }
+
+    /// Sets the PWM frequency (in Hz), rounded to the nearest period in PWM clock cycles.
+    ///
+    /// - `apb_hz`: PWM clock rate in Hz (APB by default)
+    /// - `freq_hz`: requested PWM frequency in Hz
+    ///
+    /// Returns [Error::InvalidPeriod] with the period in cycles, if the period does not fit in
+    /// [MAX_PERIOD] cycles.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{pac, pwm};
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let mut pwm = pwm::Pwm::new(dp.PWM);
+    /// pwm.set_frequency_hz(50_000_000, 1_000).unwrap();
+    /// ```
+    pub fn set_frequency_hz(&mut self, apb_hz: u32, freq_hz: u32) -> Result<()> {
+        let cycles = match freq_hz {
+            0 => 0,
+            hz => (apb_hz as u64 + hz as u64 / 2) / hz as u64,
+        };
+
+        self.set_period_cycles(cycles)
     }
 
-    /// Gets whether the [Pwm] peripheral is enabled.
+    /// Sets the PWM period (in nanoseconds), rounded to the nearest PWM clock cycle.
+    ///
+    /// - `apb_hz`: PWM clock rate in Hz (APB by default)
+    /// - `period_ns`: requested PWM period in nanoseconds
+    ///
+    /// Returns [Error::InvalidPeriod] with the period in cycles, if the period does not fit in
+    /// [MAX_PERIOD] cycles.
+    pub fn set_period_ns(&mut self, apb_hz: u32, period_ns: u32) -> Result<()> {
+        self.set_period_cycles(ns_to_cycles(apb_hz, period_ns))
+    }
+
+    /// Sets the PWM duty-cycle (in nanoseconds), rounded to the nearest PWM clock cycle.
+    ///
+    /// - `apb_hz`: PWM clock rate in Hz (APB by default)
+    /// - `duty_ns`: requested high time in nanoseconds
+    ///
+    /// The duty-cycle is clamped to the period.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{pac, pwm};
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let mut pwm = pwm::Pwm::new(dp.PWM);
+    /// // 1 ms period, 250 us pulse
+    /// pwm.set_period_ns(50_000_000, 1_000_000).unwrap();
+    /// pwm.set_duty_ns(50_000_000, 250_000);
+    /// ```
+    pub fn set_duty_ns(&mut self, apb_hz: u32, duty_ns: u32) {
+        let cycles = core::cmp::min(ns_to_cycles(apb_hz, duty_ns), MAX_PERIOD as u64);
+        self.periph.set_duty(CH, cycles as u32);
+    }
+
+    fn set_period_cycles(&mut self, cycles: u64) -> Result<()> {
+        if cycles == 0 || cycles > MAX_PERIOD as u64 {
+            Err(Error::InvalidPeriod(cycles.min(u32::MAX as u64) as u32))
+        } else {
+            self.periph.set_period(CH, cycles as u32);
+            Ok(())
+        }
+    }
+
+    /// Gets whether the [Pwm] channel is enabled.
     ///
     /// Example:
     ///
@@ -86,10 +237,12 @@ impl<PWM: PwmPeripheral> Pwm<PWM> {
     /// }
     /// ```
     pub fn enabled(&self) -> bool {
-        self.periph.enabled()
+        self.periph.enabled(CH)
     }
 
-    /// Sets whether the PWM peripheral is enabled.
+    /// Sets whether the PWM channel is enabled.
+    ///
+    /// Other channels are left unchanged.
     ///
     /// Example:
     ///
@@ -102,21 +255,66 @@ impl<PWM: PwmPeripheral> Pwm<PWM> {
     /// }
     /// ```
     pub fn enable(&mut self, val: bool) {
-        self.periph.enable(val);
+        self.periph.enable(CH, val);
+    }
+}
+
+impl<PWM: PwmPeripheral> PwmChannels<PWM> {
+    /// Enables multiple PWM channels at once, e.g. for phase-aligned outputs.
+    ///
+    /// Bit `N` of `mask` enables channel `N`. Channels not set in `mask` are left unchanged.
+    ///
+    /// **NOTE**: each PTC channel has its own free-running counter. The selected counters are
+    /// held in reset while the channels are enabled, and released back-to-back. Remaining
+    /// phase skew is limited to a few APB register writes.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{pac, pwm};
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let mut channels = pwm::Pwm::new(dp.PWM).split();
+    /// // start channels 0, 1, and 2 together
+    /// channels.enable_channels(0b0000_0111);
+    /// ```
+    pub fn enable_channels(&mut self, mask: u8) {
+        self.ch0.periph.enable_channels(mask);
+    }
+}
+
+impl<PWM: PwmPeripheral + CrgReset> Reset for PwmChannels<PWM> {
+    /// Resets the PWM peripheral, restoring the period and duty-cycle of every channel.
+    ///
+    /// **NOTE**: the reset line is shared by all channels, so only the [PwmChannels] owning
+    /// every channel can reset the peripheral. The PWM outputs are left disabled.
+    fn reset(&mut self) {
+        let periph = &mut self.ch0.periph;
+
+        let mut saved = [(0, 0); PWM_CHANNELS];
+        for (ch, cfg) in saved.iter_mut().enumerate() {
+            *cfg = (periph.period(ch), periph.duty(ch));
+        }
+
+        PWM::pulse_reset();
+
+        for (ch, &(period, duty)) in saved.iter().enumerate() {
+            periph.set_period(ch, period);
+            periph.set_duty(ch, duty);
+        }
     }
 }
 
-impl<PWM: PwmPeripheral> ErrorType for Pwm<PWM> {
+impl<PWM: PwmPeripheral, const CH: usize> ErrorType for Pwm<PWM, CH> {
     type Error = Error;
 }
 
-impl<PWM: PwmPeripheral> SetDutyCycle for Pwm<PWM> {
+impl<PWM: PwmPeripheral, const CH: usize> SetDutyCycle for Pwm<PWM, CH> {
     fn max_duty_cycle(&self) -> u16 {
-        (self.periph.period() & 0xffff) as u16
+        (self.periph.period(CH) & 0xffff) as u16
     }
 
     fn set_duty_cycle(&mut self, duty: u16) -> Result<()> {
-        self.periph.set_duty(duty as u32);
+        self.periph.set_duty(CH, duty as u32);
         Ok(())
     }
 }
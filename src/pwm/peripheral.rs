@@ -3,65 +3,151 @@ use pac::PWM;
 /// Max period length configurable by the HAL.
 pub const MAX_PERIOD: u32 = u16::MAX as u32;
 
+/// Number of PWM PTC channels on JH71xx-based SoCs.
+pub const PWM_CHANNELS: usize = 8;
+
+// Offsets of the registers in a channel register block.
+const HRC_OFFSET: usize = 0x4;
+const LRC_OFFSET: usize = 0x8;
+const CTRL_OFFSET: usize = 0xc;
+// `CTRL` register bits
+const CTRL_EN: u32 = 1 << 0;
+const CTRL_OE: u32 = 1 << 3;
+const CTRL_CNTRRST: u32 = 1 << 7;
+
+/// Gets the register block offset of a PWM PTC channel.
+///
+/// Channels 0-3 are contiguous from the peripheral base, channels 4-7 start at offset `0x8000`.
+pub const fn channel_offset(channel: usize) -> usize {
+    if channel > 3 {
+        0x8000 + (channel - 4) * 0x10
+    } else {
+        channel * 0x10
+    }
+}
+
+// Gets a pointer to a channel register, or `None` for an invalid channel.
+fn channel_reg(base: usize, channel: usize, offset: usize) -> Option<*mut u32> {
+    (channel < PWM_CHANNELS).then_some((base + channel_offset(channel) + offset) as *mut u32)
+}
+
+// Reads a channel register, invalid channels read as zero.
+fn read_channel_reg(base: usize, channel: usize, offset: usize) -> u32 {
+    // SAFETY: valid channel registers are within the PWM register space.
+    channel_reg(base, channel, offset).map_or(0, |reg| unsafe { reg.read_volatile() })
+}
+
+// Modifies a channel register, invalid channels are ignored.
+fn modify_channel_reg(base: usize, channel: usize, offset: usize, f: impl FnOnce(u32) -> u32) {
+    if let Some(reg) = channel_reg(base, channel, offset) {
+        // SAFETY: valid channel registers are within the PWM register space, and exclusive
+        // access is guaranteed by the caller's `&mut` borrow.
+        unsafe { reg.write_volatile(f(reg.read_volatile())) }
+    }
+}
+
 /// High-level functions to access low-level PWM PTC registers.
+///
+/// Register access is per-channel, `channel` values outside `0..PWM_CHANNELS` are ignored.
 pub trait PwmPeripheral {
-    /// Gets the PWM period value.
+    /// Gets the MMIO base address of the PWM PTC peripheral.
+    fn base_addr(&self) -> usize;
+
+    /// Creates another handle to the PWM PTC peripheral.
+    ///
+    /// # Safety
+    ///
+    /// Callers must ensure that handles only access disjoint channel register blocks.
+    unsafe fn steal() -> Self;
+
+    /// Gets the PWM period value of `channel`.
     ///
     /// This is the number of PWM clock cycles (APB by default).
-    fn period(&self) -> u32;
-    /// Sets the PWM period value.
+    fn period(&self, channel: usize) -> u32;
+    /// Sets the PWM period value of `channel`.
     ///
     /// This is the number of PWM clock cycles (APB by default).
-    fn set_period(&mut self, val: u32);
+    fn set_period(&mut self, channel: usize, val: u32);
 
-    /// Gets the PWM duty-cycle value.
-    fn duty(&self) -> u32;
-    /// Sets the PWM duty-cycle value.
+    /// Gets the PWM duty-cycle value of `channel`.
+    fn duty(&self, channel: usize) -> u32;
+    /// Sets the PWM duty-cycle value of `channel`.
     ///
     /// The maximum value is the PWM period value.
     ///
     /// If `val` exceeds the period value, duty-cycle will be set to the period.
-    fn set_duty(&mut self, val: u32);
+    fn set_duty(&mut self, channel: usize, val: u32);
 
-    /// Gets whether the PWM is enabled.
-    fn enabled(&self) -> bool;
-    /// Sets whether to enable the PWM.
-    fn enable(&mut self, val: bool);
+    /// Gets whether the PWM `channel` is enabled.
+    fn enabled(&self, channel: usize) -> bool;
+    /// Sets whether to enable the PWM `channel`.
+    fn enable(&mut self, channel: usize, val: bool);
+    /// Enables the PWM channels set in `mask` (bit `N` enables channel `N`).
+    ///
+    /// Each PTC channel has its own free-running counter, and there is no common enable bit.
+    /// To minimize phase skew, all selected counters are held in reset while the channels are
+    /// enabled, then released back-to-back.
+    fn enable_channels(&mut self, mask: u8);
 }
 
 macro_rules! impl_pwm_peripheral {
     ($pwm:ident) => {
         impl $crate::pwm::PwmPeripheral for $pwm {
-            fn period(&self) -> u32 {
-                self.lrc().read().lrc().bits()
+            fn base_addr(&self) -> usize {
+                Self::ptr() as usize
             }
-            fn set_period(&mut self, val: u32) {
-                self.lrc()
-                    .modify(|_, w| w.lrc().variant(core::cmp::min(val, MAX_PERIOD)));
+
+            unsafe fn steal() -> Self {
+                $pwm::steal()
             }
 
-            fn duty(&self) -> u32 {
-                self.hrc().read().hrc().bits()
+            fn period(&self, channel: usize) -> u32 {
+                read_channel_reg(self.base_addr(), channel, LRC_OFFSET)
             }
-            fn set_duty(&mut self, val: u32) {
-                let max = self.period();
-                self.hrc()
-                    .modify(|_, w| w.hrc().variant(core::cmp::min(val, max)));
+            fn set_period(&mut self, channel: usize, val: u32) {
+                modify_channel_reg(self.base_addr(), channel, LRC_OFFSET, |_| {
+                    core::cmp::min(val, MAX_PERIOD)
+                });
             }
 
-            fn enabled(&self) -> bool {
-                let r = self.ctrl().read();
-                r.en().bit_is_set() && r.oe().bit_is_set()
+            fn duty(&self, channel: usize) -> u32 {
+                read_channel_reg(self.base_addr(), channel, HRC_OFFSET)
+            }
+            fn set_duty(&mut self, channel: usize, val: u32) {
+                let max = self.period(channel);
+                modify_channel_reg(self.base_addr(), channel, HRC_OFFSET, |_| {
+                    core::cmp::min(val, max)
+                });
             }
-            fn enable(&mut self, val: bool) {
-                self.ctrl().modify(|_, w| match val {
-                    false => w.en().clear_bit().oe().clear_bit(),
-                    true => w.en().set_bit().oe().set_bit(),
-                })
+
+            fn enabled(&self, channel: usize) -> bool {
+                let ctrl = read_channel_reg(self.base_addr(), channel, CTRL_OFFSET);
+                ctrl & (CTRL_EN | CTRL_OE) == (CTRL_EN | CTRL_OE)
+            }
+            fn enable(&mut self, channel: usize, val: bool) {
+                modify_channel_reg(self.base_addr(), channel, CTRL_OFFSET, |ctrl| match val {
+                    false => ctrl & !(CTRL_EN | CTRL_OE),
+                    true => ctrl | CTRL_EN | CTRL_OE,
+                });
+            }
+            fn enable_channels(&mut self, mask: u8) {
+                let base = self.base_addr();
+                let selected = || (0..PWM_CHANNELS).filter(|ch| mask & (1 << ch) != 0);
+
+                // Hold the counters in reset while enabling the channels
+                for ch in selected() {
+                    modify_channel_reg(base, ch, CTRL_OFFSET, |ctrl| {
+                        ctrl | CTRL_CNTRRST | CTRL_EN | CTRL_OE
+                    });
+                }
+
+                // Release the counters as close together as possible
+                for ch in selected() {
+                    modify_channel_reg(base, ch, CTRL_OFFSET, |ctrl| ctrl & !CTRL_CNTRRST);
+                }
             }
         }
     };
 }
 
-// FIXME: JH7110 TRM says the PWM is eight-channel, but there is only one entry in the DTS file...
 impl_pwm_peripheral!(PWM);
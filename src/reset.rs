@@ -0,0 +1,114 @@
+//! Peripheral reset through the system clock and reset generator (`SYSCRG`).
+//!
+//! ## Examples
+//!
+//! ```no_run
+//! use jh71xx_hal::{i2c, pac, reset::Reset};
+//!
+//! let dp = pac::Peripherals::take().unwrap();
+//! let mut i2c0 = i2c::I2c::new(dp.I2C0);
+//!
+//! // Recover a wedged peripheral with a full hardware reset
+//! i2c0.reset();
+//! ```
+
+use crate::pac::{
+    I2C0, I2C1, I2C2, I2C3, I2C4, I2C5, I2C6, PWM, SPI0, SPI1, SPI2, SPI3, SPI4, SPI5, SPI6,
+    SYSCRG, UART0, UART1, UART2, UART3, UART4, UART5,
+};
+
+/// Offset of the first `SYSCRG` software reset assert register.
+pub const SYSCRG_RESET_ASSERT: usize = 0x2f8;
+/// Offset of the first `SYSCRG` software reset status register.
+pub const SYSCRG_RESET_STATUS: usize = 0x308;
+
+// Number of status polls before giving up on a reset line.
+const RESET_POLL_COUNT: usize = 1000;
+
+/// Full peripheral reset, followed by re-initialization.
+pub trait Reset {
+    /// Pulses the peripheral CRG reset line(s), and re-runs peripheral initialization.
+    ///
+    /// Any in-progress transfer is lost.
+    fn reset(&mut self);
+}
+
+/// Provides the `SYSCRG` reset line(s) for a peripheral.
+pub trait CrgReset {
+    /// `SYSCRG` reset line indices for the peripheral.
+    const RESETS: &'static [u32];
+
+    /// Asserts, then deasserts the peripheral reset line(s).
+    fn pulse_reset() {
+        for &id in Self::RESETS {
+            set_reset(id, true);
+        }
+
+        for &id in Self::RESETS {
+            set_reset(id, false);
+        }
+    }
+}
+
+/// Sets whether the `SYSCRG` reset line `id` is asserted.
+///
+/// Waits for the reset status to reflect the new state.
+pub fn set_reset(id: u32, assert: bool) {
+    let base = SYSCRG::ptr() as usize;
+    let offset = (id as usize / 32) * 4;
+    let mask = 1u32 << (id % 32);
+
+    let assert_reg = (base + SYSCRG_RESET_ASSERT + offset) as *mut u32;
+    let status_reg = (base + SYSCRG_RESET_STATUS + offset) as *const u32;
+
+    // The status bit is cleared while the reset line is asserted
+    let done = if assert { 0 } else { mask };
+
+    // SAFETY: the reset registers are within the `SYSCRG` register space, and only the bit
+    // for the selected reset line is modified.
+    unsafe {
+        let val = assert_reg.read_volatile();
+        assert_reg.write_volatile(if assert { val | mask } else { val & !mask });
+
+        for _ in 0..RESET_POLL_COUNT {
+            if status_reg.read_volatile() & mask == done {
+                break;
+            }
+            core::hint::spin_loop();
+        }
+    }
+}
+
+macro_rules! impl_crg_reset {
+    ($periph:ident, [$($id:expr),+]) => {
+        impl CrgReset for $periph {
+            const RESETS: &'static [u32] = &[$($id),+];
+        }
+    };
+}
+
+impl_crg_reset!(SPI0, [69]);
+impl_crg_reset!(SPI1, [70]);
+impl_crg_reset!(SPI2, [71]);
+impl_crg_reset!(SPI3, [72]);
+impl_crg_reset!(SPI4, [73]);
+impl_crg_reset!(SPI5, [74]);
+impl_crg_reset!(SPI6, [75]);
+
+impl_crg_reset!(I2C0, [76]);
+impl_crg_reset!(I2C1, [77]);
+impl_crg_reset!(I2C2, [78]);
+impl_crg_reset!(I2C3, [79]);
+impl_crg_reset!(I2C4, [80]);
+impl_crg_reset!(I2C5, [81]);
+impl_crg_reset!(I2C6, [82]);
+
+// APB and core reset lines
+impl_crg_reset!(UART0, [83, 84]);
+impl_crg_reset!(UART1, [85, 86]);
+impl_crg_reset!(UART2, [87, 88]);
+impl_crg_reset!(UART3, [89, 90]);
+impl_crg_reset!(UART4, [91, 92]);
+impl_crg_reset!(UART5, [93, 94]);
+
+impl_crg_reset!(PWM, [99]);
@@ -54,22 +54,116 @@
 //! - packed data transfers for efficiency (but increased complexity)
 //! - unpacked transfers for simplicity (but reduced efficiency)
 //!
-//! The [ARM pl022 SSP SPI](https://documentation-service.arm.com/static/5e8e3b2afd977155116a92f7&rut=3d45d778b3f2b62fe659ebfb50905914d913d289f017585fb1c8e07383ea508a) peripheral also supports "Slave" mode, which is outside the `embedded-hal` traits, but could still be useful to `jh71xx-hal` users.
+//! The [ARM pl022 SSP SPI](https://documentation-service.arm.com/static/5e8e3b2afd977155116a92f7&rut=3d45d778b3f2b62fe659ebfb50905914d913d289f017585fb1c8e07383ea508a) peripheral also supports "Slave" mode, which is outside the `embedded-hal` traits. It is available through [Spi::new_slave], and the [Spi::slave_read] and [Spi::slave_write] functions.
 //!
-//! Similarly, the peripheral supports the Texas Instruments Synchronous Serial and Microwire serial frame formats (currently unsupported).
+//! Similarly, the peripheral supports the Texas Instruments Synchronous Serial and Microwire serial frame formats, which are available through [Spi::new_with_format].
 
-use embedded_hal::spi::{ErrorType, SpiBus};
+use core::cmp;
 
+use embedded_hal::delay::DelayNs;
+use embedded_hal::spi::{ErrorType, Mode, SpiBus};
+
+use crate::delay::{u74_mdelay, McycleDelay};
+use crate::reset::{CrgReset, Reset};
+
+mod device;
+mod dma;
 mod error;
+mod irq;
+mod microwire;
+#[cfg(feature = "mock")]
+mod mock;
 mod peripheral;
+mod slave;
+mod snapshot;
 
+pub use device::*;
+pub use dma::*;
 pub use error::*;
+pub use irq::*;
+#[cfg(feature = "mock")]
+pub use mock::*;
 pub use peripheral::*;
+pub use snapshot::*;
+
+/// Default transfer timeout in microseconds.
+pub const TIMEOUT_US: u32 = 1_000_000;
+/// Depth (in words) of the SSP transmit and receive FIFOs.
+pub const FIFO_DEPTH: usize = 8;
+
+// Discards unread words from the receive FIFO.
+//
+// Bounded by the FIFO depth, so a wedged peripheral cannot hang the caller.
+fn discard_rx<SPI: SpiPeripheral>(periph: &mut SPI) {
+    for _ in 0..FIFO_DEPTH {
+        if !periph.rne() {
+            break;
+        }
+        let _ = periph.data();
+    }
+}
+
+/// Solves the `SSPCLKOUT` frequency equation for the divisors closest to, but not exceeding
+/// `target_hz`:
+///
+/// ```no_build,no_run
+/// target_hz = sspclk_hz / (CPSDVSR * (1 + SCR))
+/// ```
+///
+/// Returns `Some((CPSDVSR, SCR))`, or `None` if no valid combination reaches `target_hz`.
+///
+/// Example:
+///
+/// ```
+/// # use jh71xx_hal::spi;
+/// assert_eq!(spi::clock_divisors(50_000_000, 1_000_000), Some((2, 24)));
+/// assert_eq!(spi::clock_divisors(50_000_000, 12_500_000), Some((2, 1)));
+/// assert_eq!(spi::clock_divisors(50_000_000, 100), None);
+/// ```
+pub const fn clock_divisors(sspclk_hz: u32, target_hz: u32) -> Option<(u8, u8)> {
+    if target_hz == 0 {
+        return None;
+    }
+
+    let sspclk = sspclk_hz as u64;
+    let target = target_hz as u64;
+
+    let mut best: Option<(u8, u8)> = None;
+    let mut best_hz = 0u64;
+    let mut cpsdvsr = 2u64;
+
+    while cpsdvsr <= 254 {
+        // Smallest `SCR` that does not exceed the target rate
+        let div = cpsdvsr * target;
+        let scr = sspclk.div_ceil(div);
+        let scr = if scr == 0 { 0 } else { scr - 1 };
+
+        if scr <= 255 {
+            let hz = sspclk / (cpsdvsr * (1 + scr));
+            if best.is_none() || hz > best_hz {
+                best = Some((cpsdvsr as u8, scr as u8));
+                best_hz = hz;
+            }
+            if hz == target {
+                break;
+            }
+        }
+
+        cpsdvsr += 2;
+    }
+
+    best
+}
 
 /// Represents an SPI peripheral on a JH71xx-based SoC.
+///
+/// Transfer timeouts are measured with the `D` [DelayNs] source.
 #[repr(C)]
-pub struct Spi<SPI: SpiPeripheral, const WORD: u8> {
+pub struct Spi<SPI: SpiPeripheral, const WORD: u8, D: DelayNs = McycleDelay> {
     periph: SPI,
+    delay: D,
+    timeout_us: u32,
+    last_error: Option<Error>,
 }
 
 impl<SPI: SpiPeripheral, const WORD: u8> Spi<SPI, WORD> {
@@ -86,179 +180,881 @@ impl<SPI: SpiPeripheral, const WORD: u8> Spi<SPI, WORD> {
     /// let dp = pac::Peripherals::take().unwrap();
     /// let _spi = spi::Spi::<pac::SPI0, 8>::new(dp.SPI0);
     /// ```
-    pub fn new(mut periph: SPI) -> Result<Self> {
-        let data_size = DataSize::from(WORD);
+    pub fn new(periph: SPI) -> Result<Self> {
+        Self::new_inner(periph, ModeSelect::Master, FrameFormat::Spi)
+    }
+
+    /// Creates a new [Spi] from an SPI peripheral using the provided [FrameFormat].
+    ///
+    /// In [FrameFormat::Microwire] mode, each frame is an 8-bit control word followed by a
+    /// `WORD`-bit data word from the slave. See [Spi::microwire_transfer] for how the `SpiBus`
+    /// functions map onto frames.
+    ///
+    /// Returns [Error::FrameFormat] for [FrameFormat::Reserved].
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{pac, spi};
+    /// use embedded_hal::spi::SpiBus;
+    ///
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let mut spi = spi::Spi::<pac::SPI0, 16>::new_with_format(dp.SPI0, spi::FrameFormat::Microwire)
+    ///     .unwrap();
+    ///
+    /// // Send the `READ` control word, and read the addressed data word
+    /// let mut data = [0u16; 1];
+    /// spi.transfer(data.as_mut(), [0x80u16].as_ref()).unwrap();
+    /// ```
+    pub fn new_with_format(periph: SPI, frame_format: FrameFormat) -> Result<Self> {
+        Self::new_inner(periph, ModeSelect::Master, frame_format)
+    }
+
+    /// Creates a new [Spi] from an SPI peripheral configured for `slave` operation mode.
+    ///
+    /// In `slave` mode, the external master drives `SSPCLKIN`, and the `SpiBus` transfers
+    /// will not generate a clock. Use [Spi::slave_read] and [Spi::slave_write] instead.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{pac, spi};
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let mut spi = spi::Spi::<pac::SPI0, 8>::new_slave(dp.SPI0).unwrap();
+    ///
+    /// let mut cmd = [0u8; 1];
+    /// spi.slave_read(cmd.as_mut()).unwrap();
+    /// spi.slave_write([0xa5u8].as_ref()).unwrap();
+    /// ```
+    pub fn new_slave(periph: SPI) -> Result<Self> {
+        Self::new_inner(periph, ModeSelect::Slave, FrameFormat::Spi)
+    }
+
+    fn new_inner(mut periph: SPI, ms: ModeSelect, frame_format: FrameFormat) -> Result<Self> {
+        if frame_format == FrameFormat::Reserved {
+            return Err(Error::FrameFormat);
+        }
+
+        let data_size = DataSize::from_bits(WORD).ok_or(Error::WordSize(WORD))?;
         match data_size {
             DataSize::Eight | DataSize::Sixteen => {
-                periph.set_dss(data_size);
-                periph.set_ms(ModeSelect::Master);
-                periph.set_frf(FrameFormat::Spi);
-                Ok(Self { periph })
+                Self::init(&mut periph, data_size, ms, frame_format);
+                Ok(Self {
+                    periph,
+                    delay: u74_mdelay(),
+                    timeout_us: TIMEOUT_US,
+                    last_error: None,
+                })
             }
             _ => Err(Error::DataSize(data_size)),
         }
     }
+}
 
-    /// Splits the [Spi] back into the inner peripheral type.
-    pub fn split(self) -> SPI {
-        self.periph
+impl<SPI: SpiPeripheral, const WORD: u8, D: DelayNs> Spi<SPI, WORD, D> {
+    /// Builder function that sets the [DelayNs] source used to measure transfer timeouts.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{delay, pac, spi};
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let _spi = spi::Spi::<pac::SPI0, 8>::new(dp.SPI0)
+    ///     .unwrap()
+    ///     .with_delay(delay::u74_udelay());
+    /// ```
+    pub fn with_delay<D2: DelayNs>(self, delay: D2) -> Spi<SPI, WORD, D2> {
+        Spi {
+            periph: self.periph,
+            delay,
+            timeout_us: self.timeout_us,
+            last_error: self.last_error,
+        }
     }
-}
 
-impl<SPI: SpiPeripheral, const WORD: u8> ErrorType for Spi<SPI, WORD> {
-    type Error = Error;
-}
+    /// Gets the transfer timeout (in microseconds).
+    pub const fn timeout_us(&self) -> u32 {
+        self.timeout_us
+    }
 
-impl<SPI: SpiPeripheral> SpiBus<u8> for Spi<SPI, 8> {
-    fn read(&mut self, words: &mut [u8]) -> Result<()> {
-        for word in words.iter_mut() {
-            // Spin until receive FIFO is full
-            while !self.periph.rff() || self.periph.bsy() {
-                // Check for receive timeout interrupt (after masking)
-                if self.periph.rtmis() {
-                    self.periph.rtic(true);
-                    return Err(Error::Timeout);
-                // Check for receive overrun interrupt (after masking)
-                } else if self.periph.rormis() {
-                    self.periph.roric(true);
-                    return Err(Error::Overrun);
-                }
+    /// Sets the transfer timeout (in microseconds).
+    ///
+    /// The timeout bounds each wait on the peripheral in `read`, `write`, `flush`, and
+    /// [Spi::wait_idle].
+    ///
+    /// **NOTE**: `timeout_us` must be greater than zero, no-op otherwise.
+    pub fn set_timeout_us(&mut self, timeout_us: u32) {
+        if timeout_us > 0 {
+            self.timeout_us = timeout_us;
+        }
+    }
+
+    /// Builder function that sets the transfer timeout (in microseconds).
+    ///
+    /// **NOTE**: `timeout_us` must be greater than zero, no-op otherwise.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{pac, spi};
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let _spi = spi::Spi::<pac::SPI0, 8>::new(dp.SPI0)
+    ///     .unwrap()
+    ///     .with_timeout_us(10_000);
+    /// ```
+    pub fn with_timeout_us(mut self, timeout_us: u32) -> Self {
+        self.set_timeout_us(timeout_us);
+        self
+    }
+
+    /// Gets the last [Error] returned by a `SpiBus` operation, if any.
+    ///
+    /// Useful for retrieving the details of an error handled through the `embedded-hal`
+    /// [ErrorKind](embedded_hal::spi::ErrorKind), e.g. by a generic device driver, which
+    /// reports most errors as `ErrorKind::Other`.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{pac, spi};
+    /// use embedded_hal::spi::{Error as _, SpiBus};
+    ///
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let mut spi = spi::Spi::<pac::SPI0, 8>::new(dp.SPI0).unwrap();
+    ///
+    /// let mut data = [0u8; 4];
+    /// if let Err(err) = spi.read(data.as_mut()) {
+    ///     let _kind = err.kind();
+    ///     assert_eq!(spi.last_error(), Some(err));
+    /// }
+    /// ```
+    pub const fn last_error(&self) -> Option<Error> {
+        self.last_error
+    }
+
+    /// Clears the last [Error] returned by a `SpiBus` operation.
+    pub fn clear_last_error(&mut self) {
+        self.last_error = None;
+    }
+
+    // Records the error of a `SpiBus` operation result, see [Spi::last_error].
+    fn record_err<T>(&mut self, res: Result<T>) -> Result<T> {
+        if let Err(err) = res {
+            self.last_error = Some(err);
+        }
+        res
+    }
+
+    /// Gets whether the peripheral is busy transmitting, and/or receiving a frame, or the
+    /// transmit FIFO is not empty.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{pac, spi};
+    /// use embedded_hal::spi::SpiBus;
+    ///
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let mut spi = spi::Spi::<pac::SPI0, 8>::new(dp.SPI0).unwrap();
+    ///
+    /// let chunk = [0u8; spi::FIFO_DEPTH];
+    /// spi.write(chunk.as_ref()).unwrap();
+    ///
+    /// // ... do other work while the chunk is clocked out ...
+    /// while spi.is_busy() {}
+    /// ```
+    pub fn is_busy(&self) -> bool {
+        self.periph.bsy()
+    }
+
+    /// Gets whether the transmit FIFO is empty.
+    pub fn tx_fifo_empty(&self) -> bool {
+        self.periph.tfe()
+    }
+
+    /// Gets whether the transmit FIFO is full.
+    pub fn tx_fifo_full(&self) -> bool {
+        !self.periph.tnf()
+    }
+
+    /// Gets whether the receive FIFO is empty.
+    pub fn rx_fifo_empty(&self) -> bool {
+        !self.periph.rne()
+    }
+
+    /// Gets whether the receive FIFO is full.
+    pub fn rx_fifo_full(&self) -> bool {
+        self.periph.rff()
+    }
+
+    /// Waits for the transmit FIFO to empty, and the peripheral to become idle.
+    ///
+    /// Returns [Error::Timeout] if the peripheral does not become idle within the timeout.
+    pub fn wait_idle(&mut self) -> Result<()> {
+        self.wait_for(|periph| periph.tfe() && !periph.bsy())
+    }
+
+    // Polls `cond` until it is true, or the timeout elapses.
+    fn wait_for(&mut self, cond: impl Fn(&mut SPI) -> bool) -> Result<()> {
+        let mut elapsed = 0u32;
+
+        while !cond(&mut self.periph) {
+            if elapsed >= self.timeout_us {
+                return Err(Error::Timeout);
             }
-            // FIXME: support 4-7 bit data sizes
-            *word = (self.periph.data() & 0xff) as u8;
+            self.delay.delay_us(1);
+            elapsed = elapsed.saturating_add(1);
         }
+
         Ok(())
     }
 
-    fn write(&mut self, words: &[u8]) -> Result<()> {
-        for word in words.iter() {
-            while !self.periph.tfe() {}
-            self.periph.set_data(*word);
+    fn init(periph: &mut SPI, data_size: DataSize, ms: ModeSelect, frame_format: FrameFormat) {
+        periph.set_dss(data_size);
+        periph.set_ms(ms);
+        periph.set_frf(frame_format);
+        // Unmask the receive error interrupts checked by the `SpiBus` read loops
+        periph.set_rtim(InterruptMask::NotMasked);
+        periph.set_rorim(InterruptMask::NotMasked);
+    }
+
+    /// Gets the [FrameFormat] configured in the peripheral.
+    pub fn frame_format(&self) -> FrameFormat {
+        self.periph.frf()
+    }
+
+    // Gets the mask for valid bits of a `WORD`-bit data word.
+    const fn word_mask() -> u16 {
+        ((1u32 << WORD) - 1) as u16
+    }
+
+    // Gets whether transfers use the half-duplex Microwire frame format.
+    fn is_microwire(&self) -> bool {
+        self.frame_format() == FrameFormat::Microwire
+    }
+
+    /// Gets the SPI [Mode] (clock polarity and phase) configured in the peripheral.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{pac, spi};
+    /// use embedded_hal::spi::MODE_0;
+    ///
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let spi = spi::Spi::<pac::SPI0, 8>::new(dp.SPI0).unwrap();
+    /// assert_eq!(spi.mode(), MODE_0);
+    /// ```
+    pub fn mode(&self) -> Mode {
+        Mode {
+            polarity: self.periph.spo().into(),
+            phase: self.periph.sph().into(),
         }
+    }
+
+    /// Sets the SPI [Mode] (clock polarity and phase) of the peripheral.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{pac, spi};
+    /// use embedded_hal::spi::MODE_3;
+    ///
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let mut spi = spi::Spi::<pac::SPI0, 8>::new(dp.SPI0).unwrap();
+    /// spi.set_mode(MODE_3);
+    /// ```
+    pub fn set_mode(&mut self, mode: Mode) {
+        self.periph.set_spo(mode.polarity.into());
+        self.periph.set_sph(mode.phase.into());
+    }
+
+    /// Builder function that sets the SPI [Mode] (clock polarity and phase).
+    pub fn with_mode(mut self, mode: Mode) -> Self {
+        self.set_mode(mode);
+        self
+    }
+
+    /// Gets whether loopback mode is enabled.
+    pub fn loopback(&self) -> bool {
+        self.periph.loopback()
+    }
+
+    /// Enables, or disables loopback mode.
+    ///
+    /// In loopback mode, transmitted words are routed internally back to the receiver, so
+    /// transfers read back the written words without a wired `MISO`. Useful as a self-test of
+    /// the SPI peripheral.
+    ///
+    /// **NOTE**: loopback mode must be disabled before transfers with real devices, otherwise
+    /// data from the device is ignored.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{pac, spi};
+    /// use embedded_hal::spi::SpiBus;
+    ///
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let mut spi = spi::Spi::<pac::SPI0, 8>::new(dp.SPI0).unwrap();
+    ///
+    /// spi.set_loopback(true);
+    /// let mut buf = [0xa5u8, 0x5a];
+    /// spi.transfer_in_place(buf.as_mut()).unwrap();
+    /// assert_eq!(buf, [0xa5, 0x5a]);
+    /// spi.set_loopback(false);
+    /// ```
+    pub fn set_loopback(&mut self, enable: bool) {
+        self.periph.set_loopback(enable);
+    }
+
+    /// Builder function that enables, or disables loopback mode.
+    ///
+    /// See [Spi::set_loopback] for details.
+    pub fn with_loopback(mut self, enable: bool) -> Self {
+        self.set_loopback(enable);
+        self
+    }
+
+    /// Gets whether receive timeout detection is enabled.
+    pub fn rx_timeout_enabled(&self) -> bool {
+        self.periph.rtim() == InterruptMask::NotMasked
+    }
+
+    /// Enables, or disables receive timeout detection.
+    ///
+    /// When enabled (the default), `SpiBus` reads return [Error::Timeout] once the receive
+    /// FIFO has held unread data for 32 bit periods without new frames arriving, e.g. when the
+    /// slave stops responding. When disabled, reads only fail on the transfer timeout set with
+    /// [Spi::set_timeout_us].
+    ///
+    /// The receive overrun interrupt is always unmasked, so overruns are reported as
+    /// [Error::Overrun].
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{pac, spi};
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let mut spi = spi::Spi::<pac::SPI0, 8>::new(dp.SPI0).unwrap();
+    /// assert!(spi.rx_timeout_enabled());
+    ///
+    /// spi.set_rx_timeout_enabled(false);
+    /// assert!(!spi.rx_timeout_enabled());
+    /// ```
+    pub fn set_rx_timeout_enabled(&mut self, enable: bool) {
+        self.periph.rtic(true);
+        self.periph.set_rtim(enable.into());
+    }
+
+    /// Builder function that enables, or disables receive timeout detection.
+    pub fn with_rx_timeout_enabled(mut self, enable: bool) -> Self {
+        self.set_rx_timeout_enabled(enable);
+        self
+    }
+
+    /// Gets the `SSPCLKOUT` serial clock rate (in Hz) for the `sspclk_hz` input clock.
+    pub fn clock_hz(&self, sspclk_hz: u32) -> u32 {
+        let cpsdvsr = u8::from(self.periph.cpsdvsr()) as u32;
+        let scr = self.periph.scr() as u32;
+
+        sspclk_hz / (cpsdvsr * (1 + scr))
+    }
+
+    /// Sets the `SSPCLKOUT` serial clock rate to the closest rate not exceeding `target_hz`.
+    ///
+    /// - `sspclk_hz`: `SSPCLK` input clock rate in Hz
+    /// - `target_hz`: requested serial clock rate in Hz
+    ///
+    /// Returns [Error::Other] if no divisor combination can reach `target_hz`.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{pac, spi};
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let mut spi = spi::Spi::<pac::SPI0, 8>::new(dp.SPI0).unwrap();
+    /// spi.set_clock_hz(50_000_000, 1_000_000).unwrap();
+    /// assert_eq!(spi.clock_hz(50_000_000), 1_000_000);
+    /// ```
+    pub fn set_clock_hz(&mut self, sspclk_hz: u32, target_hz: u32) -> Result<()> {
+        let (cpsdvsr, scr) = clock_divisors(sspclk_hz, target_hz).ok_or(Error::Other)?;
+
+        self.periph.set_cpsdvsr(cpsdvsr.into());
+        self.periph.set_scr(scr);
+
         Ok(())
     }
 
-    fn transfer(&mut self, read: &mut [u8], write: &[u8]) -> Result<()> {
-        let rlen = read.len();
-        let wlen = write.len();
-        let len = core::cmp::min(rlen, wlen);
+    /// Builder function that sets the `SSPCLKOUT` serial clock rate.
+    ///
+    /// See [Spi::set_clock_hz] for details.
+    pub fn with_clock_hz(mut self, sspclk_hz: u32, target_hz: u32) -> Result<Self> {
+        self.set_clock_hz(sspclk_hz, target_hz)?;
+        Ok(self)
+    }
 
-        for i in 0..len {
-            self.read(&mut read[i..i + 1])?;
-            self.write(&write[i..i + 1])?;
-        }
+    /// Gets the MMIO base address of the wrapped SPI peripheral.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{pac, spi};
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let spi = spi::Spi::<pac::SPI0, 8>::new(dp.SPI0).unwrap();
+    /// assert_eq!(spi.base_addr(), pac::SPI0::ptr() as usize);
+    /// ```
+    pub fn base_addr(&self) -> usize {
+        self.periph.base_addr()
+    }
+
+    /// Captures a [SpiRegisterSnapshot] of the peripheral registers.
+    ///
+    /// Read-only: the data register is never read, and no interrupts are cleared, so transfer
+    /// state is not disturbed.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{pac, spi};
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let spi = spi::Spi::<pac::SPI0, 8>::new(dp.SPI0).unwrap();
+    ///
+    /// let snapshot = spi.dump_registers();
+    /// if snapshot.status.is_set(spi::SpiStatus::BSY) && snapshot.ms == spi::ModeSelect::Slave {
+    ///     // stuck waiting on a master that never clocks the bus
+    /// }
+    /// ```
+    pub fn dump_registers(&self) -> SpiRegisterSnapshot {
+        SpiRegisterSnapshot::read(&self.periph)
+    }
+
+    /// Splits the [Spi] back into the inner peripheral type.
+    pub fn split(self) -> SPI {
+        self.periph
+    }
 
-        if rlen > len {
-            self.read(&mut read[len..])
-        } else if wlen > len {
-            self.write(&write[len..])
+    /// Performs a strict duplex transfer, reading and writing the same number of words.
+    ///
+    /// Unlike [`SpiBus::transfer`], buffers of differing lengths are rejected with
+    /// [Error::LengthMismatch] instead of finishing with a read-only or write-only tail.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{pac, spi};
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let mut spi = spi::Spi::<pac::SPI0, 8>::new(dp.SPI0).unwrap();
+    /// let mut read_buf = [0u8; 2];
+    /// let write_buf = [0u8; 2];
+    /// spi.transfer_exact(read_buf.as_mut(), write_buf.as_ref()).unwrap();
+    /// ```
+    pub fn transfer_exact<W: Copy + 'static>(&mut self, read: &mut [W], write: &[W]) -> Result<()>
+    where
+        Self: SpiBus<W, Error = Error>,
+    {
+        if read.len() != write.len() {
+            Err(Error::LengthMismatch {
+                read: read.len(),
+                write: write.len(),
+            })
         } else {
-            Ok(())
+            SpiBus::transfer(self, read, write)
         }
     }
 
-    fn transfer_in_place(&mut self, words: &mut [u8]) -> Result<()> {
-        for i in 0..words.len() {
-            self.write(&words[i..i + 1])?;
-            self.read(&mut words[i..i + 1])?;
+    /// Writes 8-bit `cmd` words, then reads 16-bit words into `read`.
+    ///
+    /// The data size is switched from 8 to 16 bits between the phases, after the command
+    /// frames are out, e.g. for ADCs taking a byte command, and returning 16-bit samples. The
+    /// words received during the command phase are discarded, and zero words are transmitted
+    /// during the read phase. The data size configured for `WORD` is restored afterwards.
+    ///
+    /// Use [SpiWithCs::write_u8_read_u16] to keep `CS` asserted across both phases.
+    ///
+    /// Returns [Error::FrameFormat] in [FrameFormat::Microwire] mode, which has fixed 8-bit
+    /// control words.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{pac, spi};
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let mut spi = spi::Spi::<pac::SPI0, 8>::new(dp.SPI0).unwrap();
+    ///
+    /// // Start a conversion on channel 2, and read back two samples
+    /// let mut samples = [0u16; 2];
+    /// spi.write_u8_read_u16(&[0x80 | 0x02], samples.as_mut()).unwrap();
+    /// ```
+    pub fn write_u8_read_u16(&mut self, cmd: &[u8], read: &mut [u16]) -> Result<()> {
+        if self.is_microwire() {
+            return Err(Error::FrameFormat);
         }
+
+        let data_size = self.periph.dss();
+        let res = self.write_u8_read_u16_inner(cmd, read);
+
+        // Restore the data size, even if a phase failed
+        let drain_res = self.drain();
+        self.periph.set_dss(data_size);
+
+        res.and(drain_res)
+    }
+
+    // Performs the command, and read phases of [Spi::write_u8_read_u16].
+    fn write_u8_read_u16_inner(&mut self, cmd: &[u8], read: &mut [u16]) -> Result<()> {
+        // No frame may be in flight while the data size changes
+        self.drain()?;
+        self.periph.set_dss(DataSize::Eight);
+        self.transfer_fifo_masked::<u8>(&mut [], Some(cmd), 0xff)?;
+
+        self.drain()?;
+        self.periph.set_dss(DataSize::Sixteen);
+        self.transfer_fifo_masked::<u16>(read, Some(&[]), 0xffff)
+    }
+
+    // Performs a full-duplex transfer, keeping the FIFOs saturated.
+    //
+    // Words are pushed while the transmit FIFO is not full, then received words are drained,
+    // with at most [FIFO_DEPTH] words in flight so the receive FIFO cannot overrun.
+    //
+    // With `write` set to `None`, the words are transmitted from `read` before being
+    // overwritten (in-place transfer). Otherwise, zero words are transmitted past the end of
+    // `write`, and words received past the end of `read` are discarded.
+    fn transfer_fifo<W: Copy + Into<u16> + TryFrom<u16>>(
+        &mut self,
+        read: &mut [W],
+        write: Option<&[W]>,
+    ) -> Result<()> {
+        self.transfer_fifo_masked(read, write, Self::word_mask())
+    }
+
+    // Performs a full-duplex transfer of words masked with `mask`, see [Spi::transfer_fifo].
+    //
+    // Used when the configured data size differs from `WORD`.
+    fn transfer_fifo_masked<W: Copy + Into<u16> + TryFrom<u16>>(
+        &mut self,
+        read: &mut [W],
+        write: Option<&[W]>,
+        mask: u16,
+    ) -> Result<()> {
+        // Discard words left behind by earlier writes, which never read the receive FIFO
+        self.drain()?;
+
+        let len = match write {
+            Some(write) => cmp::max(read.len(), write.len()),
+            None => read.len(),
+        };
+        let (mut tx, mut rx) = (0usize, 0usize);
+
+        while rx < len {
+            while tx < len && tx - rx < FIFO_DEPTH && self.periph.tnf() {
+                let word = match write {
+                    Some(write) => write.get(tx).map_or(0, |&w| w.into()),
+                    None => read[tx].into(),
+                };
+                self.periph.set_data(word & mask);
+                tx += 1;
+            }
+
+            self.wait_for(|periph| periph.rne() || periph.rormis())?;
+
+            if self.periph.rormis() {
+                self.periph.roric(true);
+                return Err(Error::Overrun);
+            }
+
+            while rx < tx && self.periph.rne() {
+                let word = self.periph.data() & mask;
+                if let Some(dst) = read.get_mut(rx) {
+                    *dst = W::try_from(word).map_err(|_| Error::WordSize(WORD))?;
+                }
+                rx += 1;
+            }
+        }
+
         Ok(())
     }
 
-    fn flush(&mut self) -> Result<()> {
-        // clear receiver interrupts
+    /// Waits for the transmit FIFO to empty, and the peripheral to become idle.
+    ///
+    /// Any residual words in the receive FIFO are read out and discarded.
+    ///
+    /// Returns [Error::Timeout] if the FIFOs do not drain within the timeout, e.g. when
+    /// `SSPCLK` is not running.
+    fn drain(&mut self) -> Result<()> {
+        // wait while FIFOs are not empty, and/or the peripheral is busy
+        self.wait_for(|periph| {
+            // discard unread words, otherwise the receive FIFO never empties
+            discard_rx(periph);
+            periph.tfe() && !periph.bsy()
+        })?;
+
+        // discard the last received words, and clear the receiver interrupts they latched
+        discard_rx(&mut self.periph);
         self.periph.roric(true);
         self.periph.rtic(true);
 
-        // spin while FIFOs are not empty, and/or the peripheral is busy
-        while !self.periph.tfe() || self.periph.rne() || self.periph.bsy() {
-            core::hint::spin_loop();
-        }
-
         Ok(())
     }
 }
 
-impl<SPI: SpiPeripheral> SpiBus<u16> for Spi<SPI, 16> {
-    fn read(&mut self, words: &mut [u16]) -> Result<()> {
+impl<SPI: SpiPeripheral + CrgReset, const WORD: u8, D: DelayNs> Reset for Spi<SPI, WORD, D> {
+    fn reset(&mut self) {
+        // Preserve the bus configuration across the reset
+        let data_size = self.periph.dss();
+        let ms = self.periph.ms();
+        let frame_format = self.periph.frf();
+        let mode = self.mode();
+        let scr = self.periph.scr();
+        let cpsdvsr = self.periph.cpsdvsr();
+        let loopback = self.periph.loopback();
+        let rtim = self.periph.rtim();
+
+        SPI::pulse_reset();
+
+        Self::init(&mut self.periph, data_size, ms, frame_format);
+        self.periph.set_rtim(rtim);
+        self.set_mode(mode);
+        self.periph.set_scr(scr);
+        self.periph.set_cpsdvsr(cpsdvsr);
+        self.periph.set_loopback(loopback);
+    }
+}
+
+impl<SPI: SpiPeripheral + CrgReset, const WORD: u8, D: DelayNs> Spi<SPI, WORD, D> {
+    /// Discards the contents of both FIFOs, e.g. to recover from an [Error::Overrun].
+    ///
+    /// The SSP keeps its FIFO contents while disabled, so the peripheral is pulsed through its
+    /// `SYSCRG` reset line instead, emptying both FIFOs, and clearing the receive interrupts.
+    /// The configuration is restored as with [Reset::reset], along with the interrupt masks,
+    /// DMA enables, and the port enable (`SSE`).
+    ///
+    /// Unlike [`SpiBus::flush`], which waits for pending words to be clocked out, this returns
+    /// immediately.
+    ///
+    /// **NOTE**: any word in flight on the bus is cut short, so the peer may see a truncated
+    /// frame. Deassert the chip select before the next transfer.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{pac, spi};
+    /// use embedded_hal::spi::SpiBus;
+    ///
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let mut spi = spi::Spi::<pac::SPI0, 8>::new(dp.SPI0).unwrap();
+    ///
+    /// let mut buf = [0u8; 64];
+    /// if let Err(spi::Error::Overrun) = spi.transfer_in_place(buf.as_mut()) {
+    ///     // drop the stale words, instead of sending them with the next transaction
+    ///     spi.reset_fifos();
+    /// }
+    /// ```
+    pub fn reset_fifos(&mut self) {
+        // Registers not preserved by `Reset::reset`
+        let rorim = self.periph.rorim();
+        let rxim = self.periph.rxim();
+        let txim = self.periph.txim();
+        let txdmae = self.periph.txdmae();
+        let rxdmae = self.periph.rxdmae();
+        let sse = self.periph.sse();
+
+        self.reset();
+
+        self.periph.set_rorim(rorim);
+        self.periph.set_rxim(rxim);
+        self.periph.set_txim(txim);
+        self.periph.set_txdmae(txdmae);
+        self.periph.set_rxdmae(rxdmae);
+        self.periph.set_sse(sse);
+    }
+}
+
+impl<SPI: SpiPeripheral, const WORD: u8, D: DelayNs> ErrorType for Spi<SPI, WORD, D> {
+    type Error = Error;
+}
+
+impl<SPI: SpiPeripheral, const WORD: u8, D: DelayNs> Spi<SPI, WORD, D> {
+    // Reads `words` for the `SpiBus` read operation.
+    fn read_words<W: Copy + Into<u16> + TryFrom<u16>>(&mut self, words: &mut [W]) -> Result<()> {
+        if self.is_microwire() {
+            return self.microwire_transfer(words, &[]);
+        }
+
         for word in words.iter_mut() {
-            // Spin until receive FIFO is full
-            while !self.periph.rff() || self.periph.bsy() {
-                // Check for receive timeout interrupt (after masking)
-                if self.periph.rtmis() {
-                    self.periph.rtic(true);
-                    return Err(Error::Timeout);
-                // Check for receive overrun interrupt (after masking)
-                } else if self.periph.rormis() {
-                    self.periph.roric(true);
-                    return Err(Error::Overrun);
-                }
+            // Wait until receive FIFO is full, or a receive error interrupt fires
+            self.wait_for(|periph| {
+                (periph.rff() && !periph.bsy()) || periph.rtmis() || periph.rormis()
+            })?;
+
+            // Check for receive timeout interrupt (after masking)
+            if self.periph.rtmis() {
+                self.periph.rtic(true);
+                return Err(Error::Timeout);
+            // Check for receive overrun interrupt (after masking)
+            } else if self.periph.rormis() {
+                self.periph.roric(true);
+                return Err(Error::Overrun);
             }
             // FIXME: support 4-15 bit data sizes
-            *word = self.periph.data();
+            *word = W::try_from(self.periph.data() & Self::word_mask())
+                .map_err(|_| Error::WordSize(WORD))?;
         }
         Ok(())
     }
 
-    fn write(&mut self, words: &[u16]) -> Result<()> {
-        for word in words.iter() {
-            while !self.periph.tfe() {}
-            self.periph.set_data(*word);
+    // Writes `words` for the `SpiBus` write operation.
+    fn write_words<W: Copy + Into<u16> + TryFrom<u16>>(&mut self, words: &[W]) -> Result<()> {
+        if self.is_microwire() {
+            return self.microwire_transfer(&mut [], words);
+        }
+
+        for &word in words.iter() {
+            self.wait_for(|periph| periph.tfe())?;
+            self.periph.set_data(word);
         }
         Ok(())
     }
 
-    fn transfer(&mut self, read: &mut [u16], write: &[u16]) -> Result<()> {
-        let rlen = read.len();
-        let wlen = write.len();
-        let len = core::cmp::min(rlen, wlen);
-
-        for i in 0..len {
-            self.read(&mut read[i..i + 1])?;
-            self.write(&write[i..i + 1])?;
+    // Performs the `SpiBus` transfer operation.
+    fn transfer_words<W: Copy + Into<u16> + TryFrom<u16>>(
+        &mut self,
+        read: &mut [W],
+        write: &[W],
+    ) -> Result<()> {
+        if self.is_microwire() {
+            return self.microwire_transfer(read, write);
         }
 
-        if rlen > len {
-            self.read(&mut read[len..])
-        } else if wlen > len {
-            self.write(&write[len..])
-        } else {
-            Ok(())
+        self.transfer_fifo(read, Some(write))
+    }
+
+    // Performs the `SpiBus` in-place transfer operation.
+    fn transfer_words_in_place<W: Copy + Into<u16> + TryFrom<u16>>(
+        &mut self,
+        words: &mut [W],
+    ) -> Result<()> {
+        if self.is_microwire() {
+            return self.microwire_transfer_in_place(words);
         }
+
+        self.transfer_fifo(words, None)
+    }
+}
+
+impl<SPI: SpiPeripheral, D: DelayNs> SpiBus<u8> for Spi<SPI, 8, D> {
+    fn read(&mut self, words: &mut [u8]) -> Result<()> {
+        let res = self.read_words(words);
+        self.record_err(res)
+    }
+
+    fn write(&mut self, words: &[u8]) -> Result<()> {
+        let res = self.write_words(words);
+        self.record_err(res)
+    }
+
+    fn transfer(&mut self, read: &mut [u8], write: &[u8]) -> Result<()> {
+        let res = self.transfer_words(read, write);
+        self.record_err(res)
+    }
+
+    fn transfer_in_place(&mut self, words: &mut [u8]) -> Result<()> {
+        let res = self.transfer_words_in_place(words);
+        self.record_err(res)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        let res = self.drain();
+        self.record_err(res)
+    }
+}
+
+impl<SPI: SpiPeripheral, D: DelayNs> SpiBus<u16> for Spi<SPI, 16, D> {
+    fn read(&mut self, words: &mut [u16]) -> Result<()> {
+        let res = self.read_words(words);
+        self.record_err(res)
+    }
+
+    fn write(&mut self, words: &[u16]) -> Result<()> {
+        let res = self.write_words(words);
+        self.record_err(res)
+    }
+
+    fn transfer(&mut self, read: &mut [u16], write: &[u16]) -> Result<()> {
+        let res = self.transfer_words(read, write);
+        self.record_err(res)
     }
 
     fn transfer_in_place(&mut self, words: &mut [u16]) -> Result<()> {
-        for i in 0..words.len() {
-            self.write(&words[i..i + 1])?;
-            self.read(&mut words[i..i + 1])?;
-        }
-        Ok(())
+        let res = self.transfer_words_in_place(words);
+        self.record_err(res)
     }
 
     fn flush(&mut self) -> Result<()> {
-        // clear receiver interrupts
-        self.periph.roric(true);
-        self.periph.rtic(true);
+        let res = self.drain();
+        self.record_err(res)
+    }
+}
 
-        // spin while FIFOs are not empty, and/or the peripheral is busy
-        while !self.periph.tfe() || self.periph.rne() || self.periph.bsy() {}
+impl<SPI: SpiPeripheral, const WORD: u8, D: DelayNs> Spi<SPI, WORD, D> {
+    // Changes the data size to `W` bits, keeping the rest of the peripheral configuration.
+    //
+    // Waits for in-flight frames to finish first, so no frame is sent with a mixed data size.
+    fn into_word_size<const W: u8>(mut self) -> Result<Spi<SPI, W, D>> {
+        let data_size = DataSize::from_bits(W).ok_or(Error::WordSize(W))?;
 
-        Ok(())
+        self.drain()?;
+        self.periph.set_dss(data_size);
+
+        Ok(Spi {
+            periph: self.periph,
+            delay: self.delay,
+            timeout_us: self.timeout_us,
+            last_error: self.last_error,
+        })
     }
 }
 
-impl<SPI: SpiPeripheral> TryFrom<Spi<SPI, 8>> for Spi<SPI, 16> {
+/// Converts an 8-bit [Spi] into a 16-bit [Spi].
+///
+/// Only the data size changes, the clock rate, [Mode], [FrameFormat], operation mode, and
+/// timeouts are kept.
+///
+/// Example:
+///
+/// ```no_run
+/// # use jh71xx_hal::{pac, spi};
+/// let dp = pac::Peripherals::take().unwrap();
+/// let spi8 = spi::Spi::<pac::SPI0, 8>::new(dp.SPI0)
+///     .unwrap()
+///     .with_clock_hz(50_000_000, 10_000_000)
+///     .unwrap();
+/// let clock_hz = spi8.clock_hz(50_000_000);
+///
+/// // ... send 8-bit display commands ...
+///
+/// let spi16 = spi::Spi::<pac::SPI0, 16>::try_from(spi8).unwrap();
+/// assert_eq!(spi16.clock_hz(50_000_000), clock_hz);
+///
+/// // ... send 16-bit pixel data ...
+/// ```
+impl<SPI: SpiPeripheral, D: DelayNs> TryFrom<Spi<SPI, 8, D>> for Spi<SPI, 16, D> {
     type Error = Error;
 
-    fn try_from(val: Spi<SPI, 8>) -> Result<Self> {
-        Self::new(val.split())
+    fn try_from(val: Spi<SPI, 8, D>) -> Result<Self> {
+        val.into_word_size()
     }
 }
 
-impl<SPI: SpiPeripheral> TryFrom<Spi<SPI, 16>> for Spi<SPI, 8> {
+/// Converts a 16-bit [Spi] into an 8-bit [Spi].
+///
+/// Only the data size changes, the clock rate, [Mode], [FrameFormat], operation mode, and
+/// timeouts are kept.
+impl<SPI: SpiPeripheral, D: DelayNs> TryFrom<Spi<SPI, 16, D>> for Spi<SPI, 8, D> {
     type Error = Error;
 
-    fn try_from(val: Spi<SPI, 16>) -> Result<Self> {
-        Self::new(val.split())
+    fn try_from(val: Spi<SPI, 16, D>) -> Result<Self> {
+        val.into_word_size()
     }
 }
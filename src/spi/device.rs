@@ -0,0 +1,110 @@
+use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::OutputPin;
+use embedded_hal::spi::{ErrorType, Operation, SpiBus, SpiDevice};
+
+use crate::delay::McycleDelay;
+
+use super::{Error, Result, Spi, SpiPeripheral};
+
+/// SPI device with a software (GPIO) chip-select.
+///
+/// The SSP `SSPFSSOUT` signal is only asserted per-frame, so [SpiWithCs] holds the `CS` pin low
+/// for the whole [SpiDevice] transaction instead.
+///
+/// Example:
+///
+/// ```no_run
+/// # use jh71xx_hal::{gpio, pac, spi};
+/// use embedded_hal::spi::SpiDevice;
+///
+/// let dp = pac::Peripherals::take().unwrap();
+/// let cs = gpio::get_gpio(dp.SYS_PINCTRL.gpio_49()).into_enabled_output();
+/// let bus = spi::Spi::<pac::SPI0, 8>::new(dp.SPI0).unwrap();
+///
+/// let mut dev = spi::SpiWithCs::new(bus, cs).unwrap();
+/// let mut id = [0u8; 3];
+/// dev.transfer(id.as_mut(), [0x9f].as_ref()).unwrap();
+/// ```
+pub struct SpiWithCs<SPI: SpiPeripheral, const WORD: u8, CS: OutputPin, D: DelayNs = McycleDelay> {
+    spi: Spi<SPI, WORD, D>,
+    cs: CS,
+}
+
+impl<SPI: SpiPeripheral, const WORD: u8, CS: OutputPin, D: DelayNs> SpiWithCs<SPI, WORD, CS, D> {
+    /// Creates a new [SpiWithCs] from an [Spi] bus, and a `CS` output pin.
+    ///
+    /// The `CS` pin is deasserted (driven high).
+    pub fn new(spi: Spi<SPI, WORD, D>, mut cs: CS) -> Result<Self> {
+        cs.set_high().map_err(|_| Error::ChipSelectFault)?;
+        Ok(Self { spi, cs })
+    }
+
+    /// Gets a reference to the inner [Spi] bus.
+    pub fn bus(&self) -> &Spi<SPI, WORD, D> {
+        &self.spi
+    }
+
+    /// Gets a mutable reference to the inner [Spi] bus.
+    ///
+    /// Transfers on the bus do not assert the `CS` pin.
+    pub fn bus_mut(&mut self) -> &mut Spi<SPI, WORD, D> {
+        &mut self.spi
+    }
+
+    /// Writes 8-bit `cmd` words, then reads 16-bit words into `read`, with `CS` asserted for
+    /// both phases.
+    ///
+    /// See [Spi::write_u8_read_u16].
+    pub fn write_u8_read_u16(&mut self, cmd: &[u8], read: &mut [u16]) -> Result<()> {
+        self.cs.set_low().map_err(|_| Error::ChipSelectFault)?;
+
+        let res = self.spi.write_u8_read_u16(cmd, read);
+
+        // Always deassert CS, even if a transfer failed
+        let cs_res = self.cs.set_high().map_err(|_| Error::ChipSelectFault);
+
+        res.and(cs_res)
+    }
+
+    /// Splits the [SpiWithCs] back into the [Spi] bus, and the `CS` pin.
+    pub fn split(self) -> (Spi<SPI, WORD, D>, CS) {
+        (self.spi, self.cs)
+    }
+}
+
+impl<SPI: SpiPeripheral, const WORD: u8, CS: OutputPin, D: DelayNs> ErrorType
+    for SpiWithCs<SPI, WORD, CS, D>
+{
+    type Error = Error;
+}
+
+impl<W, SPI, const WORD: u8, CS, D> SpiDevice<W> for SpiWithCs<SPI, WORD, CS, D>
+where
+    W: Copy + 'static,
+    SPI: SpiPeripheral,
+    CS: OutputPin,
+    D: DelayNs,
+    Spi<SPI, WORD, D>: SpiBus<W, Error = Error>,
+{
+    fn transaction(&mut self, operations: &mut [Operation<'_, W>]) -> Result<()> {
+        self.cs.set_low().map_err(|_| Error::ChipSelectFault)?;
+
+        let res = operations.iter_mut().try_for_each(|op| match op {
+            Operation::Read(buf) => SpiBus::read(&mut self.spi, buf),
+            Operation::Write(buf) => SpiBus::write(&mut self.spi, buf),
+            Operation::Transfer(read, write) => SpiBus::transfer(&mut self.spi, read, write),
+            Operation::TransferInPlace(buf) => SpiBus::transfer_in_place(&mut self.spi, buf),
+            Operation::DelayNs(ns) => {
+                SpiBus::flush(&mut self.spi)?;
+                self.spi.delay.delay_ns(*ns);
+                Ok(())
+            }
+        });
+
+        // Always deassert CS, even if a transfer failed
+        let flush_res = SpiBus::flush(&mut self.spi);
+        let cs_res = self.cs.set_high().map_err(|_| Error::ChipSelectFault);
+
+        res.and(flush_res).and(cs_res)
+    }
+}
@@ -0,0 +1,638 @@
+use embedded_hal::delay::DelayNs;
+use embedded_hal::spi::SpiBus;
+
+#[cfg(feature = "dma")]
+use crate::dma::{self, Channel, TransferWidth, MAX_BLOCK_TS};
+#[cfg(feature = "dma")]
+use crate::pac::{SPI0, SPI1, SPI2, SPI3, SPI4, SPI5, SPI6};
+
+#[cfg(feature = "dma")]
+use super::Error;
+use super::{Result, Spi, SpiPeripheral};
+
+/// `DMAC` channel used for SPI transmit DMA transfers.
+#[cfg(feature = "dma")]
+pub const SPI_TX_DMA_CHANNEL: Channel = Channel::Ch0;
+/// `DMAC` channel used for SPI receive DMA transfers.
+#[cfg(feature = "dma")]
+pub const SPI_RX_DMA_CHANNEL: Channel = Channel::Ch1;
+
+/// Transfers shorter than this (in bytes) use PIO, since DMA setup costs more than it saves.
+pub const SPI_DMA_MIN_LEN: usize = 16;
+
+// Source of the dummy words clocked out by receive-only DMA transfers.
+#[cfg(feature = "dma")]
+static DMA_DUMMY_WORD: u8 = 0;
+
+/// Provides the `DMAC` hardware handshaking interfaces for an SSP SPI peripheral.
+#[cfg(feature = "dma")]
+pub trait SpiDma {
+    /// Receive FIFO DMA request line.
+    const RX_HANDSHAKE: u8;
+    /// Transmit FIFO DMA request line.
+    const TX_HANDSHAKE: u8;
+}
+
+#[cfg(feature = "dma")]
+macro_rules! impl_spi_dma {
+    ($spi:ident, $rx:expr, $tx:expr) => {
+        impl SpiDma for $spi {
+            const RX_HANDSHAKE: u8 = $rx;
+            const TX_HANDSHAKE: u8 = $tx;
+        }
+    };
+}
+
+#[cfg(feature = "dma")]
+impl_spi_dma!(SPI0, 14, 15);
+#[cfg(feature = "dma")]
+impl_spi_dma!(SPI1, 16, 17);
+#[cfg(feature = "dma")]
+impl_spi_dma!(SPI2, 18, 19);
+#[cfg(feature = "dma")]
+impl_spi_dma!(SPI3, 20, 21);
+#[cfg(feature = "dma")]
+impl_spi_dma!(SPI4, 22, 23);
+#[cfg(feature = "dma")]
+impl_spi_dma!(SPI5, 24, 25);
+#[cfg(feature = "dma")]
+impl_spi_dma!(SPI6, 26, 27);
+
+/// Handle to an in-progress SPI transmit DMA transfer.
+///
+/// With the `dma` feature, buffers larger than the `DMAC` maximum block size are sent as
+/// consecutive DMA blocks, each started from [DmaTransfer::poll].
+///
+/// Dropping the handle before completion aborts the transfer.
+pub struct DmaTransfer<'a, SPI: SpiPeripheral, D: DelayNs> {
+    spi: &'a mut Spi<SPI, 8, D>,
+    buf: &'a [u8],
+    pos: usize,
+    done: bool,
+}
+
+impl<'a, SPI: SpiPeripheral, D: DelayNs> DmaTransfer<'a, SPI, D> {
+    /// Gets whether the transfer has completed.
+    pub const fn is_done(&self) -> bool {
+        self.done
+    }
+
+    /// Gets the number of bytes handed to the DMA controller so far.
+    pub const fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Gets the buffer being transferred.
+    pub const fn buffer(&self) -> &[u8] {
+        self.buf
+    }
+}
+
+#[cfg(feature = "dma")]
+impl<'a, SPI: SpiPeripheral + SpiDma, D: DelayNs> DmaTransfer<'a, SPI, D> {
+    /// Polls the transfer for completion.
+    ///
+    /// Returns `Ok(())` once all bytes have been shifted out, and the peripheral is idle.
+    pub fn poll(&mut self) -> nb::Result<(), Error> {
+        if self.done {
+            return Ok(());
+        }
+
+        dma::poll(SPI_TX_DMA_CHANNEL).map_err(|err| {
+            err.map(|_| {
+                self.finish();
+                Error::Dma
+            })
+        })?;
+
+        if self.pos < self.buf.len() {
+            self.start_block().map_err(nb::Error::Other)?;
+            return Err(nb::Error::WouldBlock);
+        }
+
+        if !self.spi.periph.tfe() || self.spi.periph.bsy() {
+            return Err(nb::Error::WouldBlock);
+        }
+
+        self.finish();
+
+        Ok(())
+    }
+
+    /// Waits for the transfer to complete.
+    pub fn wait(mut self) -> Result<()> {
+        nb::block!(self.poll())
+    }
+
+    // Starts a DMA block for the next chunk of the buffer.
+    fn start_block(&mut self) -> Result<()> {
+        let len = core::cmp::min(self.buf.len() - self.pos, MAX_BLOCK_TS);
+        let src = self.buf[self.pos..].as_ptr() as usize;
+        let dst = self.spi.periph.data_addr();
+
+        // SAFETY: the caller of `write_dma` guarantees the buffer stays valid until the transfer
+        // completes, or is aborted.
+        unsafe {
+            dma::start_mem_to_periph(
+                SPI_TX_DMA_CHANNEL,
+                src,
+                dst,
+                len,
+                TransferWidth::Bits8,
+                SPI::TX_HANDSHAKE,
+            )
+        }
+        .map_err(|_| {
+            self.finish();
+            Error::Other
+        })?;
+
+        self.pos += len;
+
+        Ok(())
+    }
+
+    // Stops DMA requests, and discards words received during the transmit-only transfer.
+    fn finish(&mut self) {
+        let periph = &mut self.spi.periph;
+
+        periph.set_txdmae(false);
+        super::discard_rx(periph);
+        periph.roric(true);
+        periph.rtic(true);
+
+        self.done = true;
+    }
+}
+
+#[cfg(not(feature = "dma"))]
+impl<'a, SPI: SpiPeripheral, D: DelayNs> DmaTransfer<'a, SPI, D> {
+    /// Polls the transfer for completion.
+    ///
+    /// Without the `dma` feature, the transfer completes before the handle is returned.
+    pub fn poll(&mut self) -> nb::Result<(), super::Error> {
+        self.spi.wait_idle().map_err(nb::Error::Other)
+    }
+
+    /// Waits for the transfer to complete.
+    pub fn wait(mut self) -> Result<()> {
+        nb::block!(self.poll())
+    }
+}
+
+#[cfg(feature = "dma")]
+impl<'a, SPI: SpiPeripheral, D: DelayNs> Drop for DmaTransfer<'a, SPI, D> {
+    fn drop(&mut self) {
+        if !self.done {
+            dma::abort(SPI_TX_DMA_CHANNEL);
+            self.spi.periph.set_txdmae(false);
+        }
+    }
+}
+
+#[cfg(feature = "dma")]
+impl<SPI: SpiPeripheral + SpiDma, D: DelayNs> Spi<SPI, 8, D> {
+    /// Writes `buf` to the SPI bus using the `DMAC` to feed the transmit FIFO.
+    ///
+    /// Returns a [DmaTransfer] handle to poll, or wait for completion. Received words are
+    /// discarded.
+    ///
+    /// Constraints:
+    ///
+    /// - buffers shorter than [SPI_DMA_MIN_LEN] are written with PIO before returning
+    /// - buffers longer than the `DMAC` maximum block size are sent as consecutive blocks
+    /// - `buf` has no alignment requirement, data items are single bytes
+    /// - the `DMAC` is not cache-coherent, `buf` must be written back to memory before the call
+    ///
+    /// Returns [Error::Other] if the `DMAC` transfer cannot be started, and [Error::Dma] if the
+    /// `DMAC` reports an error during the transfer.
+    ///
+    /// # Safety
+    ///
+    /// The `DMAC` keeps reading from `buf` until the transfer completes, and only dropping the
+    /// returned handle aborts it early. The handle must not be leaked (e.g. with
+    /// [core::mem::forget]): it must be dropped, or waited on, before `buf` is modified, or goes
+    /// out of scope.
+    ///
+    /// No other transfer may use [SPI_TX_DMA_CHANNEL] until then.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{pac, spi};
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let mut spi = spi::Spi::<pac::SPI0, 8>::new(dp.SPI0).unwrap();
+    /// let framebuffer = [0u8; 32 * 1024];
+    ///
+    /// // SAFETY: the transfer is waited on before `framebuffer` goes out of scope
+    /// let transfer = unsafe { spi.write_dma(framebuffer.as_ref()) }.unwrap();
+    /// // ... do other work ...
+    /// transfer.wait().unwrap();
+    /// ```
+    pub unsafe fn write_dma<'a>(&'a mut self, buf: &'a [u8]) -> Result<DmaTransfer<'a, SPI, D>> {
+        if buf.len() < SPI_DMA_MIN_LEN {
+            return self.write_pio(buf);
+        }
+
+        self.drain()?;
+
+        let mut transfer = DmaTransfer {
+            done: buf.is_empty(),
+            spi: self,
+            buf,
+            pos: 0,
+        };
+
+        if !transfer.done {
+            transfer.spi.periph.set_txdmae(true);
+            transfer.start_block()?;
+        }
+
+        Ok(transfer)
+    }
+}
+
+#[cfg(not(feature = "dma"))]
+impl<SPI: SpiPeripheral, D: DelayNs> Spi<SPI, 8, D> {
+    /// Writes `buf` to the SPI bus.
+    ///
+    /// Without the `dma` feature, this is a blocking write, and the returned [DmaTransfer] is
+    /// already complete.
+    ///
+    /// # Safety
+    ///
+    /// Always safe to call without the `dma` feature. Callers must still uphold the `dma`
+    /// feature contract, since enabling the feature does not change the signature: the handle
+    /// must not be leaked before `buf` is modified, or goes out of scope.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{pac, spi};
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let mut spi = spi::Spi::<pac::SPI0, 8>::new(dp.SPI0).unwrap();
+    /// let framebuffer = [0u8; 32 * 1024];
+    ///
+    /// // SAFETY: the transfer is waited on before `framebuffer` goes out of scope
+    /// let transfer = unsafe { spi.write_dma(framebuffer.as_ref()) }.unwrap();
+    /// transfer.wait().unwrap();
+    /// ```
+    pub unsafe fn write_dma<'a>(&'a mut self, buf: &'a [u8]) -> Result<DmaTransfer<'a, SPI, D>> {
+        self.write_pio(buf)
+    }
+}
+
+impl<SPI: SpiPeripheral, D: DelayNs> Spi<SPI, 8, D> {
+    // Writes `buf` with PIO, returning a completed [DmaTransfer].
+    fn write_pio<'a>(&'a mut self, buf: &'a [u8]) -> Result<DmaTransfer<'a, SPI, D>> {
+        SpiBus::write(self, buf)?;
+        self.drain()?;
+
+        Ok(DmaTransfer {
+            spi: self,
+            buf,
+            pos: buf.len(),
+            done: true,
+        })
+    }
+
+    // Reads into `read` with PIO, writing `write` if provided, returning a completed
+    // [DmaRxTransfer].
+    fn transfer_pio<'a>(
+        &'a mut self,
+        read: &'a mut [u8],
+        write: Option<&'a [u8]>,
+    ) -> Result<DmaRxTransfer<'a, SPI, D>> {
+        match write {
+            Some(buf) => SpiBus::transfer(self, read, buf)?,
+            None => SpiBus::read(self, read)?,
+        }
+        self.drain()?;
+
+        Ok(DmaRxTransfer {
+            pos: read.len(),
+            spi: self,
+            read,
+            write,
+            done: true,
+        })
+    }
+}
+
+/// Handle to an in-progress SPI receive (or duplex) DMA transfer.
+///
+/// With the `dma` feature, the receive FIFO is drained by the `DMAC` into the read buffer, while
+/// the transmit FIFO is fed from the write buffer (or with dummy zero words for receive-only
+/// transfers).
+///
+/// Dropping the handle before completion aborts the transfer.
+pub struct DmaRxTransfer<'a, SPI: SpiPeripheral, D: DelayNs> {
+    spi: &'a mut Spi<SPI, 8, D>,
+    read: &'a mut [u8],
+    write: Option<&'a [u8]>,
+    pos: usize,
+    done: bool,
+}
+
+impl<'a, SPI: SpiPeripheral, D: DelayNs> DmaRxTransfer<'a, SPI, D> {
+    /// Gets whether the transfer has completed.
+    pub const fn is_done(&self) -> bool {
+        self.done
+    }
+
+    /// Gets the number of bytes handed to the DMA controller so far.
+    pub const fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Gets the buffer receiving the data.
+    ///
+    /// The contents are only valid once the transfer has completed.
+    pub fn read_buffer(&self) -> &[u8] {
+        self.read
+    }
+
+    /// Gets the buffer being transmitted, `None` for receive-only transfers.
+    pub const fn write_buffer(&self) -> Option<&[u8]> {
+        self.write
+    }
+}
+
+#[cfg(feature = "dma")]
+impl<'a, SPI: SpiPeripheral + SpiDma, D: DelayNs> DmaRxTransfer<'a, SPI, D> {
+    /// Polls the transfer for completion.
+    ///
+    /// Returns `Ok(())` once all bytes have been received, and the peripheral is idle.
+    pub fn poll(&mut self) -> nb::Result<(), Error> {
+        if self.done {
+            return Ok(());
+        }
+
+        for ch in [SPI_RX_DMA_CHANNEL, SPI_TX_DMA_CHANNEL] {
+            dma::poll(ch).map_err(|err| {
+                err.map(|_| {
+                    self.abort();
+                    Error::Dma
+                })
+            })?;
+        }
+
+        if self.pos < self.read.len() {
+            self.start_block().map_err(nb::Error::Other)?;
+            return Err(nb::Error::WouldBlock);
+        }
+
+        if !self.spi.periph.tfe() || self.spi.periph.bsy() {
+            return Err(nb::Error::WouldBlock);
+        }
+
+        self.finish();
+
+        Ok(())
+    }
+
+    /// Waits for the transfer to complete.
+    pub fn wait(mut self) -> Result<()> {
+        nb::block!(self.poll())
+    }
+
+    // Starts receive, and transmit DMA blocks for the next chunk of the buffers.
+    fn start_block(&mut self) -> Result<()> {
+        let len = core::cmp::min(self.read.len() - self.pos, MAX_BLOCK_TS);
+        let data = self.spi.periph.data_addr();
+        let dst = self.read[self.pos..].as_mut_ptr() as usize;
+
+        // SAFETY: the caller of `read_dma`/`transfer_dma` guarantees the buffers stay valid
+        // until the transfer completes, or is aborted. The receive block is started first, so
+        // no received word is missed.
+        let res = unsafe {
+            dma::start_periph_to_mem(
+                SPI_RX_DMA_CHANNEL,
+                data,
+                dst,
+                len,
+                TransferWidth::Bits8,
+                SPI::RX_HANDSHAKE,
+            )
+            .and_then(|_| match self.write {
+                Some(buf) => dma::start_mem_to_periph(
+                    SPI_TX_DMA_CHANNEL,
+                    buf[self.pos..].as_ptr() as usize,
+                    data,
+                    len,
+                    TransferWidth::Bits8,
+                    SPI::TX_HANDSHAKE,
+                ),
+                None => dma::start_fixed_to_periph(
+                    SPI_TX_DMA_CHANNEL,
+                    &DMA_DUMMY_WORD as *const u8 as usize,
+                    data,
+                    len,
+                    TransferWidth::Bits8,
+                    SPI::TX_HANDSHAKE,
+                ),
+            })
+        };
+
+        res.map_err(|_| {
+            self.abort();
+            Error::Other
+        })?;
+
+        self.pos += len;
+
+        Ok(())
+    }
+
+    // Aborts both channels, and stops DMA requests.
+    fn abort(&mut self) {
+        dma::abort(SPI_RX_DMA_CHANNEL);
+        dma::abort(SPI_TX_DMA_CHANNEL);
+        self.finish();
+    }
+
+    // Stops DMA requests.
+    fn finish(&mut self) {
+        self.spi.periph.set_txdmae(false);
+        self.spi.periph.set_rxdmae(false);
+        self.done = true;
+    }
+}
+
+#[cfg(not(feature = "dma"))]
+impl<'a, SPI: SpiPeripheral, D: DelayNs> DmaRxTransfer<'a, SPI, D> {
+    /// Polls the transfer for completion.
+    ///
+    /// Without the `dma` feature, the transfer completes before the handle is returned.
+    pub fn poll(&mut self) -> nb::Result<(), super::Error> {
+        self.spi.wait_idle().map_err(nb::Error::Other)
+    }
+
+    /// Waits for the transfer to complete.
+    pub fn wait(mut self) -> Result<()> {
+        nb::block!(self.poll())
+    }
+}
+
+#[cfg(feature = "dma")]
+impl<'a, SPI: SpiPeripheral, D: DelayNs> Drop for DmaRxTransfer<'a, SPI, D> {
+    fn drop(&mut self) {
+        if !self.done {
+            dma::abort(SPI_RX_DMA_CHANNEL);
+            dma::abort(SPI_TX_DMA_CHANNEL);
+            self.spi.periph.set_txdmae(false);
+            self.spi.periph.set_rxdmae(false);
+        }
+    }
+}
+
+#[cfg(feature = "dma")]
+impl<SPI: SpiPeripheral + SpiDma, D: DelayNs> Spi<SPI, 8, D> {
+    /// Reads `buf.len()` bytes from the SPI bus using the `DMAC` to drain the receive FIFO.
+    ///
+    /// Dummy zero bytes are clocked out while reading. Returns a [DmaRxTransfer] handle to poll,
+    /// or wait for completion.
+    ///
+    /// Constraints:
+    ///
+    /// - buffers shorter than [SPI_DMA_MIN_LEN] are read with PIO before returning
+    /// - `buf` has no alignment requirement, data items are single bytes
+    /// - the `DMAC` is not cache-coherent, `buf` must be invalidated before reading the data
+    ///
+    /// Returns [Error::Other] if the `DMAC` transfer cannot be started, and [Error::Dma] if the
+    /// `DMAC` reports an error during the transfer.
+    ///
+    /// # Safety
+    ///
+    /// The `DMAC` keeps writing into `buf` until the transfer completes, and only dropping the
+    /// returned handle aborts it early. The handle must not be leaked (e.g. with
+    /// [core::mem::forget]): it must be dropped, or waited on, before `buf` is accessed again,
+    /// or goes out of scope.
+    ///
+    /// No other transfer may use [SPI_RX_DMA_CHANNEL], or [SPI_TX_DMA_CHANNEL] until then.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{pac, spi};
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let mut spi = spi::Spi::<pac::SPI0, 8>::new(dp.SPI0).unwrap();
+    /// let mut sector = [0u8; 512];
+    ///
+    /// // SAFETY: the transfer is waited on before `sector` is accessed again
+    /// unsafe { spi.read_dma(sector.as_mut()) }.unwrap().wait().unwrap();
+    /// ```
+    pub unsafe fn read_dma<'a>(
+        &'a mut self,
+        buf: &'a mut [u8],
+    ) -> Result<DmaRxTransfer<'a, SPI, D>> {
+        self.start_rx_dma(buf, None)
+    }
+
+    /// Performs a duplex transfer using the `DMAC` to feed the transmit FIFO from `write`, and
+    /// drain the receive FIFO into `read`.
+    ///
+    /// Same constraints as [Spi::read_dma], and [Spi::write_dma]. Returns
+    /// [Error::LengthMismatch] if the buffers have different lengths.
+    ///
+    /// # Safety
+    ///
+    /// Same contract as [Spi::read_dma], for both `read`, and `write`.
+    pub unsafe fn transfer_dma<'a>(
+        &'a mut self,
+        read: &'a mut [u8],
+        write: &'a [u8],
+    ) -> Result<DmaRxTransfer<'a, SPI, D>> {
+        if read.len() != write.len() {
+            return Err(Error::LengthMismatch {
+                read: read.len(),
+                write: write.len(),
+            });
+        }
+
+        self.start_rx_dma(read, Some(write))
+    }
+
+    fn start_rx_dma<'a>(
+        &'a mut self,
+        read: &'a mut [u8],
+        write: Option<&'a [u8]>,
+    ) -> Result<DmaRxTransfer<'a, SPI, D>> {
+        if read.len() < SPI_DMA_MIN_LEN {
+            return self.transfer_pio(read, write);
+        }
+
+        self.drain()?;
+
+        self.periph.set_rxdmae(true);
+        self.periph.set_txdmae(true);
+
+        let mut transfer = DmaRxTransfer {
+            spi: self,
+            read,
+            write,
+            pos: 0,
+            done: false,
+        };
+
+        transfer.start_block()?;
+
+        Ok(transfer)
+    }
+}
+
+#[cfg(not(feature = "dma"))]
+impl<SPI: SpiPeripheral, D: DelayNs> Spi<SPI, 8, D> {
+    /// Reads `buf.len()` bytes from the SPI bus.
+    ///
+    /// Without the `dma` feature, this is a blocking read, and the returned [DmaRxTransfer] is
+    /// already complete.
+    ///
+    /// # Safety
+    ///
+    /// Always safe to call without the `dma` feature. Callers must still uphold the `dma`
+    /// feature contract, since enabling the feature does not change the signature: the handle
+    /// must not be leaked before `buf` is accessed again, or goes out of scope.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{pac, spi};
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let mut spi = spi::Spi::<pac::SPI0, 8>::new(dp.SPI0).unwrap();
+    /// let mut sector = [0u8; 512];
+    ///
+    /// // SAFETY: the transfer is waited on before `sector` is accessed again
+    /// unsafe { spi.read_dma(sector.as_mut()) }.unwrap().wait().unwrap();
+    /// ```
+    pub unsafe fn read_dma<'a>(
+        &'a mut self,
+        buf: &'a mut [u8],
+    ) -> Result<DmaRxTransfer<'a, SPI, D>> {
+        self.transfer_pio(buf, None)
+    }
+
+    /// Performs a duplex transfer.
+    ///
+    /// Without the `dma` feature, this is a blocking transfer, and the returned [DmaRxTransfer]
+    /// is already complete. Returns [Error::LengthMismatch](super::Error::LengthMismatch) if
+    /// the buffers have different lengths.
+    ///
+    /// # Safety
+    ///
+    /// Same contract as [Spi::read_dma].
+    pub unsafe fn transfer_dma<'a>(
+        &'a mut self,
+        read: &'a mut [u8],
+        write: &'a [u8],
+    ) -> Result<DmaRxTransfer<'a, SPI, D>> {
+        if read.len() != write.len() {
+            return Err(super::Error::LengthMismatch {
+                read: read.len(),
+                write: write.len(),
+            });
+        }
+
+        self.transfer_pio(read, Some(write))
+    }
+}
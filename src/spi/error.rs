@@ -18,7 +18,32 @@ pub enum Error {
     ChipSelectFault,
     Timeout,
     Other,
+    /// Unsupported [DataSize] requested for transfers.
     DataSize(DataSize),
+    /// Word size (in bits) outside the range supported by the peripheral.
+    WordSize(u8),
+    /// Read and write buffers for a duplex transfer have different lengths.
+    LengthMismatch {
+        read: usize,
+        write: usize,
+    },
+    /// DMA controller reported an error during a transfer.
+    Dma,
+}
+
+impl Error {
+    /// Gets the rejected [DataSize], if the error is [Error::DataSize].
+    ///
+    /// Useful for retrieving the requested size when the error is handled through the
+    /// `embedded-hal` [ErrorKind], which reports it as [ErrorKind::Other].
+    ///
+    /// See `Spi::last_error` for the errors of `SpiBus` operations.
+    pub const fn data_size(&self) -> Option<DataSize> {
+        match self {
+            Self::DataSize(ds) => Some(*ds),
+            _ => None,
+        }
+    }
 }
 
 impl From<&Error> for ErrorKind {
@@ -31,6 +56,9 @@ impl From<&Error> for ErrorKind {
             Error::Timeout => Self::Other,
             Error::Other => Self::Other,
             Error::DataSize(_ds) => Self::Other,
+            Error::WordSize(_bits) => Self::Other,
+            Error::LengthMismatch { .. } => Self::Other,
+            Error::Dma => Self::Other,
         }
     }
 }
@@ -57,6 +85,11 @@ impl fmt::Display for Error {
             Self::Timeout => write!(f, "receive FIFO timeout"),
             Self::Other => write!(f, "other"),
             Self::DataSize(ds) => write!(f, "invalid data size: {ds}"),
+            Self::WordSize(bits) => write!(f, "invalid word size: {bits} bits"),
+            Self::LengthMismatch { read, write } => {
+                write!(f, "transfer length mismatch, read: {read}, write: {write}")
+            }
+            Self::Dma => write!(f, "DMA transfer error"),
         }
     }
 }
@@ -0,0 +1,164 @@
+//! SSP SPI interrupt servicing.
+
+use embedded_hal::delay::DelayNs;
+
+use crate::bitflag_is_set;
+
+use super::{InterruptMask, Spi, SpiPeripheral};
+
+bitflags! {
+    /// SSP SPI interrupt sources, in the bit order of the `SSPIMSC`, `SSPRIS`, and `SSPMIS`
+    /// registers.
+    #[repr(C)]
+    #[derive(Clone, Copy, Debug, Default, PartialEq)]
+    pub struct SpiInterrupt: u32 {
+        const NONE = 0b0000;
+        /// Data was received while the receive FIFO was full (`SSPRORINTR`).
+        const RX_OVERRUN = 0b0001;
+        /// The receive FIFO held unread data with no new frames for 32 bit periods
+        /// (`SSPRTINTR`).
+        const RX_TIMEOUT = 0b0010;
+        /// The receive FIFO is half full, or more (`SSPRXINTR`).
+        const RX = 0b0100;
+        /// The transmit FIFO is half empty, or more (`SSPTXINTR`).
+        const TX = 0b1000;
+        const MASK = 0b1111;
+    }
+}
+
+bitflag_is_set!(SpiInterrupt);
+
+impl SpiInterrupt {
+    // Decodes the unmasked (enabled) interrupt sources from the `SSPIMSC` register.
+    pub(crate) fn unmasked<SPI: SpiPeripheral>(periph: &SPI) -> Self {
+        Self::from_sources(
+            periph.rorim() == InterruptMask::NotMasked,
+            periph.rtim() == InterruptMask::NotMasked,
+            periph.rxim() == InterruptMask::NotMasked,
+            periph.txim() == InterruptMask::NotMasked,
+        )
+    }
+
+    // Decodes the raw interrupt status from the `SSPRIS` register.
+    pub(crate) fn raw<SPI: SpiPeripheral>(periph: &SPI) -> Self {
+        Self::from_sources(
+            periph.rorris(),
+            periph.rtris(),
+            periph.rxris(),
+            periph.txris(),
+        )
+    }
+
+    // Decodes the masked interrupt status from the `SSPMIS` register.
+    pub(crate) fn masked<SPI: SpiPeripheral>(periph: &SPI) -> Self {
+        Self::from_sources(
+            periph.rormis(),
+            periph.rtmis(),
+            periph.rxmis(),
+            periph.txmis(),
+        )
+    }
+
+    // Combines the per-source register bits into [SpiInterrupt] flags.
+    fn from_sources(rx_overrun: bool, rx_timeout: bool, rx: bool, tx: bool) -> Self {
+        let mut irqs = Self::NONE;
+
+        if rx_overrun {
+            irqs |= Self::RX_OVERRUN;
+        }
+        if rx_timeout {
+            irqs |= Self::RX_TIMEOUT;
+        }
+        if rx {
+            irqs |= Self::RX;
+        }
+        if tx {
+            irqs |= Self::TX;
+        }
+
+        irqs
+    }
+}
+
+impl<SPI: SpiPeripheral, const WORD: u8, D: DelayNs> Spi<SPI, WORD, D> {
+    /// Gets the unmasked (enabled) [SpiInterrupt] sources.
+    ///
+    /// [SpiInterrupt::RX_OVERRUN], and [SpiInterrupt::RX_TIMEOUT] are enabled by default, so
+    /// the `SpiBus` reads can detect them.
+    pub fn interrupts(&self) -> SpiInterrupt {
+        SpiInterrupt::unmasked(&self.periph)
+    }
+
+    /// Unmasks the `irqs` interrupt sources, so they assert the `SSPINTR` interrupt line.
+    ///
+    /// Other interrupt sources are left unchanged.
+    pub fn enable_interrupts(&mut self, irqs: SpiInterrupt) {
+        self.set_interrupts(irqs, InterruptMask::NotMasked);
+    }
+
+    /// Masks the `irqs` interrupt sources.
+    ///
+    /// Other interrupt sources are left unchanged.
+    pub fn disable_interrupts(&mut self, irqs: SpiInterrupt) {
+        self.set_interrupts(irqs, InterruptMask::Masked);
+    }
+
+    /// Services the SSP SPI interrupt.
+    ///
+    /// Call from the SPI interrupt handler. Returns the pending (unmasked) [SpiInterrupt]
+    /// sources, and clears the [SpiInterrupt::RX_OVERRUN], and [SpiInterrupt::RX_TIMEOUT]
+    /// interrupts.
+    ///
+    /// [SpiInterrupt::RX], and [SpiInterrupt::TX] are FIFO level interrupts, and cannot be
+    /// cleared. They stay pending until the receive FIFO is drained below half full, or the
+    /// transmit FIFO is filled above half empty, so either service the FIFO, or disable them
+    /// with [Spi::disable_interrupts] before returning from the handler.
+    ///
+    /// Only sources unmasked with [Spi::enable_interrupts] are reported.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{pac, spi};
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let mut spi = spi::Spi::<pac::SPI0, 8>::new(dp.SPI0).unwrap();
+    /// spi.enable_interrupts(spi::SpiInterrupt::RX);
+    ///
+    /// // in the SPI interrupt handler
+    /// let irqs = spi.on_interrupt();
+    /// if irqs.is_set(spi::SpiInterrupt::RX_OVERRUN) {
+    ///     // received data was lost
+    /// }
+    /// if irqs.is_set(spi::SpiInterrupt::RX) {
+    ///     // drain the receive FIFO
+    /// }
+    /// ```
+    pub fn on_interrupt(&mut self) -> SpiInterrupt {
+        let irqs = SpiInterrupt::masked(&self.periph);
+
+        if irqs.is_set(SpiInterrupt::RX_OVERRUN) {
+            self.periph.roric(true);
+        }
+        if irqs.is_set(SpiInterrupt::RX_TIMEOUT) {
+            self.periph.rtic(true);
+        }
+
+        irqs
+    }
+
+    // Sets the interrupt `mask` of the `irqs` interrupt sources.
+    fn set_interrupts(&mut self, irqs: SpiInterrupt, mask: InterruptMask) {
+        if irqs.is_set(SpiInterrupt::RX_OVERRUN) {
+            self.periph.set_rorim(mask);
+        }
+        if irqs.is_set(SpiInterrupt::RX_TIMEOUT) {
+            self.periph.set_rtim(mask);
+        }
+        if irqs.is_set(SpiInterrupt::RX) {
+            self.periph.set_rxim(mask);
+        }
+        if irqs.is_set(SpiInterrupt::TX) {
+            self.periph.set_txim(mask);
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use embedded_hal::delay::DelayNs;
+
+use super::{Error, Result, Spi, SpiPeripheral};
+
+// Control words are always 8-bit in the Microwire frame format.
+const CONTROL_MASK: u16 = 0xff;
+
+impl<SPI: SpiPeripheral, const WORD: u8, D: DelayNs> Spi<SPI, WORD, D> {
+    /// Performs a half-duplex [FrameFormat::Microwire](super::FrameFormat::Microwire) transfer.
+    ///
+    /// Each frame sends one 8-bit control word from `write`, then reads one `WORD`-bit data word
+    /// from the slave into `read`:
+    ///
+    /// - control words past the end of `write` are sent as zero
+    /// - data words past the end of `read` are discarded
+    ///
+    /// The `SpiBus` functions use this mapping when the peripheral is in Microwire mode, so
+    /// `write` discards the responses, and `read` sends zero control words.
+    ///
+    /// Returns [Error::FrameFormat] if the peripheral is not in Microwire mode.
+    pub fn microwire_transfer<W>(&mut self, read: &mut [W], write: &[W]) -> Result<()>
+    where
+        W: Copy + Into<u16> + TryFrom<u16>,
+    {
+        if !self.is_microwire() {
+            return Err(Error::FrameFormat);
+        }
+
+        for i in 0..core::cmp::max(read.len(), write.len()) {
+            let ctrl = write.get(i).map(|&w| w.into()).unwrap_or(0);
+            let data = self.microwire_frame(ctrl)?;
+
+            if let Some(word) = read.get_mut(i) {
+                *word = W::try_from(data).map_err(|_| Error::WordSize(WORD))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Performs a half-duplex [FrameFormat::Microwire](super::FrameFormat::Microwire) transfer
+    /// in-place.
+    ///
+    /// Each word is sent as an 8-bit control word, and replaced with the data word read back.
+    pub fn microwire_transfer_in_place<W>(&mut self, words: &mut [W]) -> Result<()>
+    where
+        W: Copy + Into<u16> + TryFrom<u16>,
+    {
+        if !self.is_microwire() {
+            return Err(Error::FrameFormat);
+        }
+
+        for word in words.iter_mut() {
+            let data = self.microwire_frame((*word).into())?;
+            *word = W::try_from(data).map_err(|_| Error::WordSize(WORD))?;
+        }
+
+        Ok(())
+    }
+
+    // Sends a control word, and waits for the data word of the same frame.
+    fn microwire_frame(&mut self, ctrl: u16) -> Result<u16> {
+        self.wait_for(|periph| periph.tnf())?;
+        self.periph.set_data(ctrl & CONTROL_MASK);
+
+        self.wait_for(|periph| periph.rne() || periph.rormis())?;
+
+        if self.periph.rormis() {
+            self.periph.roric(true);
+            Err(Error::Overrun)
+        } else {
+            Ok(self.periph.data() & Self::word_mask())
+        }
+    }
+}
@@ -0,0 +1,468 @@
+//! In-memory [SpiPeripheral] for testing [Spi](super::Spi) logic off-target.
+
+use core::cell::{Cell, RefCell};
+
+use heapless::{Deque, Vec};
+
+use super::{
+    ClockPhase, ClockPolarity, DataSize, FrameFormat, InterruptMask, ModeSelect, PrescaleDivisor,
+    SpiPeripheral, FIFO_DEPTH,
+};
+
+/// Maximum number of transmitted words recorded by [MockSpi].
+pub const MOCK_LOG_LEN: usize = 256;
+/// Maximum number of canned receive words queued in [MockSpi].
+pub const MOCK_RX_LEN: usize = 64;
+
+/// In-memory SSP SPI peripheral, implementing [SpiPeripheral].
+///
+/// Transmitted words are recorded, and the SSP FIFO behavior needed by the [Spi](super::Spi)
+/// driver is emulated:
+///
+/// - words written to `SSPDR` are masked to the [DataSize], and transmit instantly
+/// - each transmitted word receives the next word queued with [MockSpi::push_rx] (or `0`),
+///   or the transmitted word itself in loopback mode
+/// - received words overflowing the [FIFO_DEPTH]-word receive FIFO raise the receive overrun
+///   interrupt
+///
+/// Receive overrun, and timeout interrupts are simulated with [MockSpi::raise_overrun], and
+/// [MockSpi::raise_timeout]. A silent bus is simulated with [MockSpi::set_stalled].
+///
+/// Only available with the `mock` feature.
+///
+/// Example:
+///
+/// ```
+/// use embedded_hal::spi::SpiBus;
+/// use jh71xx_hal::spi::{self, MockSpi};
+///
+/// let mock = MockSpi::new();
+/// mock.push_rx(&[0x1a5, 0x5a]);
+///
+/// let mut spi = spi::Spi::<_, 8>::new(mock).unwrap();
+///
+/// // Mismatched lengths: the extra read word is clocked out with a zero
+/// let mut read = [0u8; 3];
+/// spi.transfer(read.as_mut(), [0x01, 0x02].as_ref()).unwrap();
+///
+/// // Received words are masked to the 8-bit data size
+/// assert_eq!(read, [0xa5, 0x5a, 0x00]);
+///
+/// let mock = spi.split();
+/// assert_eq!(mock.tx_words().as_slice(), [0x01, 0x02, 0x00]);
+///
+/// // Simulate a receive overrun
+/// mock.raise_overrun();
+/// let mut spi = spi::Spi::<_, 8>::new(mock).unwrap();
+/// assert_eq!(spi.read([0u8; 1].as_mut()), Err(spi::Error::Overrun));
+///
+/// // Words received by a write overrun the receive FIFO, but are discarded before the next
+/// // transfer
+/// let mock = spi.split();
+/// mock.push_rx(&[0xee; 12]);
+/// mock.push_rx(&[0x42]);
+///
+/// let mut spi = spi::Spi::<_, 8>::new(mock).unwrap();
+/// spi.write([0u8; 12].as_ref()).unwrap();
+///
+/// let mut read = [0u8; 1];
+/// spi.transfer(read.as_mut(), [0u8].as_ref()).unwrap();
+/// assert_eq!(read, [0x42]);
+///
+/// // Word size conversions keep the clock divisor
+/// let spi = spi.with_clock_hz(50_000_000, 5_000_000).unwrap();
+/// let clock_hz = spi.clock_hz(50_000_000);
+/// assert_eq!(clock_hz, 5_000_000);
+///
+/// let spi = spi::Spi::<_, 16>::try_from(spi).unwrap();
+/// assert_eq!(spi.clock_hz(50_000_000), clock_hz);
+///
+/// let spi = spi::Spi::<_, 8>::try_from(spi).unwrap();
+/// assert_eq!(spi.clock_hz(50_000_000), clock_hz);
+/// ```
+pub struct MockSpi {
+    dss: Cell<DataSize>,
+    frf: Cell<FrameFormat>,
+    spo: Cell<ClockPolarity>,
+    sph: Cell<ClockPhase>,
+    scr: Cell<u8>,
+    ms: Cell<ModeSelect>,
+    loopback: Cell<bool>,
+    sse: Cell<bool>,
+    cpsdvsr: Cell<u8>,
+    rorim: Cell<InterruptMask>,
+    rtim: Cell<InterruptMask>,
+    rxim: Cell<InterruptMask>,
+    txim: Cell<InterruptMask>,
+    rorris: Cell<bool>,
+    rtris: Cell<bool>,
+    txdmae: Cell<bool>,
+    rxdmae: Cell<bool>,
+    stalled: Cell<bool>,
+    rx_fifo: RefCell<Deque<u16, FIFO_DEPTH>>,
+    rx_data: RefCell<Deque<u16, MOCK_RX_LEN>>,
+    tx_words: RefCell<Vec<u16, MOCK_LOG_LEN>>,
+}
+
+impl MockSpi {
+    /// Creates a new [MockSpi] with all registers at their reset values.
+    pub const fn new() -> Self {
+        Self {
+            dss: Cell::new(DataSize::Reserved),
+            frf: Cell::new(FrameFormat::Spi),
+            spo: Cell::new(ClockPolarity::Low),
+            sph: Cell::new(ClockPhase::Low),
+            scr: Cell::new(0),
+            ms: Cell::new(ModeSelect::Master),
+            loopback: Cell::new(false),
+            sse: Cell::new(false),
+            cpsdvsr: Cell::new(0),
+            rorim: Cell::new(InterruptMask::Masked),
+            rtim: Cell::new(InterruptMask::Masked),
+            rxim: Cell::new(InterruptMask::Masked),
+            txim: Cell::new(InterruptMask::Masked),
+            rorris: Cell::new(false),
+            rtris: Cell::new(false),
+            txdmae: Cell::new(false),
+            rxdmae: Cell::new(false),
+            stalled: Cell::new(false),
+            rx_fifo: RefCell::new(Deque::new()),
+            rx_data: RefCell::new(Deque::new()),
+            tx_words: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Queues `data` to be received by subsequent transmitted words.
+    ///
+    /// Words past [MOCK_RX_LEN] queued words are dropped.
+    pub fn push_rx(&self, data: &[u16]) {
+        let mut rx_data = self.rx_data.borrow_mut();
+        for &w in data {
+            rx_data.push_back(w).ok();
+        }
+    }
+
+    /// Raises the receive overrun interrupt (`SSPRORINTR`).
+    pub fn raise_overrun(&self) {
+        self.rorris.set(true);
+    }
+
+    /// Raises the receive timeout interrupt (`SSPRTINTR`).
+    ///
+    /// Example:
+    ///
+    /// ```
+    /// use embedded_hal::spi::SpiBus;
+    /// use jh71xx_hal::spi::{self, InterruptMask, MockSpi, SpiPeripheral};
+    ///
+    /// // Receive timeout detection is enabled after construction
+    /// let mut spi = spi::Spi::<_, 8>::new(MockSpi::new()).unwrap();
+    /// assert!(spi.rx_timeout_enabled());
+    ///
+    /// let mock = spi.split();
+    /// assert_eq!(mock.rtim(), InterruptMask::NotMasked);
+    ///
+    /// mock.raise_timeout();
+    /// let mut spi = spi::Spi::<_, 8>::new(mock).unwrap();
+    /// assert_eq!(spi.read([0u8; 1].as_mut()), Err(spi::Error::Timeout));
+    /// ```
+    pub fn raise_timeout(&self) {
+        self.rtris.set(true);
+    }
+
+    /// Stalls, or resumes the bus.
+    ///
+    /// While stalled, e.g. when `SSPCLK` is not running, the peripheral stays busy with a
+    /// non-empty transmit FIFO, and transmitted words receive nothing.
+    ///
+    /// Example:
+    ///
+    /// ```
+    /// use core::cell::Cell;
+    ///
+    /// use embedded_hal::{delay::DelayNs, spi::SpiBus};
+    /// use jh71xx_hal::spi::{self, MockSpi};
+    ///
+    /// // Counts the elapsed delay time in microseconds
+    /// struct StubDelay<'a>(&'a Cell<u32>);
+    ///
+    /// impl DelayNs for StubDelay<'_> {
+    ///     fn delay_ns(&mut self, ns: u32) {
+    ///         self.0.set(self.0.get() + ns.div_ceil(1_000));
+    ///     }
+    /// }
+    ///
+    /// let elapsed = Cell::new(0);
+    ///
+    /// let mock = MockSpi::new();
+    /// mock.set_stalled(true);
+    ///
+    /// let mut spi = spi::Spi::<_, 8>::new(mock)
+    ///     .unwrap()
+    ///     .with_timeout_us(100)
+    ///     .with_delay(StubDelay(&elapsed));
+    ///
+    /// // Waits on a silent bus give up once the timeout elapses
+    /// assert_eq!(spi.flush(), Err(spi::Error::Timeout));
+    /// assert_eq!(elapsed.get(), 100);
+    ///
+    /// // The details are kept for drivers only seeing the `embedded-hal` error kind
+    /// assert_eq!(spi.last_error(), Some(spi::Error::Timeout));
+    /// spi.clear_last_error();
+    ///
+    /// elapsed.set(0);
+    /// assert_eq!(spi.read([0u8; 1].as_mut()), Err(spi::Error::Timeout));
+    /// assert_eq!(elapsed.get(), 100);
+    /// ```
+    pub fn set_stalled(&self, stalled: bool) {
+        self.stalled.set(stalled);
+    }
+
+    /// Gets the recorded transmitted words, masked to the [DataSize].
+    ///
+    /// Only the first [MOCK_LOG_LEN] words are recorded.
+    pub fn tx_words(&self) -> Vec<u16, MOCK_LOG_LEN> {
+        self.tx_words.borrow().clone()
+    }
+
+    /// Clears the recorded transmitted words.
+    pub fn clear_log(&self) {
+        self.tx_words.borrow_mut().clear();
+    }
+
+    // Gets the mask for valid bits of the configured data size.
+    fn word_mask(&self) -> u16 {
+        ((1u32 << self.dss.get().bits()) - 1) as u16
+    }
+
+    // Gets whether the interrupt with raw status `raw` is unmasked, and pending.
+    fn masked(raw: bool, mask: InterruptMask) -> bool {
+        raw && mask == InterruptMask::NotMasked
+    }
+}
+
+impl Default for MockSpi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SpiPeripheral for MockSpi {
+    fn base_addr(&self) -> usize {
+        0
+    }
+
+    fn dss(&self) -> DataSize {
+        self.dss.get()
+    }
+
+    fn set_dss(&mut self, val: DataSize) {
+        self.dss.set(val);
+    }
+
+    fn frf(&self) -> FrameFormat {
+        self.frf.get()
+    }
+
+    fn set_frf(&mut self, val: FrameFormat) {
+        self.frf.set(val);
+    }
+
+    fn spo(&self) -> ClockPolarity {
+        self.spo.get()
+    }
+
+    fn set_spo(&mut self, val: ClockPolarity) {
+        self.spo.set(val);
+    }
+
+    fn sph(&self) -> ClockPhase {
+        self.sph.get()
+    }
+
+    fn set_sph(&mut self, val: ClockPhase) {
+        self.sph.set(val);
+    }
+
+    fn scr(&self) -> u8 {
+        self.scr.get()
+    }
+
+    fn set_scr(&mut self, val: u8) {
+        self.scr.set(val);
+    }
+
+    fn ms(&self) -> ModeSelect {
+        self.ms.get()
+    }
+
+    fn set_ms(&mut self, val: ModeSelect) {
+        self.ms.set(val);
+    }
+
+    fn loopback(&self) -> bool {
+        self.loopback.get()
+    }
+
+    fn set_loopback(&mut self, val: bool) {
+        self.loopback.set(val);
+    }
+
+    fn sse(&self) -> bool {
+        self.sse.get()
+    }
+
+    fn set_sse(&mut self, val: bool) {
+        self.sse.set(val);
+    }
+
+    fn cpsdvsr(&self) -> PrescaleDivisor {
+        PrescaleDivisor::from(self.cpsdvsr.get())
+    }
+
+    fn set_cpsdvsr(&mut self, val: PrescaleDivisor) {
+        self.cpsdvsr.set(val.into());
+    }
+
+    fn data(&self) -> u16 {
+        self.rx_fifo.borrow_mut().pop_front().unwrap_or(0)
+    }
+
+    fn set_data<D: Into<u16>>(&mut self, val: D) {
+        let mask = self.word_mask();
+        let word = val.into() & mask;
+
+        self.tx_words.borrow_mut().push(word).ok();
+
+        if self.stalled.get() {
+            return;
+        }
+
+        let rx = if self.loopback.get() {
+            word
+        } else {
+            self.rx_data.borrow_mut().pop_front().unwrap_or(0) & mask
+        };
+
+        if self.rx_fifo.borrow_mut().push_back(rx).is_err() {
+            self.rorris.set(true);
+        }
+    }
+
+    fn roric(&mut self, val: bool) {
+        if val {
+            self.rorris.set(false);
+        }
+    }
+
+    fn rtic(&mut self, val: bool) {
+        if val {
+            self.rtris.set(false);
+        }
+    }
+
+    fn rorim(&self) -> InterruptMask {
+        self.rorim.get()
+    }
+
+    fn set_rorim(&mut self, val: InterruptMask) {
+        self.rorim.set(val);
+    }
+
+    fn rtim(&self) -> InterruptMask {
+        self.rtim.get()
+    }
+
+    fn set_rtim(&mut self, val: InterruptMask) {
+        self.rtim.set(val);
+    }
+
+    fn rxim(&self) -> InterruptMask {
+        self.rxim.get()
+    }
+
+    fn set_rxim(&mut self, val: InterruptMask) {
+        self.rxim.set(val);
+    }
+
+    fn txim(&self) -> InterruptMask {
+        self.txim.get()
+    }
+
+    fn set_txim(&mut self, val: InterruptMask) {
+        self.txim.set(val);
+    }
+
+    fn rormis(&self) -> bool {
+        Self::masked(self.rorris(), self.rorim.get())
+    }
+
+    fn rtmis(&self) -> bool {
+        Self::masked(self.rtris(), self.rtim.get())
+    }
+
+    fn rxmis(&self) -> bool {
+        Self::masked(self.rxris(), self.rxim.get())
+    }
+
+    fn txmis(&self) -> bool {
+        Self::masked(self.txris(), self.txim.get())
+    }
+
+    fn rorris(&self) -> bool {
+        self.rorris.get()
+    }
+
+    fn rtris(&self) -> bool {
+        self.rtris.get()
+    }
+
+    fn rxris(&self) -> bool {
+        self.rx_fifo.borrow().len() >= FIFO_DEPTH / 2
+    }
+
+    fn txris(&self) -> bool {
+        // The transmit FIFO is always empty, unless the bus is stalled
+        !self.stalled.get()
+    }
+
+    fn tfe(&self) -> bool {
+        !self.stalled.get()
+    }
+
+    fn tnf(&self) -> bool {
+        true
+    }
+
+    fn rne(&self) -> bool {
+        !self.rx_fifo.borrow().is_empty()
+    }
+
+    fn rff(&self) -> bool {
+        self.rx_fifo.borrow().is_full()
+    }
+
+    fn bsy(&self) -> bool {
+        self.stalled.get()
+    }
+
+    fn txdmae(&self) -> bool {
+        self.txdmae.get()
+    }
+
+    fn set_txdmae(&mut self, val: bool) {
+        self.txdmae.set(val);
+    }
+
+    fn rxdmae(&self) -> bool {
+        self.rxdmae.get()
+    }
+
+    fn set_rxdmae(&mut self, val: bool) {
+        self.rxdmae.set(val);
+    }
+
+    fn data_addr(&self) -> usize {
+        0
+    }
+}
@@ -1,5 +1,7 @@
 use core::fmt;
 
+use embedded_hal::spi::{Phase, Polarity};
+
 use pac::{SPI0, SPI1, SPI2, SPI3, SPI4, SPI5, SPI6};
 
 /// Represents the data word size (in bits) of the FIFO buffers.
@@ -83,6 +85,43 @@ impl From<u8> for DataSize {
     }
 }
 
+impl DataSize {
+    /// Converts a word size (in bits) into a [DataSize].
+    ///
+    /// Returns `None` for word sizes outside the supported range `[4:16]`.
+    ///
+    /// **NOTE**: the `From<u8>` implementation converts from the raw `DSS` register value,
+    /// which is one less than the word size.
+    pub const fn from_bits(bits: u8) -> Option<Self> {
+        match bits {
+            4 => Some(Self::Four),
+            5 => Some(Self::Five),
+            6 => Some(Self::Six),
+            7 => Some(Self::Seven),
+            8 => Some(Self::Eight),
+            9 => Some(Self::Nine),
+            10 => Some(Self::Ten),
+            11 => Some(Self::Eleven),
+            12 => Some(Self::Twelve),
+            13 => Some(Self::Thirteen),
+            14 => Some(Self::Fourteen),
+            15 => Some(Self::Fifteen),
+            16 => Some(Self::Sixteen),
+            _ => None,
+        }
+    }
+
+    /// Gets the word size (in bits) of the [DataSize].
+    ///
+    /// Returns `0` for [DataSize::Reserved].
+    pub const fn bits(&self) -> u8 {
+        match self {
+            Self::Reserved => 0,
+            _ => *self as u8 + 1,
+        }
+    }
+}
+
 impl fmt::Display for DataSize {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", <&str>::from(self))
@@ -107,6 +146,24 @@ impl From<bool> for ClockPolarity {
     }
 }
 
+impl From<Polarity> for ClockPolarity {
+    fn from(val: Polarity) -> Self {
+        match val {
+            Polarity::IdleLow => Self::Low,
+            Polarity::IdleHigh => Self::High,
+        }
+    }
+}
+
+impl From<ClockPolarity> for Polarity {
+    fn from(val: ClockPolarity) -> Self {
+        match val {
+            ClockPolarity::Low => Self::IdleLow,
+            ClockPolarity::High => Self::IdleHigh,
+        }
+    }
+}
+
 /// Represents the `SSPCLKOUT` clock phase settings.
 #[repr(u8)]
 #[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
@@ -125,6 +182,24 @@ impl From<bool> for ClockPhase {
     }
 }
 
+impl From<Phase> for ClockPhase {
+    fn from(val: Phase) -> Self {
+        match val {
+            Phase::CaptureOnFirstTransition => Self::Low,
+            Phase::CaptureOnSecondTransition => Self::High,
+        }
+    }
+}
+
+impl From<ClockPhase> for Phase {
+    fn from(val: ClockPhase) -> Self {
+        match val {
+            ClockPhase::Low => Self::CaptureOnFirstTransition,
+            ClockPhase::High => Self::CaptureOnSecondTransition,
+        }
+    }
+}
+
 /// Represents the data frame format.
 #[repr(u8)]
 #[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
@@ -238,6 +313,9 @@ impl From<bool> for InterruptMask {
 
 /// High-level, safe functions needed to access low-level SSP SPI registers.
 pub trait SpiPeripheral {
+    /// Gets the MMIO base address of the SSP SPI peripheral.
+    fn base_addr(&self) -> usize;
+
     /// Gets the [DataSize] selected for SPI transfers.
     fn dss(&self) -> DataSize;
     /// Sets the [DataSize] selected for SPI transfers.
@@ -286,6 +364,22 @@ pub trait SpiPeripheral {
     /// Sets the [ModeSelect] configuration for the SPI peripheral.
     fn set_ms(&mut self, val: ModeSelect);
 
+    /// Gets whether loopback mode is enabled.
+    ///
+    /// In loopback mode, the transmit serial shifter output is connected internally to the
+    /// receive serial shifter input.
+    fn loopback(&self) -> bool;
+    /// Sets whether loopback mode is enabled.
+    fn set_loopback(&mut self, val: bool);
+
+    /// Gets whether the synchronous serial port is enabled (`SSE`).
+    fn sse(&self) -> bool;
+    /// Sets whether the synchronous serial port is enabled (`SSE`).
+    ///
+    /// Disabling the port stops the serial clock, and the frame in progress. The FIFO contents
+    /// are kept, e.g. to pre-load the transmit FIFO before enabling the port.
+    fn set_sse(&mut self, val: bool);
+
     /// Sets the `SSPCLKOUT` [PrescaleDivisor].
     ///
     /// The frequency is calculated as:
@@ -377,11 +471,28 @@ pub trait SpiPeripheral {
     fn rff(&self) -> bool;
     /// Gets whether the SSP peripheral is busy.
     fn bsy(&self) -> bool;
+
+    /// Gets whether the transmit FIFO DMA request is enabled.
+    fn txdmae(&self) -> bool;
+    /// Sets whether the transmit FIFO DMA request is enabled.
+    fn set_txdmae(&mut self, val: bool);
+
+    /// Gets whether the receive FIFO DMA request is enabled.
+    fn rxdmae(&self) -> bool;
+    /// Sets whether the receive FIFO DMA request is enabled.
+    fn set_rxdmae(&mut self, val: bool);
+
+    /// Gets the bus address of the `SSPDR` data register, used as a DMA source/destination.
+    fn data_addr(&self) -> usize;
 }
 
 macro_rules! impl_spi_peripheral {
     ($spi:ident) => {
         impl $crate::spi::SpiPeripheral for $spi {
+            fn base_addr(&self) -> usize {
+                Self::ptr() as usize
+            }
+
             fn dss(&self) -> $crate::spi::DataSize {
                 self.ssp_cr0().read().dss().bits().into()
             }
@@ -433,6 +544,20 @@ macro_rules! impl_spi_peripheral {
                 });
             }
 
+            fn loopback(&self) -> bool {
+                self.ssp_cr1().read().lbm().bit_is_set()
+            }
+            fn set_loopback(&mut self, val: bool) {
+                self.ssp_cr1().modify(|_, w| w.lbm().bit(val));
+            }
+
+            fn sse(&self) -> bool {
+                self.ssp_cr1().read().sse().bit_is_set()
+            }
+            fn set_sse(&mut self, val: bool) {
+                self.ssp_cr1().modify(|_, w| w.sse().bit(val));
+            }
+
             fn cpsdvsr(&self) -> $crate::spi::PrescaleDivisor {
                 self.ssp_cpsr().read().cpsdvsr().bits().into()
             }
@@ -549,6 +674,24 @@ macro_rules! impl_spi_peripheral {
             fn bsy(&self) -> bool {
                 self.ssp_sr().read().bsy().bit_is_set()
             }
+
+            fn txdmae(&self) -> bool {
+                self.ssp_dmacr().read().txdmae().bit_is_set()
+            }
+            fn set_txdmae(&mut self, val: bool) {
+                self.ssp_dmacr().modify(|_, w| w.txdmae().bit(val));
+            }
+
+            fn rxdmae(&self) -> bool {
+                self.ssp_dmacr().read().rxdmae().bit_is_set()
+            }
+            fn set_rxdmae(&mut self, val: bool) {
+                self.ssp_dmacr().modify(|_, w| w.rxdmae().bit(val));
+            }
+
+            fn data_addr(&self) -> usize {
+                self.ssp_dr().as_ptr() as usize
+            }
         }
     };
 }
@@ -0,0 +1,74 @@
+use embedded_hal::delay::DelayNs;
+
+use super::{Error, ModeSelect, Result, Spi, SpiPeripheral};
+
+impl<SPI: SpiPeripheral, const WORD: u8, D: DelayNs> Spi<SPI, WORD, D> {
+    /// Gets whether the peripheral is configured for `slave` operation mode.
+    pub fn is_slave(&self) -> bool {
+        self.periph.ms() == ModeSelect::Slave
+    }
+
+    /// Reads words clocked in by the external master in `slave` mode.
+    ///
+    /// Waits on the receive FIFO without generating a clock. Each wait is bounded by the
+    /// transfer timeout (see [Spi::set_timeout_us]), so it should be long enough to cover the
+    /// master's idle time between frames.
+    ///
+    /// Returns:
+    ///
+    /// - `Ok(())` once `words` is filled
+    /// - `Err(Error::ModeFault)` if the peripheral is not in `slave` mode
+    /// - `Err(Error::Overrun)` if the receive FIFO overflowed
+    /// - `Err(Error::Timeout)` if the master stops clocking
+    pub fn slave_read<W: Copy + TryFrom<u16>>(&mut self, words: &mut [W]) -> Result<()> {
+        self.check_slave()?;
+
+        let mask = Self::word_mask();
+        for word in words.iter_mut() {
+            self.wait_for(|periph| periph.rne() || periph.rormis())?;
+
+            if self.periph.rormis() {
+                self.periph.roric(true);
+                return Err(Error::Overrun);
+            }
+
+            *word = W::try_from(self.periph.data() & mask).map_err(|_| Error::WordSize(WORD))?;
+        }
+
+        Ok(())
+    }
+
+    /// Queues words in the transmit FIFO to be clocked out by the external master in `slave`
+    /// mode.
+    ///
+    /// Waits for space in the transmit FIFO without generating a clock, and returns once the
+    /// last word is queued. Use [Spi::wait_idle] to wait for the master to clock out all words.
+    ///
+    /// **NOTE**: words received while transmitting are left in the receive FIFO, and should be
+    /// read with [Spi::slave_read] to avoid an overrun.
+    ///
+    /// Returns:
+    ///
+    /// - `Ok(())` once `words` is queued
+    /// - `Err(Error::ModeFault)` if the peripheral is not in `slave` mode
+    /// - `Err(Error::Timeout)` if the master stops clocking
+    pub fn slave_write<W: Copy + Into<u16>>(&mut self, words: &[W]) -> Result<()> {
+        self.check_slave()?;
+
+        let mask = Self::word_mask();
+        for word in words.iter() {
+            self.wait_for(|periph| periph.tnf())?;
+            self.periph.set_data((*word).into() & mask);
+        }
+
+        Ok(())
+    }
+
+    fn check_slave(&self) -> Result<()> {
+        if self.is_slave() {
+            Ok(())
+        } else {
+            Err(Error::ModeFault)
+        }
+    }
+}
@@ -0,0 +1,102 @@
+//! SSP SPI register snapshot, for debugging.
+
+use crate::bitflag_is_set;
+
+use super::{
+    ClockPhase, ClockPolarity, DataSize, FrameFormat, ModeSelect, SpiInterrupt, SpiPeripheral,
+};
+
+bitflags! {
+    /// SSP SPI status flags, in the bit order of the `SSPSR` register.
+    #[repr(C)]
+    #[derive(Clone, Copy, Debug, Default, PartialEq)]
+    pub struct SpiStatus: u32 {
+        const NONE = 0b0_0000;
+        /// Transmit FIFO is empty (`TFE`).
+        const TFE = 0b0_0001;
+        /// Transmit FIFO is not full (`TNF`).
+        const TNF = 0b0_0010;
+        /// Receive FIFO is not empty (`RNE`).
+        const RNE = 0b0_0100;
+        /// Receive FIFO is full (`RFF`).
+        const RFF = 0b0_1000;
+        /// The SSP is transmitting, or receiving a frame, or the transmit FIFO is not empty
+        /// (`BSY`).
+        const BSY = 0b1_0000;
+        const MASK = 0b1_1111;
+    }
+}
+
+bitflag_is_set!(SpiStatus);
+
+/// Snapshot of the SSP SPI peripheral registers, for diagnosing stuck transfers.
+///
+/// Captured with [Spi::dump_registers](super::Spi::dump_registers). Register fields are
+/// decoded through the [SpiPeripheral] accessors.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SpiRegisterSnapshot {
+    /// MMIO base address of the SSP SPI peripheral.
+    pub base_addr: usize,
+    /// `SSPCR0` data size select (`DSS`).
+    pub dss: DataSize,
+    /// `SSPCR0` frame format (`FRF`).
+    pub frf: FrameFormat,
+    /// `SSPCR0` clock polarity (`SPO`).
+    pub spo: ClockPolarity,
+    /// `SSPCR0` clock phase (`SPH`).
+    pub sph: ClockPhase,
+    /// `SSPCR0` serial clock rate (`SCR`).
+    pub scr: u8,
+    /// `SSPCR1` master/slave mode select (`MS`).
+    pub ms: ModeSelect,
+    /// `SSPCR1` loopback mode (`LBM`).
+    pub loopback: bool,
+    /// `SSPCR1` synchronous serial port enable (`SSE`).
+    pub sse: bool,
+    /// `SSPCPSR` clock prescale divisor (`CPSDVSR`).
+    pub cpsdvsr: u8,
+    /// `SSPIMSC` unmasked (enabled) interrupt sources.
+    pub interrupts: SpiInterrupt,
+    /// `SSPRIS` raw interrupt status.
+    pub raw_interrupts: SpiInterrupt,
+    /// `SSPMIS` masked interrupt status.
+    pub masked_interrupts: SpiInterrupt,
+    /// `SSPSR` status flags.
+    pub status: SpiStatus,
+    /// `SSPDMACR` transmit DMA enable (`TXDMAE`).
+    pub txdmae: bool,
+    /// `SSPDMACR` receive DMA enable (`RXDMAE`).
+    pub rxdmae: bool,
+}
+
+impl SpiRegisterSnapshot {
+    // Reads the registers of `periph`.
+    //
+    // Only registers without read side effects are read, the data register is never read.
+    pub(crate) fn read<SPI: SpiPeripheral>(periph: &SPI) -> Self {
+        let status = |set: bool, flag: SpiStatus| if set { flag } else { SpiStatus::NONE };
+
+        Self {
+            base_addr: periph.base_addr(),
+            dss: periph.dss(),
+            frf: periph.frf(),
+            spo: periph.spo(),
+            sph: periph.sph(),
+            scr: periph.scr(),
+            ms: periph.ms(),
+            loopback: periph.loopback(),
+            sse: periph.sse(),
+            cpsdvsr: periph.cpsdvsr().into(),
+            interrupts: SpiInterrupt::unmasked(periph),
+            raw_interrupts: SpiInterrupt::raw(periph),
+            masked_interrupts: SpiInterrupt::masked(periph),
+            status: status(periph.tfe(), SpiStatus::TFE)
+                | status(periph.tnf(), SpiStatus::TNF)
+                | status(periph.rne(), SpiStatus::RNE)
+                | status(periph.rff(), SpiStatus::RFF)
+                | status(periph.bsy(), SpiStatus::BSY),
+            txdmae: periph.txdmae(),
+            rxdmae: periph.rxdmae(),
+        }
+    }
+}
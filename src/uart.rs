@@ -1,13 +1,32 @@
 //! UART peripheral access
 
-use core::marker::PhantomData;
+use core::cell::{Cell, RefCell};
+
+use embedded_hal::delay::DelayNs;
+
+use crate::{
+    delay::{u74_mdelay, McycleDelay},
+    reset::{CrgReset, Reset},
+};
 
 mod config;
+mod dma;
 mod error;
+#[cfg(feature = "mock")]
+mod mock;
+mod modem;
+#[cfg(feature = "rt")]
+mod ring;
 mod serial;
 
 pub use config::*;
+pub use dma::*;
 pub use error::*;
+#[cfg(feature = "mock")]
+pub use mock::*;
+pub use modem::*;
+#[cfg(feature = "rt")]
+pub use ring::*;
 pub use serial::*;
 
 /// Clock used by Dw_apb_uart: 50 MHz
@@ -20,75 +39,239 @@ pub const TIMEOUT_US: u64 = 1_000_000;
 
 /// Represents UART TX functionality.
 ///
+/// Borrows the UART peripheral, and [DelayNs] source from the [Uart] it was split from.
+///
 /// Inspired by `esp-hal` implementation: <https://github.com/esp-rs/esp-hal>
-pub struct UartTx<T: Serial> {
-    _serial: PhantomData<T>,
+pub struct UartTx<'d, T: Serial, D: DelayNs = McycleDelay> {
+    uart: &'d T,
+    delay: &'d RefCell<D>,
+    timeout: u64,
 }
 
-impl<T: Serial> UartTx<T> {
-    fn new_inner() -> Self {
+impl<'d, T: Serial, D: DelayNs> UartTx<'d, T, D> {
+    fn new_inner(uart: &'d T, delay: &'d RefCell<D>, timeout: u64) -> Self {
         Self {
-            _serial: PhantomData,
+            uart,
+            delay,
+            timeout,
         }
     }
 
+    /// Gets the timeout (in microseconds) inherited from the [Uart] it was split from.
+    pub const fn timeout(&self) -> u64 {
+        self.timeout
+    }
+
     /// Writes bytes over serial.
     ///
-    /// Blocking function.
+    /// Blocking function, waits at most the [timeout](UartTx::timeout) for the transmit FIFO
+    /// to accept each byte.
     ///
     /// Returns:
     ///
     /// - `Ok(written: usize)` on success, `written` bytes written to peripheral
-    /// - `Err(Error)` on failure
+    /// - `Err(Error::WriteTimeout)` if the transmitter stalled
+    /// - `Err(Error)` on other failures
     pub fn write_bytes(&mut self, data: &[u8]) -> Result<usize> {
         let count = data.len();
 
-        data.iter()
-            .try_for_each(|&c| nb::block!(self.write_byte(c)))?;
+        data.iter().try_for_each(|&c| {
+            let uart = self.uart;
+            block_timeout(self.delay, self.timeout, Error::WriteTimeout, || {
+                uart.write_byte(c)
+            })
+        })?;
 
         Ok(count)
     }
 
+    /// Starts, or stops transmitting a break condition.
+    ///
+    /// While the break is active, the `TX` line is held low, and no data is transmitted.
+    /// Starting a break waits for pending data to be sent first, so no character is cut short.
+    /// Stopping the break returns the line to idle, and normal transmission resumes.
+    pub fn send_break(&mut self, enable: bool) {
+        if enable {
+            // A stalled transmitter is not an error here, start the break anyway
+            self.flush_timeout().ok();
+        }
+        self.uart.set_break(enable);
+    }
+
+    /// Transmits a break condition for `us` microseconds.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{delay, pac, uart};
+    /// use embedded_io::Write;
+    ///
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let mut uart = uart::Uart::new(dp.UART0);
+    /// let (mut tx, _rx) = uart.split();
+    /// let mut delay = delay::u74_mdelay();
+    ///
+    /// // LIN header: 13 bit times of break at 19200 baud, then the sync byte, and frame ID
+    /// tx.send_break_for(677, &mut delay);
+    /// tx.write_all(&[0x55, 0x3c]).unwrap();
+    /// ```
+    pub fn send_break_for<B: DelayNs>(&mut self, us: u32, delay: &mut B) {
+        self.send_break(true);
+        delay.delay_us(us);
+        self.send_break(false);
+    }
+
     fn write_byte(&mut self, byte: u8) -> nb::Result<(), Error> {
-        T::write_byte(byte)
+        self.uart.write_byte(byte)
     }
 
     fn flush(&mut self) -> nb::Result<(), Error> {
-        T::flush()
+        self.uart.flush()
+    }
+
+    // Waits at most the timeout for the transmitter to empty.
+    fn flush_timeout(&mut self) -> Result<()> {
+        let uart = self.uart;
+        block_timeout(self.delay, self.timeout, Error::WriteTimeout, || {
+            uart.flush()
+        })
     }
 }
 
 /// Represents UART RX functionality.
 ///
+/// Borrows the UART peripheral, and [DelayNs] source from the [Uart] it was split from.
+///
 /// Based on the implementation in `esp-hal`: <https://github.com/esp-rs/esp-hal>
-pub struct UartRx<T: Serial> {
-    _serial: PhantomData<T>,
+pub struct UartRx<'d, T: Serial, D: DelayNs = McycleDelay> {
+    uart: &'d T,
+    errors: &'d Cell<ErrorCounts>,
+    delay: &'d RefCell<D>,
+    timeout: u64,
 }
 
-impl<T: Serial> UartRx<T> {
-    fn new_inner() -> Self {
+impl<'d, T: Serial, D: DelayNs> UartRx<'d, T, D> {
+    fn new_inner(
+        uart: &'d T,
+        errors: &'d Cell<ErrorCounts>,
+        delay: &'d RefCell<D>,
+        timeout: u64,
+    ) -> Self {
         Self {
-            _serial: PhantomData,
+            uart,
+            errors,
+            delay,
+            timeout,
         }
     }
 
+    /// Gets the timeout (in microseconds) inherited from the [Uart] it was split from.
+    pub const fn timeout(&self) -> u64 {
+        self.timeout
+    }
+
     /// Reads bytes from the peripheral.
     ///
-    /// Continues to read bytes while receive FIFO is full, or at least one byte is read.
+    /// Waits at most the [timeout](UartRx::timeout) for at least one byte to arrive, then
+    /// continues to read bytes while the receive FIFO has data, or until `buf` is full.
+    ///
+    /// Returns `Err(Error::ReadTimeout)` if no byte arrived, see [UartRx::read_bytes_timeout].
     pub fn read_bytes(&mut self, buf: &mut [u8]) -> Result<usize> {
-        let mut count = 0usize;
+        self.read_bytes_timeout(buf, self.timeout)
+    }
+
+    /// Reads bytes from the peripheral, waiting at most `timeout_us` microseconds for data.
+    ///
+    /// Waits for at least one byte to arrive, then continues to read bytes while the receive
+    /// FIFO has data, or until `buf` is full.
+    ///
+    /// Use [UartRx::timeout] for the timeout configured on the [Uart].
+    ///
+    /// Returns:
+    ///
+    /// - `Ok(read: usize)` on success, `read` bytes received
+    /// - `Err(Error::ReadTimeout)` if no byte arrived within `timeout_us` microseconds
+    /// - `Err(Error)` on other failures
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{pac, uart};
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let mut uart = uart::Uart::new(dp.UART0).with_timeout(10_000);
+    /// let (_tx, mut rx) = uart.split();
+    ///
+    /// let mut buf = [0u8; 16];
+    /// match rx.read_bytes_timeout(buf.as_mut(), rx.timeout()) {
+    ///     Ok(_len) => (), // handle the received bytes
+    ///     Err(uart::Error::ReadTimeout) => (), // device is silent
+    ///     Err(_err) => (), // handle the line error
+    /// }
+    /// ```
+    pub fn read_bytes_timeout(&mut self, buf: &mut [u8], timeout_us: u64) -> Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        buf[0] = self.read_byte_with(timeout_us)?;
+        let mut count = 1usize;
 
-        for byte in buf.iter_mut() {
+        for byte in buf[1..].iter_mut() {
             match self.read_byte() {
                 Ok(b) => {
                     *byte = b;
                     count = count.saturating_add(1);
                 }
+                Err(nb::Error::WouldBlock) => break,
+                Err(nb::Error::Other(e)) => return Err(e),
+            }
+        }
+
+        Ok(count)
+    }
+
+    /// Reads bytes from the peripheral until the line goes idle.
+    ///
+    /// Returns once `buf` is full, or no byte has been received for `idle_us` microseconds.
+    /// Useful for detecting end-of-frame by inter-byte gap, e.g. Modbus RTU.
+    ///
+    /// Returns:
+    ///
+    /// - `Ok(read: usize)` on success, `read` bytes received before the line went idle
+    /// - `Err(Error)` on failure
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{delay, pac, uart};
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let mut uart = uart::Uart::new(dp.UART0);
+    /// let (_tx, mut rx) = uart.split();
+    /// let mut delay = delay::u74_mdelay();
+    ///
+    /// let mut frame = [0u8; 256];
+    /// // 3.5 character times at 9600 baud
+    /// let _len = rx.read_with_idle_timeout(&mut frame, 4_010, &mut delay).unwrap();
+    /// ```
+    pub fn read_with_idle_timeout<B: DelayNs>(
+        &mut self,
+        buf: &mut [u8],
+        idle_us: u32,
+        delay: &mut B,
+    ) -> Result<usize> {
+        let mut count = 0usize;
+        let mut idle = 0u32;
+
+        while count < buf.len() && idle < idle_us {
+            match self.read_byte() {
+                Ok(b) => {
+                    buf[count] = b;
+                    count = count.saturating_add(1);
+                    idle = 0;
+                }
                 Err(nb::Error::WouldBlock) => {
-                    // Block until we have at least one byte
-                    if count > 0 {
-                        break;
-                    }
+                    delay.delay_us(1);
+                    idle = idle.saturating_add(1);
                 }
                 Err(nb::Error::Other(e)) => return Err(e),
             }
@@ -98,19 +281,64 @@ impl<T: Serial> UartRx<T> {
     }
 
     fn read_byte(&mut self) -> nb::Result<u8, Error> {
-        T::read_byte()
+        self.uart.read_byte().inspect_err(|err| {
+            if let nb::Error::Other(e) = err {
+                let mut counts = self.errors.get();
+                counts.record(*e);
+                self.errors.set(counts);
+            }
+        })
+    }
+
+    // Waits at most the timeout for a byte to arrive.
+    fn read_byte_timeout(&mut self) -> Result<u8> {
+        self.read_byte_with(self.timeout)
+    }
+
+    // Waits at most `timeout_us` microseconds for a byte to arrive.
+    fn read_byte_with(&mut self, timeout_us: u64) -> Result<u8> {
+        let delay = self.delay;
+        block_timeout(delay, timeout_us, Error::ReadTimeout, || self.read_byte())
+    }
+}
+
+// Polls `f` until it completes, waiting at most `timeout_us` microseconds.
+//
+// Returns `timeout_err` if `f` is still blocked once the timeout elapses.
+fn block_timeout<D: DelayNs, R>(
+    delay: &RefCell<D>,
+    timeout_us: u64,
+    timeout_err: Error,
+    mut f: impl FnMut() -> nb::Result<R, Error>,
+) -> Result<R> {
+    let mut elapsed = 0u64;
+
+    loop {
+        match f() {
+            Ok(r) => return Ok(r),
+            Err(nb::Error::WouldBlock) if elapsed < timeout_us => {
+                delay.borrow_mut().delay_us(1);
+                elapsed = elapsed.saturating_add(1);
+            }
+            Err(nb::Error::WouldBlock) => return Err(timeout_err),
+            Err(nb::Error::Other(e)) => return Err(e),
+        }
     }
 }
 
 /// Represents a UART peripheral.
 ///
+/// Read, and write timeouts are measured with the `D` [DelayNs] source.
+///
 /// Based on the implementation in [`esp-hal`](https://github.com/esp-rs/esp-hal).
 #[repr(C)]
-pub struct Uart<UART: Serial> {
-    tx: UartTx<UART>,
-    rx: UartRx<UART>,
+pub struct Uart<UART: Serial, D: DelayNs = McycleDelay> {
+    uart: UART,
     timeout: u64,
     config: Config,
+    loopback: bool,
+    errors: Cell<ErrorCounts>,
+    delay: RefCell<D>,
 }
 
 impl<UART: Serial> Uart<UART> {
@@ -133,6 +361,9 @@ impl<UART: Serial> Uart<UART> {
 
     /// Creates a new [Uart] from a custom configuration.
     ///
+    /// **NOTE**: an invalid `config` is ignored, leaving the peripheral unconfigured. Use
+    /// [Uart::try_new_with_config] to handle configuration errors.
+    ///
     /// Parameters:
     ///
     /// - `uart`: UART peripheral that implements the [Serial] trait.
@@ -155,6 +386,7 @@ impl<UART: Serial> Uart<UART> {
     ///         baud_rate: uart::BaudRate::B115200,
     ///         // default APB0 clock frequency
     ///         clk_hz: 50_000_000,
+    ///         flow_control: uart::FlowControl::None,
     ///     },
     /// );
     /// ```
@@ -162,26 +394,249 @@ impl<UART: Serial> Uart<UART> {
         uart.setup(config).ok();
 
         Self {
-            tx: UartTx::new_inner(),
-            rx: UartRx::new_inner(),
+            uart,
             timeout,
             config,
+            loopback: false,
+            errors: Cell::new(ErrorCounts::new()),
+            delay: RefCell::new(u74_mdelay()),
         }
     }
 
-    /// Splits the [Uart] into a transmitter and receiver
-    pub fn split(self) -> (UartTx<UART>, UartRx<UART>) {
-        (self.tx, self.rx)
+    /// Creates a new [Uart] from a custom configuration, validating the configuration.
+    ///
+    /// Parameters:
+    ///
+    /// - `uart`: UART peripheral that implements the [Serial] trait.
+    /// - `timeout`: time in microseconds before aborting transaction.
+    /// - `config`: UART configuration parameters.
+    ///
+    /// Returns an [Error] if the `config` is invalid, see [Config::validate].
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{pac, uart};
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let config = uart::Config {
+    ///     baud_rate: uart::BaudRate::Custom(10_000_000),
+    ///     ..uart::Config::new()
+    /// };
+    ///
+    /// assert_eq!(
+    ///     uart::Uart::try_new_with_config(dp.UART0, 1_000_000, config).err(),
+    ///     Some(uart::Error::InvalidBaud)
+    /// );
+    /// ```
+    pub fn try_new_with_config(mut uart: UART, timeout: u64, config: Config) -> Result<Self> {
+        uart.setup(config)?;
+
+        Ok(Self {
+            uart,
+            timeout,
+            config,
+            loopback: false,
+            errors: Cell::new(ErrorCounts::new()),
+            delay: RefCell::new(u74_mdelay()),
+        })
+    }
+
+    /// Creates a new [Uart] from an already initialized peripheral.
+    ///
+    /// Unlike [Uart::new], the peripheral is not reconfigured, and the FIFOs are not reset. This
+    /// is useful for attaching to a console already set up by the bootloader, without corrupting
+    /// in-flight output.
+    ///
+    /// **NOTE**: the stored [Config] is the default, and may not reflect the peripheral
+    /// configuration. Use [Uart::with_config] to record the actual settings.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{pac, uart};
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let _uart = uart::Uart::attach(dp.UART0);
+    /// ```
+    pub fn attach(uart: UART) -> Self {
+        Self {
+            uart,
+            timeout: TIMEOUT_US,
+            config: Config::new(),
+            loopback: false,
+            errors: Cell::new(ErrorCounts::new()),
+            delay: RefCell::new(u74_mdelay()),
+        }
+    }
+}
+
+impl<UART: Serial, D: DelayNs> Uart<UART, D> {
+    /// Builder function that sets the [DelayNs] source used to measure read, and write
+    /// timeouts.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{delay, pac, uart};
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let _uart = uart::Uart::new(dp.UART0).with_delay(delay::u74_udelay());
+    /// ```
+    pub fn with_delay<D2: DelayNs>(self, delay: D2) -> Uart<UART, D2> {
+        Uart {
+            uart: self.uart,
+            timeout: self.timeout,
+            config: self.config,
+            loopback: self.loopback,
+            errors: self.errors,
+            delay: RefCell::new(delay),
+        }
+    }
+
+    /// Gets the MMIO base address of the wrapped UART peripheral.
+    pub fn base_addr(&self) -> usize {
+        UART::base_addr()
+    }
+
+    /// Splits the [Uart] into a transmitter and receiver.
+    ///
+    /// Both halves borrow the UART peripheral for as long as they are alive.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{pac, uart};
+    /// use embedded_io::{Read, Write};
+    ///
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let mut uart = uart::Uart::new(dp.UART0);
+    /// let (mut tx, mut rx) = uart.split();
+    ///
+    /// let mut buf = [0u8; 16];
+    /// let len = rx.read(buf.as_mut()).unwrap();
+    /// tx.write_all(&buf[..len]).unwrap();
+    /// ```
+    pub fn split(&mut self) -> (UartTx<'_, UART, D>, UartRx<'_, UART, D>) {
+        (self.tx(), self.rx())
+    }
+
+    /// Releases the UART peripheral.
+    pub fn free(self) -> UART {
+        self.uart
+    }
+
+    fn tx(&self) -> UartTx<'_, UART, D> {
+        UartTx::new_inner(&self.uart, &self.delay, self.timeout)
+    }
+
+    fn rx(&self) -> UartRx<'_, UART, D> {
+        UartRx::new_inner(&self.uart, &self.errors, &self.delay, self.timeout)
     }
 
     /// Read a byte from the UART FIFO.
+    ///
+    /// Waits at most the [timeout](Uart::timeout) for a byte to arrive, returns
+    /// `Err(Error::ReadTimeout)` otherwise.
     pub fn read_byte(&mut self) -> Result<u8> {
-        Ok(self.rx.read_byte()?)
+        self.rx().read_byte_timeout()
+    }
+
+    /// Reads bytes from the peripheral, waiting at most the configured [timeout](Uart::timeout)
+    /// for data.
+    ///
+    /// See [UartRx::read_bytes_timeout].
+    pub fn read_bytes_timeout(&mut self, buf: &mut [u8]) -> Result<usize> {
+        self.rx().read_bytes_timeout(buf, self.timeout)
     }
 
     /// Write a byte to the UART FIFO.
+    ///
+    /// Waits at most the [timeout](Uart::timeout) for space in the transmit FIFO, returns
+    /// `Err(Error::WriteTimeout)` otherwise.
     pub fn write_byte(&mut self, byte: u8) -> Result<()> {
-        Ok(self.tx.write_byte(byte)?)
+        self.tx().write_bytes(&[byte]).map(|_| ())
+    }
+
+    /// Starts, or stops transmitting a break condition.
+    ///
+    /// See [UartTx::send_break].
+    pub fn send_break(&mut self, enable: bool) {
+        self.tx().send_break(enable);
+    }
+
+    /// Transmits a break condition for `us` microseconds.
+    ///
+    /// See [UartTx::send_break_for].
+    pub fn send_break_for<B: DelayNs>(&mut self, us: u32, delay: &mut B) {
+        self.tx().send_break_for(us, delay);
+    }
+
+    /// Enables the receive data available interrupt.
+    ///
+    /// The interrupt is raised when the receive FIFO reaches its trigger level, or on a
+    /// character timeout with data left in the FIFO. Service it with an [RxRingBuffer] to
+    /// receive in the background.
+    pub fn enable_rx_interrupt(&mut self) {
+        self.uart.set_rx_interrupt(true);
+    }
+
+    /// Disables the receive data available interrupt.
+    pub fn disable_rx_interrupt(&mut self) {
+        self.uart.set_rx_interrupt(false);
+    }
+
+    /// Asserts, or deasserts the Data Terminal Ready (`DTR`) modem control output.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use embedded_hal::delay::DelayNs;
+    /// # use jh71xx_hal::{delay, pac, uart};
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let mut uart = uart::Uart::new(dp.UART0);
+    /// let mut delay = delay::u74_mdelay();
+    ///
+    /// // Pulse DTR to reset an attached device
+    /// uart.set_dtr(true);
+    /// delay.delay_ms(100);
+    /// uart.set_dtr(false);
+    /// ```
+    pub fn set_dtr(&mut self, assert: bool) {
+        self.uart.set_dtr(assert);
+    }
+
+    /// Asserts, or deasserts the Request To Send (`RTS`) modem control output.
+    ///
+    /// **NOTE**: with [FlowControl::RtsCts], `RTS` must stay asserted for the receiver to drive
+    /// it automatically. Deasserting `RTS` holds off the remote transmitter.
+    pub fn set_rts(&mut self, assert: bool) {
+        self.uart.set_rts(assert);
+    }
+
+    /// Reads the [ModemStatus] signals.
+    ///
+    /// **NOTE**: reading clears the change indicators.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{pac, uart};
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let mut uart = uart::Uart::new(dp.UART0);
+    ///
+    /// let status = uart.read_modem_status();
+    /// if status.delta_dcd && !status.dcd {
+    ///     // carrier lost, hang up
+    /// }
+    /// ```
+    pub fn read_modem_status(&mut self) -> ModemStatus {
+        self.uart.modem_status()
+    }
+
+    /// Resets the receive and transmit FIFOs.
+    ///
+    /// **NOTE**: destructive, any unsent and unread data is discarded. Use
+    /// [`flush`](io::Write::flush) to wait for pending data to be sent.
+    pub fn clear_fifos(&mut self) {
+        self.uart.clear_fifos();
     }
 
     /// Gets the timeout (in microseconds).
@@ -206,6 +661,103 @@ impl<UART: Serial> Uart<UART> {
         self
     }
 
+    /// Sets the hardware [FlowControl].
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{pac, uart};
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let mut uart = uart::Uart::new(dp.UART0);
+    /// uart.set_flow_control(uart::FlowControl::RtsCts);
+    /// ```
+    pub fn set_flow_control(&mut self, flow_control: FlowControl) {
+        self.uart.set_flow_control(flow_control);
+        self.config.flow_control = flow_control;
+    }
+
+    /// Builder function that sets the hardware [FlowControl].
+    pub fn with_flow_control(mut self, flow_control: FlowControl) -> Self {
+        self.set_flow_control(flow_control);
+        self
+    }
+
+    /// Gets whether loopback mode is enabled.
+    pub const fn loopback(&self) -> bool {
+        self.loopback
+    }
+
+    /// Enables, or disables loopback mode.
+    ///
+    /// In loopback mode, the transmitter output is routed internally to the receiver input, and
+    /// the `TX` line is held idle. Useful for testing the UART without external connections.
+    ///
+    /// Disabling loopback mode restores normal operation. The setting is kept across
+    /// [Uart::split], and [Reset::reset].
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{pac, uart};
+    /// use embedded_io::{Read, Write};
+    ///
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let mut uart = uart::Uart::new(dp.UART0);
+    /// uart.set_loopback(true);
+    ///
+    /// let (mut tx, mut rx) = uart.split();
+    /// tx.write_all(b"ping").unwrap();
+    /// tx.flush().unwrap();
+    ///
+    /// let mut buf = [0u8; 4];
+    /// rx.read_exact(buf.as_mut()).unwrap();
+    /// assert_eq!(&buf, b"ping");
+    ///
+    /// uart.set_loopback(false);
+    /// ```
+    pub fn set_loopback(&mut self, enable: bool) {
+        self.uart.set_loopback(enable);
+        self.loopback = enable;
+    }
+
+    /// Builder function that enables, or disables loopback mode.
+    pub fn with_loopback(mut self, enable: bool) -> Self {
+        self.set_loopback(enable);
+        self
+    }
+
+    /// Gets the receive line [ErrorCounts] accumulated since the [Uart] was created, or the
+    /// last [Uart::reset_error_counts].
+    ///
+    /// Errors are counted for reads through the [Uart], and the [UartRx] split from it. Reads
+    /// serviced by an [RxRingBuffer] are not counted.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{pac, uart};
+    /// use embedded_io::Read;
+    ///
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let mut uart = uart::Uart::new(dp.UART0);
+    ///
+    /// let mut buf = [0u8; 64];
+    /// let _ = uart.read(buf.as_mut());
+    ///
+    /// let counts = uart.error_counts();
+    /// if counts.framing > 0 {
+    ///     // check the cable, and baud rate
+    /// }
+    /// ```
+    pub fn error_counts(&self) -> ErrorCounts {
+        self.errors.get()
+    }
+
+    /// Resets the receive line [ErrorCounts] to zero.
+    pub fn reset_error_counts(&mut self) {
+        self.errors.set(ErrorCounts::new());
+    }
+
     /// Gets the [Config].
     pub const fn config(&self) -> Config {
         self.config
@@ -223,87 +775,93 @@ impl<UART: Serial> Uart<UART> {
     }
 }
 
-impl<UART: Serial> io::ErrorType for Uart<UART> {
+impl<UART: Serial + CrgReset, D: DelayNs> Reset for Uart<UART, D> {
+    fn reset(&mut self) {
+        UART::pulse_reset();
+        self.uart.setup(self.config).ok();
+        self.uart.set_loopback(self.loopback);
+    }
+}
+
+impl<UART: Serial, D: DelayNs> io::ErrorType for Uart<UART, D> {
     type Error = Error;
 }
 
-impl<UART: Serial> io::ErrorType for UartRx<UART> {
+impl<UART: Serial, D: DelayNs> io::ErrorType for UartRx<'_, UART, D> {
     type Error = Error;
 }
 
-impl<UART: Serial> io::ErrorType for UartTx<UART> {
+impl<UART: Serial, D: DelayNs> io::ErrorType for UartTx<'_, UART, D> {
     type Error = Error;
 }
 
-impl<UART: Serial> embedded_hal_nb::serial::ErrorType for Uart<UART> {
+impl<UART: Serial, D: DelayNs> embedded_hal_nb::serial::ErrorType for Uart<UART, D> {
     type Error = Error;
 }
 
-impl<UART: Serial> embedded_hal_nb::serial::ErrorType for UartRx<UART> {
+impl<UART: Serial, D: DelayNs> embedded_hal_nb::serial::ErrorType for UartRx<'_, UART, D> {
     type Error = Error;
 }
 
-impl<UART: Serial> embedded_hal_nb::serial::ErrorType for UartTx<UART> {
+impl<UART: Serial, D: DelayNs> embedded_hal_nb::serial::ErrorType for UartTx<'_, UART, D> {
     type Error = Error;
 }
 
-impl<UART: Serial> io::Read for Uart<UART> {
+impl<UART: Serial, D: DelayNs> io::Read for Uart<UART, D> {
     fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
-        self.rx.read_bytes(buf)
+        self.rx().read_bytes(buf)
     }
 }
 
-impl<UART: Serial> io::Read for UartRx<UART> {
+impl<UART: Serial, D: DelayNs> io::Read for UartRx<'_, UART, D> {
     fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
         self.read_bytes(buf)
     }
 }
 
-impl<UART: Serial> io::Write for Uart<UART> {
+impl<UART: Serial, D: DelayNs> io::Write for Uart<UART, D> {
     fn write(&mut self, buf: &[u8]) -> Result<usize> {
-        self.tx.write_bytes(buf)
+        self.tx().write_bytes(buf)
     }
 
     fn flush(&mut self) -> Result<()> {
-        self.tx.flush()?;
-        Ok(())
+        self.tx().flush_timeout()
     }
 }
 
-impl<UART: Serial> io::Write for UartTx<UART> {
+impl<UART: Serial, D: DelayNs> io::Write for UartTx<'_, UART, D> {
     fn write(&mut self, buf: &[u8]) -> Result<usize> {
         self.write_bytes(buf)
     }
 
     fn flush(&mut self) -> Result<()> {
-        self.flush()?;
-        Ok(())
+        self.flush_timeout()
     }
 }
 
-impl<UART: Serial> embedded_hal_nb::serial::Read for Uart<UART> {
+impl<UART: Serial, D: DelayNs> embedded_hal_nb::serial::Read for Uart<UART, D> {
     fn read(&mut self) -> nb::Result<u8, Self::Error> {
-        self.rx.read_byte()
+        self.rx().read_byte()
     }
 }
 
-impl<UART: Serial> embedded_hal_nb::serial::Read for UartRx<UART> {
+impl<UART: Serial, D: DelayNs> embedded_hal_nb::serial::Read for UartRx<'_, UART, D> {
     fn read(&mut self) -> nb::Result<u8, Self::Error> {
         self.read_byte()
     }
 }
 
-impl<UART: Serial> embedded_hal_nb::serial::Write for Uart<UART> {
+impl<UART: Serial, D: DelayNs> embedded_hal_nb::serial::Write for Uart<UART, D> {
     fn write(&mut self, val: u8) -> nb::Result<(), Self::Error> {
-        self.tx.write_byte(val)
+        self.tx().write_byte(val)
     }
 
     fn flush(&mut self) -> nb::Result<(), Self::Error> {
-        self.tx.flush()
+        self.tx().flush()
     }
 }
 
-impl<UART: Serial> embedded_hal_nb::serial::Write for UartTx<UART> {
+impl<UART: Serial, D: DelayNs> embedded_hal_nb::serial::Write for UartTx<'_, UART, D> {
     fn write(&mut self, val: u8) -> nb::Result<(), Self::Error> {
         self.write_byte(val)
     }
@@ -1,4 +1,4 @@
-use super::APB0;
+use super::{Error, Result, APB0};
 
 /// Fixed divisor constant multiplier.
 ///
@@ -46,14 +46,26 @@ impl Parity {
 }
 
 /// Configure the number of stop bits.
+///
+/// The DesignWare UART has a single `STOP` bit, the number of stop bits it selects depends on
+/// the [DataLength]:
+///
+/// - [DataLength::Five]: 1.5 stop bits
+/// - all other data lengths: 2 stop bits
 #[repr(u8)]
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
 pub enum Stop {
     /// Send one stop bit to signal the end of transmission.
     #[default]
     One = 0,
-    /// Send 1.5 or 2 stop bits to signal the end of transmission.
+    /// Send 2 stop bits to signal the end of transmission.
+    ///
+    /// Only valid with 6-, 7-, or 8-bit [DataLength].
     Two = 1,
+    /// Send 1.5 stop bits to signal the end of transmission.
+    ///
+    /// Only valid with [DataLength::Five].
+    OnePointFive = 2,
 }
 
 impl Stop {
@@ -61,23 +73,58 @@ impl Stop {
     pub const fn new() -> Self {
         Self::One
     }
+
+    /// Gets whether the [Stop] setting is valid for the [DataLength].
+    pub const fn is_valid(&self, data_len: DataLength) -> bool {
+        matches!(
+            (self, data_len),
+            (Self::One, _)
+                | (Self::OnePointFive, DataLength::Five)
+                | (
+                    Self::Two,
+                    DataLength::Six | DataLength::Seven | DataLength::Eight
+                )
+        )
+    }
+}
+
+/// Hardware flow control settings.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum FlowControl {
+    /// Disable hardware flow control.
+    #[default]
+    None = 0,
+    /// Enable automatic `RTS`/`CTS` flow control.
+    ///
+    /// The transmitter pauses while `CTS` is deasserted, and `RTS` is deasserted when the
+    /// receive FIFO reaches its trigger level.
+    RtsCts = 1,
+}
+
+impl FlowControl {
+    /// Creates a new [FlowControl].
+    pub const fn new() -> Self {
+        Self::None
+    }
 }
 
 /// Represents baud rate divisior arguments to setup the UART peripheral.
 ///
 /// The baud rate divisor is split into two 8-bit registers: DLL and DLM.
-#[repr(usize)]
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum BaudRate {
-    B1200 = 1_200,
-    B2400 = 2_400,
-    B4800 = 4_800,
-    B9600 = 9_600,
-    B19200 = 19_200,
-    B38400 = 38_400,
-    B57600 = 57_600,
+    B1200,
+    B2400,
+    B4800,
+    B9600,
+    B19200,
+    B38400,
+    B57600,
     #[default]
-    B115200 = 115_200,
+    B115200,
+    /// Arbitrary baud rate (in bits per second), e.g. `921_600`.
+    Custom(u32),
 }
 
 impl BaudRate {
@@ -96,11 +143,68 @@ impl BaudRate {
         ((self.baud_divisor(clk_hz) & 0xff00) >> 8) as u8
     }
 
-    /// Gets the baud divisor value.
+    /// Gets the baud rate in bits per second.
+    pub const fn bps(&self) -> usize {
+        match self {
+            Self::B1200 => 1_200,
+            Self::B2400 => 2_400,
+            Self::B4800 => 4_800,
+            Self::B9600 => 9_600,
+            Self::B19200 => 19_200,
+            Self::B38400 => 38_400,
+            Self::B57600 => 57_600,
+            Self::B115200 => 115_200,
+            Self::Custom(bps) => *bps as usize,
+        }
+    }
+
+    /// Gets whether the [BaudRate] is achievable from the `clk_hz` input clock.
+    ///
+    /// The rounded baud divisor must be non-zero, and fit in the 16-bit `DLL`/`DLH` divisor
+    /// latch.
+    ///
+    /// Example:
+    ///
+    /// ```
+    /// # use jh71xx_hal::uart;
+    /// assert!(uart::BaudRate::B115200.is_valid(uart::APB0));
+    /// assert!(!uart::BaudRate::B115200.is_valid(0));
+    /// assert!(!uart::BaudRate::Custom(0).is_valid(uart::APB0));
+    /// assert!(!uart::BaudRate::Custom(10_000_000).is_valid(uart::APB0));
+    /// ```
+    pub const fn is_valid(&self, clk_hz: usize) -> bool {
+        let div = FIXED_DIV.saturating_mul(self.bps());
+        if div == 0 {
+            return false;
+        }
+
+        let divisor = clk_hz.saturating_add(div / 2) / div;
+        divisor != 0 && divisor <= u16::MAX as usize
+    }
+
+    /// Gets the baud divisor value, rounded to the nearest achievable divisor.
+    ///
+    /// Returns `0` if the baud rate is zero, and saturates at the maximum 16-bit divisor.
+    ///
+    /// Example:
+    ///
+    /// ```
+    /// # use jh71xx_hal::uart;
+    /// assert_eq!(uart::BaudRate::B115200.baud_divisor(uart::APB0), 27);
+    /// assert_eq!(uart::BaudRate::Custom(921_600).baud_divisor(uart::APB0), 3);
+    /// ```
     pub const fn baud_divisor(&self, clk_hz: usize) -> u16 {
-        clk_hz
-            .saturating_div(FIXED_DIV)
-            .saturating_div(*self as usize) as u16
+        let div = FIXED_DIV.saturating_mul(self.bps());
+        if div == 0 {
+            return 0;
+        }
+
+        let divisor = clk_hz.saturating_add(div / 2) / div;
+        if divisor > u16::MAX as usize {
+            u16::MAX
+        } else {
+            divisor as u16
+        }
     }
 }
 
@@ -113,6 +217,7 @@ pub struct Config {
     pub parity: Parity,
     pub baud_rate: BaudRate,
     pub clk_hz: usize,
+    pub flow_control: FlowControl,
 }
 
 impl Config {
@@ -124,6 +229,81 @@ impl Config {
             parity: Parity::new(),
             baud_rate: BaudRate::new(),
             clk_hz: APB0,
+            flow_control: FlowControl::new(),
+        }
+    }
+
+    /// Validates the [Config] settings.
+    ///
+    /// Returns:
+    ///
+    /// - [Error::InvalidStop] if the [Stop] bits are invalid for the [DataLength]
+    /// - [Error::InvalidBaud] if the [BaudRate] is not achievable from `clk_hz`, see
+    ///   [BaudRate::is_valid]
+    ///
+    /// Example:
+    ///
+    /// ```
+    /// # use jh71xx_hal::uart;
+    /// assert!(uart::Config::new().validate().is_ok());
+    ///
+    /// let config = uart::Config {
+    ///     clk_hz: 0,
+    ///     ..uart::Config::new()
+    /// };
+    /// assert_eq!(config.validate(), Err(uart::Error::InvalidBaud));
+    /// ```
+    pub const fn validate(&self) -> Result<()> {
+        if !self.stop.is_valid(self.data_len) {
+            Err(Error::InvalidStop)
+        } else if !self.baud_rate.is_valid(self.clk_hz) {
+            Err(Error::InvalidBaud)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Gets the actual baud rate produced by the integer baud divisor.
+    ///
+    /// Calculated as: `clk_hz` / ([FIXED_DIV] * `baud_divisor`)
+    ///
+    /// Returns `0` if the baud divisor is zero, i.e. the clock is too slow for the baud rate.
+    pub const fn actual_baud_rate(&self) -> usize {
+        match self.baud_rate.baud_divisor(self.clk_hz) as usize {
+            0 => 0,
+            div => self.clk_hz / (FIXED_DIV * div),
+        }
+    }
+
+    /// Gets the error (in percent) of the actual baud rate from the configured [BaudRate].
+    ///
+    /// Positive values mean the actual rate is faster than configured, negative values slower.
+    ///
+    /// Returns `None` if the configured [BaudRate] is zero.
+    ///
+    /// Example:
+    ///
+    /// ```
+    /// # use jh71xx_hal::uart;
+    /// let config = uart::Config::new();
+    /// // most UARTs tolerate roughly 2% error
+    /// assert!(config.baud_error_percent().unwrap().abs() < 2.0);
+    ///
+    /// let config = uart::Config {
+    ///     baud_rate: uart::BaudRate::Custom(0),
+    ///     ..config
+    /// };
+    /// assert_eq!(config.baud_error_percent(), None);
+    /// ```
+    pub fn baud_error_percent(&self) -> Option<f32> {
+        match self.baud_rate.bps() {
+            0 => None,
+            bps => {
+                let target = bps as f32;
+                let actual = self.actual_baud_rate() as f32;
+
+                Some((actual - target) * 100.0 / target)
+            }
         }
     }
 }
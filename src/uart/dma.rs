@@ -0,0 +1,548 @@
+use crate::dma::Channel;
+#[cfg(feature = "dma")]
+use crate::dma::{self, TransferWidth, MAX_BLOCK_TS};
+#[cfg(feature = "dma")]
+use crate::pac::{UART0, UART1, UART2, UART3, UART4, UART5};
+
+#[cfg(feature = "dma")]
+use super::Error;
+use embedded_hal::delay::DelayNs;
+
+use crate::delay::McycleDelay;
+
+use super::{Result, Serial, Uart};
+
+/// Transfers shorter than this (in bytes) use PIO, since DMA setup costs more than it saves.
+pub const UART_DMA_MIN_LEN: usize = 16;
+
+/// Provides the `DMAC` hardware handshaking interfaces for a UART peripheral.
+#[cfg(feature = "dma")]
+pub trait UartDma: Serial {
+    /// Receive FIFO DMA request line.
+    const RX_HANDSHAKE: u8;
+    /// Transmit FIFO DMA request line.
+    const TX_HANDSHAKE: u8;
+
+    /// Gets the address of the `RBR`/`THR` data register.
+    fn data_addr() -> usize {
+        // `RBR`, and `THR` share the first register offset
+        Self::base_addr()
+    }
+}
+
+#[cfg(feature = "dma")]
+macro_rules! impl_uart_dma {
+    ($uart:ident, $rx:expr, $tx:expr) => {
+        impl UartDma for $uart {
+            const RX_HANDSHAKE: u8 = $rx;
+            const TX_HANDSHAKE: u8 = $tx;
+        }
+    };
+}
+
+#[cfg(feature = "dma")]
+impl_uart_dma!(UART0, 28, 29);
+#[cfg(feature = "dma")]
+impl_uart_dma!(UART1, 30, 31);
+#[cfg(feature = "dma")]
+impl_uart_dma!(UART2, 32, 33);
+#[cfg(feature = "dma")]
+impl_uart_dma!(UART3, 34, 35);
+#[cfg(feature = "dma")]
+impl_uart_dma!(UART4, 36, 37);
+#[cfg(feature = "dma")]
+impl_uart_dma!(UART5, 38, 39);
+
+/// Handle to an in-progress UART transmit DMA transfer.
+///
+/// With the `dma` feature, buffers larger than the `DMAC` maximum block size are sent as
+/// consecutive DMA blocks, each started from [DmaTransfer::poll].
+///
+/// Dropping the handle before completion aborts the transfer.
+pub struct DmaTransfer<'a, UART: Serial, D: DelayNs = McycleDelay> {
+    uart: &'a mut Uart<UART, D>,
+    ch: Channel,
+    buf: &'a [u8],
+    pos: usize,
+    done: bool,
+}
+
+impl<'a, UART: Serial, D: DelayNs> DmaTransfer<'a, UART, D> {
+    /// Gets whether the transfer has completed.
+    pub const fn is_done(&self) -> bool {
+        self.done
+    }
+
+    /// Gets the number of bytes handed to the DMA controller so far.
+    pub const fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Gets the `DMAC` [Channel] used for the transfer.
+    pub const fn channel(&self) -> Channel {
+        self.ch
+    }
+
+    /// Gets the buffer being transferred.
+    pub const fn buffer(&self) -> &[u8] {
+        self.buf
+    }
+}
+
+#[cfg(feature = "dma")]
+impl<'a, UART: UartDma, D: DelayNs> DmaTransfer<'a, UART, D> {
+    /// Polls the transfer for completion.
+    ///
+    /// Returns `Ok(())` once all bytes have been shifted out of the transmitter.
+    pub fn poll(&mut self) -> nb::Result<(), Error> {
+        if self.done {
+            return Ok(());
+        }
+
+        dma::poll(self.ch).map_err(|err| {
+            err.map(|_| {
+                self.finish();
+                Error::Dma
+            })
+        })?;
+
+        if self.pos < self.buf.len() {
+            self.start_block().map_err(nb::Error::Other)?;
+            return Err(nb::Error::WouldBlock);
+        }
+
+        self.uart.uart.flush()?;
+        self.finish();
+
+        Ok(())
+    }
+
+    /// Waits for the transfer to complete.
+    pub fn wait(mut self) -> Result<()> {
+        nb::block!(self.poll())
+    }
+
+    // Starts a DMA block for the next chunk of the buffer.
+    fn start_block(&mut self) -> Result<()> {
+        let len = core::cmp::min(self.buf.len() - self.pos, MAX_BLOCK_TS);
+        let src = self.buf[self.pos..].as_ptr() as usize;
+
+        // SAFETY: the caller of `write_dma`/`read_dma` guarantees the buffer stays valid, and
+        // the channel is not used by another transfer, until the transfer completes, or is
+        // aborted.
+        unsafe {
+            dma::start_mem_to_periph(
+                self.ch,
+                src,
+                UART::data_addr(),
+                len,
+                TransferWidth::Bits8,
+                UART::TX_HANDSHAKE,
+            )
+        }
+        .map_err(|_| {
+            self.finish();
+            Error::Dma
+        })?;
+
+        self.pos += len;
+
+        Ok(())
+    }
+
+    // Returns the peripheral to PIO mode.
+    fn finish(&mut self) {
+        self.uart.uart.set_dma_mode(false);
+        self.done = true;
+    }
+}
+
+#[cfg(not(feature = "dma"))]
+impl<'a, UART: Serial, D: DelayNs> DmaTransfer<'a, UART, D> {
+    /// Polls the transfer for completion.
+    ///
+    /// Without the `dma` feature, the transfer completes before the handle is returned.
+    pub fn poll(&mut self) -> nb::Result<(), super::Error> {
+        self.uart.uart.flush()
+    }
+
+    /// Waits for the transfer to complete.
+    pub fn wait(mut self) -> Result<()> {
+        nb::block!(self.poll())
+    }
+}
+
+#[cfg(feature = "dma")]
+impl<'a, UART: Serial, D: DelayNs> Drop for DmaTransfer<'a, UART, D> {
+    fn drop(&mut self) {
+        if !self.done {
+            dma::abort(self.ch);
+            self.uart.uart.set_dma_mode(false);
+        }
+    }
+}
+
+/// Handle to an in-progress UART receive DMA transfer.
+///
+/// With the `dma` feature, the receive FIFO is drained by the `DMAC` into the read buffer.
+///
+/// Dropping the handle before completion aborts the transfer.
+pub struct DmaRxTransfer<'a, UART: Serial, D: DelayNs = McycleDelay> {
+    // Only borrowed to keep the peripheral exclusive without the `dma` feature
+    #[cfg_attr(not(feature = "dma"), allow(dead_code))]
+    uart: &'a mut Uart<UART, D>,
+    ch: Channel,
+    buf: &'a mut [u8],
+    pos: usize,
+    done: bool,
+}
+
+impl<'a, UART: Serial, D: DelayNs> DmaRxTransfer<'a, UART, D> {
+    /// Gets whether the transfer has completed.
+    pub const fn is_done(&self) -> bool {
+        self.done
+    }
+
+    /// Gets the number of bytes handed to the DMA controller so far.
+    pub const fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Gets the `DMAC` [Channel] used for the transfer.
+    pub const fn channel(&self) -> Channel {
+        self.ch
+    }
+
+    /// Gets the buffer receiving the data.
+    ///
+    /// The contents are only valid once the transfer has completed.
+    pub fn buffer(&self) -> &[u8] {
+        self.buf
+    }
+}
+
+#[cfg(feature = "dma")]
+impl<'a, UART: UartDma, D: DelayNs> DmaRxTransfer<'a, UART, D> {
+    /// Polls the transfer for completion.
+    ///
+    /// Returns `Ok(())` once all bytes have been received.
+    ///
+    /// **NOTE**: the `DMAC` reads the data register directly, so line status errors (break,
+    /// framing, parity) are not reported for DMA transfers.
+    pub fn poll(&mut self) -> nb::Result<(), Error> {
+        if self.done {
+            return Ok(());
+        }
+
+        dma::poll(self.ch).map_err(|err| {
+            err.map(|_| {
+                self.finish();
+                Error::Dma
+            })
+        })?;
+
+        if self.pos < self.buf.len() {
+            self.start_block().map_err(nb::Error::Other)?;
+            return Err(nb::Error::WouldBlock);
+        }
+
+        self.finish();
+
+        Ok(())
+    }
+
+    /// Waits for the transfer to complete.
+    pub fn wait(mut self) -> Result<()> {
+        nb::block!(self.poll())
+    }
+
+    // Starts a DMA block for the next chunk of the buffer.
+    fn start_block(&mut self) -> Result<()> {
+        let len = core::cmp::min(self.buf.len() - self.pos, MAX_BLOCK_TS);
+        let dst = self.buf[self.pos..].as_mut_ptr() as usize;
+
+        // SAFETY: the caller of `write_dma`/`read_dma` guarantees the buffer stays valid, and
+        // the channel is not used by another transfer, until the transfer completes, or is
+        // aborted.
+        unsafe {
+            dma::start_periph_to_mem(
+                self.ch,
+                UART::data_addr(),
+                dst,
+                len,
+                TransferWidth::Bits8,
+                UART::RX_HANDSHAKE,
+            )
+        }
+        .map_err(|_| {
+            self.finish();
+            Error::Dma
+        })?;
+
+        self.pos += len;
+
+        Ok(())
+    }
+
+    // Returns the peripheral to PIO mode.
+    fn finish(&mut self) {
+        self.uart.uart.set_dma_mode(false);
+        self.done = true;
+    }
+}
+
+#[cfg(not(feature = "dma"))]
+impl<'a, UART: Serial, D: DelayNs> DmaRxTransfer<'a, UART, D> {
+    /// Polls the transfer for completion.
+    ///
+    /// Without the `dma` feature, the transfer completes before the handle is returned.
+    pub fn poll(&mut self) -> nb::Result<(), super::Error> {
+        Ok(())
+    }
+
+    /// Waits for the transfer to complete.
+    pub fn wait(mut self) -> Result<()> {
+        nb::block!(self.poll())
+    }
+}
+
+#[cfg(feature = "dma")]
+impl<'a, UART: Serial, D: DelayNs> Drop for DmaRxTransfer<'a, UART, D> {
+    fn drop(&mut self) {
+        if !self.done {
+            dma::abort(self.ch);
+            self.uart.uart.set_dma_mode(false);
+        }
+    }
+}
+
+#[cfg(feature = "dma")]
+impl<UART: UartDma, D: DelayNs> Uart<UART, D> {
+    /// Writes `buf` over serial using the `DMAC` channel `ch` to feed the transmit FIFO.
+    ///
+    /// Returns a [DmaTransfer] handle to poll, or wait for completion.
+    ///
+    /// Constraints:
+    ///
+    /// - buffers shorter than [UART_DMA_MIN_LEN] are written with PIO before returning
+    /// - buffers longer than the `DMAC` maximum block size are sent as consecutive blocks
+    /// - `buf` has no alignment requirement, data items are single bytes
+    /// - the `DMAC` is not cache-coherent, `buf` must be written back to memory before the call
+    ///
+    /// Returns [Error::Dma] if the `DMAC` transfer cannot be started.
+    ///
+    /// # Safety
+    ///
+    /// The `DMAC` keeps reading from `buf` until the transfer completes, and only dropping the
+    /// returned handle aborts it early. The handle must not be leaked (e.g. with
+    /// [core::mem::forget]): it must be dropped, or waited on, before `buf` is modified, or goes
+    /// out of scope.
+    ///
+    /// No other transfer may use `ch` until then. Channels
+    /// [SPI_TX_DMA_CHANNEL](crate::spi::SPI_TX_DMA_CHANNEL), and
+    /// [SPI_RX_DMA_CHANNEL](crate::spi::SPI_RX_DMA_CHANNEL) are used by SPI DMA transfers.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{dma, pac, uart};
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let mut uart = uart::Uart::new(dp.UART0);
+    /// let log = [b'.'; 4096];
+    ///
+    /// // SAFETY: the transfer is waited on before `log` goes out of scope, and no other
+    /// // transfer uses channel 2
+    /// let transfer = unsafe { uart.write_dma(dma::Channel::Ch2, log.as_ref()) }.unwrap();
+    /// // ... do other work ...
+    /// transfer.wait().unwrap();
+    /// ```
+    pub unsafe fn write_dma<'a>(
+        &'a mut self,
+        ch: Channel,
+        buf: &'a [u8],
+    ) -> Result<DmaTransfer<'a, UART, D>> {
+        if buf.len() < UART_DMA_MIN_LEN {
+            return self.write_pio(ch, buf);
+        }
+
+        self.uart.set_dma_mode(true);
+
+        let mut transfer = DmaTransfer {
+            uart: self,
+            ch,
+            buf,
+            pos: 0,
+            done: false,
+        };
+
+        transfer.start_block()?;
+
+        Ok(transfer)
+    }
+
+    /// Reads `buf.len()` bytes over serial using the `DMAC` channel `ch` to drain the receive
+    /// FIFO.
+    ///
+    /// Returns a [DmaRxTransfer] handle to poll, or wait for completion.
+    ///
+    /// Constraints:
+    ///
+    /// - buffers shorter than [UART_DMA_MIN_LEN] are read with PIO before returning
+    /// - the transfer only completes once `buf.len()` bytes have been received
+    /// - `buf` has no alignment requirement, data items are single bytes
+    /// - the `DMAC` is not cache-coherent, `buf` must be invalidated before reading the data
+    ///
+    /// Returns [Error::Dma] if the `DMAC` transfer cannot be started.
+    ///
+    /// # Safety
+    ///
+    /// The `DMAC` keeps writing into `buf` until the transfer completes, and only dropping the
+    /// returned handle aborts it early. The handle must not be leaked (e.g. with
+    /// [core::mem::forget]): it must be dropped, or waited on, before `buf` is accessed again,
+    /// or goes out of scope.
+    ///
+    /// No other transfer may use `ch` until then.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{dma, pac, uart};
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let mut uart = uart::Uart::new(dp.UART0);
+    /// let mut packet = [0u8; 256];
+    ///
+    /// // SAFETY: the transfer is waited on before `packet` is accessed again, and no other
+    /// // transfer uses channel 3
+    /// unsafe { uart.read_dma(dma::Channel::Ch3, packet.as_mut()) }
+    ///     .unwrap()
+    ///     .wait()
+    ///     .unwrap();
+    /// ```
+    pub unsafe fn read_dma<'a>(
+        &'a mut self,
+        ch: Channel,
+        buf: &'a mut [u8],
+    ) -> Result<DmaRxTransfer<'a, UART, D>> {
+        if buf.len() < UART_DMA_MIN_LEN {
+            return self.read_pio(ch, buf);
+        }
+
+        self.uart.set_dma_mode(true);
+
+        let mut transfer = DmaRxTransfer {
+            uart: self,
+            ch,
+            buf,
+            pos: 0,
+            done: false,
+        };
+
+        transfer.start_block()?;
+
+        Ok(transfer)
+    }
+}
+
+#[cfg(not(feature = "dma"))]
+impl<UART: Serial, D: DelayNs> Uart<UART, D> {
+    /// Writes `buf` over serial.
+    ///
+    /// Without the `dma` feature, this is a blocking write, `ch` is unused, and the returned
+    /// [DmaTransfer] is already complete.
+    ///
+    /// # Safety
+    ///
+    /// Always safe to call without the `dma` feature. Callers must still uphold the `dma`
+    /// feature contract, since enabling the feature does not change the signature: the handle
+    /// must not be leaked before `buf` is modified, or goes out of scope, and no other transfer
+    /// may use `ch` until then.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{dma, pac, uart};
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let mut uart = uart::Uart::new(dp.UART0);
+    /// let log = [b'.'; 4096];
+    ///
+    /// // SAFETY: the transfer is waited on before `log` goes out of scope
+    /// unsafe { uart.write_dma(dma::Channel::Ch2, log.as_ref()) }
+    ///     .unwrap()
+    ///     .wait()
+    ///     .unwrap();
+    /// ```
+    pub unsafe fn write_dma<'a>(
+        &'a mut self,
+        ch: Channel,
+        buf: &'a [u8],
+    ) -> Result<DmaTransfer<'a, UART, D>> {
+        self.write_pio(ch, buf)
+    }
+
+    /// Reads `buf.len()` bytes over serial.
+    ///
+    /// Without the `dma` feature, this is a blocking read, `ch` is unused, and the returned
+    /// [DmaRxTransfer] is already complete.
+    ///
+    /// # Safety
+    ///
+    /// Same contract as [Uart::write_dma].
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use jh71xx_hal::{dma, pac, uart};
+    /// let dp = pac::Peripherals::take().unwrap();
+    /// let mut uart = uart::Uart::new(dp.UART0);
+    /// let mut packet = [0u8; 256];
+    ///
+    /// // SAFETY: the transfer is waited on before `packet` is accessed again
+    /// unsafe { uart.read_dma(dma::Channel::Ch3, packet.as_mut()) }
+    ///     .unwrap()
+    ///     .wait()
+    ///     .unwrap();
+    /// ```
+    pub unsafe fn read_dma<'a>(
+        &'a mut self,
+        ch: Channel,
+        buf: &'a mut [u8],
+    ) -> Result<DmaRxTransfer<'a, UART, D>> {
+        self.read_pio(ch, buf)
+    }
+}
+
+impl<UART: Serial, D: DelayNs> Uart<UART, D> {
+    // Writes `buf` with PIO, returning a completed [DmaTransfer].
+    fn write_pio<'a>(&'a mut self, ch: Channel, buf: &'a [u8]) -> Result<DmaTransfer<'a, UART, D>> {
+        let mut tx = self.tx();
+        tx.write_bytes(buf)?;
+        tx.flush_timeout()?;
+
+        Ok(DmaTransfer {
+            uart: self,
+            ch,
+            buf,
+            pos: buf.len(),
+            done: true,
+        })
+    }
+
+    // Reads `buf.len()` bytes with PIO, returning a completed [DmaRxTransfer].
+    fn read_pio<'a>(
+        &'a mut self,
+        ch: Channel,
+        buf: &'a mut [u8],
+    ) -> Result<DmaRxTransfer<'a, UART, D>> {
+        let mut rx = self.rx();
+        buf.iter_mut()
+            .try_for_each(|b| rx.read_byte_timeout().map(|c| *b = c))?;
+
+        Ok(DmaRxTransfer {
+            pos: buf.len(),
+            uart: self,
+            ch,
+            buf,
+            done: true,
+        })
+    }
+}
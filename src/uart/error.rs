@@ -10,6 +10,66 @@ pub enum Error {
     ReadOverrun,
     WriteOverrun,
     WouldBlock,
+    /// The [Stop](super::Stop) bits setting is invalid for the [DataLength](super::DataLength).
+    InvalidStop,
+    /// The [BaudRate](super::BaudRate) is not achievable from the configured input clock.
+    InvalidBaud,
+    /// The received character did not have a valid stop bit.
+    FramingError,
+    /// The received character had an incorrect parity bit.
+    ParityError,
+    /// The receive line was held low for longer than a full character time.
+    BreakDetected,
+    /// DMA controller reported an error during a transfer.
+    Dma,
+}
+
+/// Cumulative receive line error counts.
+///
+/// Counts saturate at [u32::MAX].
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ErrorCounts {
+    /// Characters received without a valid stop bit.
+    pub framing: u32,
+    /// Characters received with an incorrect parity bit.
+    pub parity: u32,
+    /// Characters lost to a receive FIFO overrun.
+    pub overrun: u32,
+    /// Break conditions detected on the receive line.
+    pub break_detected: u32,
+}
+
+impl ErrorCounts {
+    /// Creates a new [ErrorCounts] with all counts at zero.
+    pub const fn new() -> Self {
+        Self {
+            framing: 0,
+            parity: 0,
+            overrun: 0,
+            break_detected: 0,
+        }
+    }
+
+    /// Gets the total number of receive line errors.
+    pub const fn total(&self) -> u32 {
+        self.framing
+            .saturating_add(self.parity)
+            .saturating_add(self.overrun)
+            .saturating_add(self.break_detected)
+    }
+
+    // Increments the count matching `err`, other errors are ignored.
+    pub(crate) fn record(&mut self, err: Error) {
+        let count = match err {
+            Error::FramingError => &mut self.framing,
+            Error::ParityError => &mut self.parity,
+            Error::ReadOverrun => &mut self.overrun,
+            Error::BreakDetected => &mut self.break_detected,
+            _ => return,
+        };
+        *count = count.saturating_add(1);
+    }
 }
 
 impl From<&Error> for io::ErrorKind {
@@ -19,6 +79,9 @@ impl From<&Error> for io::ErrorKind {
             Error::ReadOverrun => Self::InvalidInput,
             Error::WriteOverrun => Self::InvalidData,
             Error::WouldBlock => Self::Other,
+            Error::InvalidStop | Error::InvalidBaud => Self::InvalidInput,
+            Error::FramingError | Error::ParityError | Error::BreakDetected => Self::InvalidData,
+            Error::Dma => Self::Other,
         }
     }
 }
@@ -30,6 +93,11 @@ impl From<&Error> for embedded_hal_nb::serial::ErrorKind {
             Error::ReadOverrun => Self::Overrun,
             Error::WriteOverrun => Self::Overrun,
             Error::WouldBlock => Self::Other,
+            Error::InvalidStop | Error::InvalidBaud => Self::Other,
+            Error::FramingError => Self::FrameFormat,
+            Error::ParityError => Self::Parity,
+            Error::BreakDetected => Self::Other,
+            Error::Dma => Self::Other,
         }
     }
 }
@@ -0,0 +1,309 @@
+//! In-memory [Serial] peripheral for testing [Uart](super::Uart) logic off-target.
+
+use core::cell::{Cell, RefCell};
+
+use embedded_hal::delay::DelayNs;
+use heapless::{Deque, Vec};
+
+use super::{Config, Error, FlowControl, ModemStatus, Result, Serial};
+
+/// Maximum number of transmitted bytes recorded by [MockSerial].
+pub const MOCK_LOG_LEN: usize = 256;
+/// Depth of the [MockSerial] receive FIFO.
+pub const MOCK_RX_LEN: usize = 64;
+
+/// In-memory UART peripheral, implementing [Serial].
+///
+/// Transmitted bytes are recorded, and the UART behavior needed by the [Uart](super::Uart)
+/// driver is emulated:
+///
+/// - bytes are transmitted instantly, unless the transmitter is stalled with
+///   [MockSerial::set_thre]
+/// - bytes queued with [MockSerial::push_rx] are received in order, or the transmitted bytes in
+///   loopback mode
+/// - line errors are simulated with [MockSerial::raise_error]
+/// - modem status changes are simulated with [MockSerial::set_modem_status]
+///
+/// **NOTE**: [Serial::setup] does not reset the FIFOs, so data queued before creating the
+/// [Uart](super::Uart) is kept.
+///
+/// Only available with the `mock` feature.
+///
+/// Example:
+///
+/// ```
+/// use embedded_io::{Read, Write};
+/// use jh71xx_hal::uart::{self, MockDelay, MockSerial};
+///
+/// let mock = MockSerial::new();
+/// mock.push_rx(b"hi");
+///
+/// let mut uart = uart::Uart::new(mock).with_delay(MockDelay).with_timeout(100);
+///
+/// let mut buf = [0u8; 4];
+/// assert_eq!(uart.read(buf.as_mut()), Ok(2));
+/// assert_eq!(&buf[..2], b"hi");
+///
+/// // Nothing left to receive
+/// assert_eq!(uart.read(buf.as_mut()), Err(uart::Error::ReadTimeout));
+///
+/// uart.write_all(b"ok").unwrap();
+///
+/// // Stall the transmitter
+/// let mock = uart.free();
+/// assert_eq!(mock.tx_bytes().as_slice(), b"ok");
+/// mock.set_thre(false);
+///
+/// let mut uart = uart::Uart::new(mock).with_delay(MockDelay).with_timeout(100);
+/// assert_eq!(uart.write_byte(b'!'), Err(uart::Error::WriteTimeout));
+/// assert_eq!(uart.flush(), Err(uart::Error::WriteTimeout));
+/// ```
+pub struct MockSerial {
+    config: Cell<Option<Config>>,
+    flow_control: Cell<FlowControl>,
+    rx_interrupt: Cell<bool>,
+    loopback: Cell<bool>,
+    brk: Cell<bool>,
+    dma_mode: Cell<bool>,
+    dtr: Cell<bool>,
+    rts: Cell<bool>,
+    msr: Cell<ModemStatus>,
+    thre: Cell<bool>,
+    rx_err: Cell<Option<Error>>,
+    rx_fifo: RefCell<Deque<u8, MOCK_RX_LEN>>,
+    tx_bytes: RefCell<Vec<u8, MOCK_LOG_LEN>>,
+}
+
+impl MockSerial {
+    /// Creates a new [MockSerial] with an idle transmitter, and empty FIFOs.
+    pub const fn new() -> Self {
+        Self {
+            config: Cell::new(None),
+            flow_control: Cell::new(FlowControl::None),
+            rx_interrupt: Cell::new(false),
+            loopback: Cell::new(false),
+            brk: Cell::new(false),
+            dma_mode: Cell::new(false),
+            dtr: Cell::new(false),
+            rts: Cell::new(false),
+            msr: Cell::new(ModemStatus::new()),
+            thre: Cell::new(true),
+            rx_err: Cell::new(None),
+            rx_fifo: RefCell::new(Deque::new()),
+            tx_bytes: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Queues `data` in the receive FIFO.
+    ///
+    /// Bytes past [MOCK_RX_LEN] queued bytes raise a receive overrun on the next read.
+    pub fn push_rx(&self, data: &[u8]) {
+        data.iter().for_each(|&b| self.receive(b));
+    }
+
+    /// Raises a line error, reported by the next read.
+    pub fn raise_error(&self, err: Error) {
+        self.rx_err.set(Some(err));
+    }
+
+    /// Sets whether the transmit holding register is empty (`THRE`).
+    ///
+    /// Clearing `THRE` stalls the transmitter: writes, and flushes block until it is set.
+    pub fn set_thre(&self, thre: bool) {
+        self.thre.set(thre);
+    }
+
+    /// Sets the modem status signals, reported by the next [Serial::modem_status].
+    ///
+    /// The change indicators are derived from the previous signal states, and accumulate until
+    /// read.
+    pub fn set_modem_status(&self, cts: bool, dsr: bool, ri: bool, dcd: bool) {
+        let prev = self.msr.get();
+
+        self.msr.set(ModemStatus {
+            cts,
+            dsr,
+            ri,
+            dcd,
+            delta_cts: prev.delta_cts || prev.cts != cts,
+            delta_dsr: prev.delta_dsr || prev.dsr != dsr,
+            trailing_ri: prev.trailing_ri || (prev.ri && !ri),
+            delta_dcd: prev.delta_dcd || prev.dcd != dcd,
+        });
+    }
+
+    /// Gets the recorded transmitted bytes.
+    ///
+    /// Only the first [MOCK_LOG_LEN] bytes are recorded.
+    pub fn tx_bytes(&self) -> Vec<u8, MOCK_LOG_LEN> {
+        self.tx_bytes.borrow().clone()
+    }
+
+    /// Clears the recorded transmitted bytes.
+    pub fn clear_log(&self) {
+        self.tx_bytes.borrow_mut().clear();
+    }
+
+    /// Gets the [Config] from the last successful [Serial::setup].
+    pub fn config(&self) -> Option<Config> {
+        self.config.get()
+    }
+
+    /// Gets the [FlowControl] setting.
+    pub fn flow_control(&self) -> FlowControl {
+        self.flow_control.get()
+    }
+
+    /// Gets whether the receive data available interrupt is enabled.
+    pub fn rx_interrupt(&self) -> bool {
+        self.rx_interrupt.get()
+    }
+
+    /// Gets whether loopback mode is enabled.
+    pub fn loopback(&self) -> bool {
+        self.loopback.get()
+    }
+
+    /// Gets whether a break condition is being transmitted.
+    pub fn is_break(&self) -> bool {
+        self.brk.get()
+    }
+
+    /// Gets whether the `DTR` modem control output is asserted.
+    pub fn dtr(&self) -> bool {
+        self.dtr.get()
+    }
+
+    /// Gets whether the `RTS` modem control output is asserted.
+    pub fn rts(&self) -> bool {
+        self.rts.get()
+    }
+
+    /// Gets whether multi-transfer DMA mode is enabled.
+    pub fn dma_mode(&self) -> bool {
+        self.dma_mode.get()
+    }
+
+    // Pushes a received byte, raising an overrun if the receive FIFO is full.
+    fn receive(&self, byte: u8) {
+        if self.rx_fifo.borrow_mut().push_back(byte).is_err() {
+            self.rx_err.set(Some(Error::ReadOverrun));
+        }
+    }
+}
+
+impl Default for MockSerial {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Serial for MockSerial {
+    fn base_addr() -> usize {
+        0
+    }
+
+    fn setup(&mut self, config: Config) -> Result<()> {
+        config.validate()?;
+
+        self.config.set(Some(config));
+        self.set_flow_control(config.flow_control);
+
+        Ok(())
+    }
+
+    fn read_byte(&self) -> nb::Result<u8, Error> {
+        if let Some(err) = self.rx_err.take() {
+            Err(nb::Error::Other(err))
+        } else {
+            self.rx_fifo
+                .borrow_mut()
+                .pop_front()
+                .ok_or(nb::Error::WouldBlock)
+        }
+    }
+
+    fn write_byte(&self, byte: u8) -> nb::Result<(), Error> {
+        if !self.thre.get() {
+            return Err(nb::Error::WouldBlock);
+        }
+
+        self.tx_bytes.borrow_mut().push(byte).ok();
+
+        if self.loopback.get() {
+            self.receive(byte);
+        }
+
+        Ok(())
+    }
+
+    fn flush(&self) -> nb::Result<(), Error> {
+        if self.thre.get() {
+            Ok(())
+        } else {
+            Err(nb::Error::WouldBlock)
+        }
+    }
+
+    fn clear_fifos(&mut self) {
+        self.rx_fifo.borrow_mut().clear();
+    }
+
+    fn set_flow_control(&mut self, flow_control: FlowControl) {
+        if flow_control == FlowControl::RtsCts {
+            self.rts.set(true);
+        }
+        self.flow_control.set(flow_control);
+    }
+
+    fn set_rx_interrupt(&mut self, enable: bool) {
+        self.rx_interrupt.set(enable);
+    }
+
+    fn set_loopback(&mut self, enable: bool) {
+        self.loopback.set(enable);
+    }
+
+    fn set_dtr(&mut self, assert: bool) {
+        self.dtr.set(assert);
+    }
+
+    fn set_rts(&mut self, assert: bool) {
+        self.rts.set(assert);
+    }
+
+    fn modem_status(&self) -> ModemStatus {
+        let status = self.msr.get();
+
+        self.msr.set(ModemStatus {
+            delta_cts: false,
+            delta_dsr: false,
+            trailing_ri: false,
+            delta_dcd: false,
+            ..status
+        });
+
+        status
+    }
+
+    fn set_break(&self, enable: bool) {
+        self.brk.set(enable);
+    }
+
+    fn set_dma_mode(&mut self, enable: bool) {
+        self.dma_mode.set(enable);
+    }
+}
+
+/// [DelayNs] source that returns immediately.
+///
+/// Pair with [MockSerial] to exercise [Uart](super::Uart) timeouts off-target, where the
+/// cycle counter is unavailable. Timeouts then expire after `timeout` polls.
+///
+/// Only available with the `mock` feature.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MockDelay;
+
+impl DelayNs for MockDelay {
+    fn delay_ns(&mut self, _ns: u32) {}
+}
@@ -0,0 +1,46 @@
+/// Modem status signals, and their change indicators, read from the `MSR` register.
+///
+/// Signal states are reported as asserted (`true`), independent of the inverted line levels.
+///
+/// **NOTE**: the change indicators are cleared by reading the `MSR` register.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ModemStatus {
+    /// Clear To Send (`CTS`) is asserted.
+    pub cts: bool,
+    /// Data Set Ready (`DSR`) is asserted.
+    pub dsr: bool,
+    /// Ring Indicator (`RI`) is asserted.
+    pub ri: bool,
+    /// Data Carrier Detect (`DCD`) is asserted.
+    pub dcd: bool,
+    /// `CTS` changed since the last read.
+    pub delta_cts: bool,
+    /// `DSR` changed since the last read.
+    pub delta_dsr: bool,
+    /// `RI` changed from asserted to deasserted since the last read (trailing edge).
+    pub trailing_ri: bool,
+    /// `DCD` changed since the last read.
+    pub delta_dcd: bool,
+}
+
+impl ModemStatus {
+    /// Creates a new [ModemStatus] with all signals deasserted, and unchanged.
+    pub const fn new() -> Self {
+        Self {
+            cts: false,
+            dsr: false,
+            ri: false,
+            dcd: false,
+            delta_cts: false,
+            delta_dsr: false,
+            trailing_ri: false,
+            delta_dcd: false,
+        }
+    }
+
+    /// Gets whether any signal changed since the last read.
+    pub const fn changed(&self) -> bool {
+        self.delta_cts || self.delta_dsr || self.trailing_ri || self.delta_dcd
+    }
+}
@@ -0,0 +1,196 @@
+use core::cell::RefCell;
+
+use critical_section::Mutex;
+use embedded_hal::delay::DelayNs;
+use heapless::Deque;
+
+use super::{Error, Result, Serial, Uart};
+
+// Ring buffer state shared between the UART interrupt handler, and the reader.
+struct RxRing<const N: usize> {
+    buf: Deque<u8, N>,
+    err: Option<Error>,
+}
+
+impl<const N: usize> RxRing<N> {
+    const fn new() -> Self {
+        Self {
+            buf: Deque::new(),
+            err: None,
+        }
+    }
+
+    // Keeps the first error since the last read, so an overrun is not masked by later errors.
+    fn set_err(&mut self, err: Error) {
+        self.err.get_or_insert(err);
+    }
+}
+
+/// Interrupt-driven UART receive buffer.
+///
+/// The UART interrupt handler drains the receive FIFO into the ring buffer with
+/// [RxRingBuffer::service], and the application reads the received bytes without blocking with
+/// [RxRingBuffer::read_bytes].
+///
+/// Bytes received while the ring buffer is full are dropped, and reported as
+/// [Error::ReadOverrun] on the next read.
+///
+/// **NOTE**: while the receive interrupt is enabled, do not read from the [Uart] directly, or
+/// bytes will be taken from under the interrupt handler.
+///
+/// Example:
+///
+/// ```no_run
+/// # use jh71xx_hal::{pac, uart};
+/// use jh71xx_hal::interrupt::plic;
+///
+/// static RX: uart::RxRingBuffer<256> = uart::RxRingBuffer::new();
+///
+/// // Call from the PLIC external interrupt handler on HART 1
+/// fn on_external_interrupt() {
+///     if let Some(irq) = plic::claim_on(1) {
+///         if irq == plic::source::UART0 {
+///             // SAFETY: the interrupt handler only reads from the receive FIFO
+///             RX.service(unsafe { &pac::UART0::steal() });
+///         }
+///         plic::complete_on(1, irq).ok();
+///     }
+/// }
+///
+/// let dp = pac::Peripherals::take().unwrap();
+/// let mut uart = uart::Uart::new(dp.UART0);
+///
+/// plic::set_priority(plic::source::UART0, 1).unwrap();
+/// plic::enable_source_on(1, plic::source::UART0).unwrap();
+/// uart.enable_rx_interrupt();
+///
+/// let mut line = [0u8; 64];
+/// loop {
+///     match RX.read_bytes(line.as_mut()) {
+///         Ok(len) => {
+///             // ... handle the `len` received bytes ...
+/// #           let _ = len;
+///         }
+///         Err(uart::Error::ReadOverrun) => {
+///             // ... some bytes were lost ...
+///         }
+///         Err(_) => (),
+///     }
+///     // ... do other work ...
+/// }
+/// ```
+pub struct RxRingBuffer<const N: usize> {
+    inner: Mutex<RefCell<RxRing<N>>>,
+}
+
+impl<const N: usize> RxRingBuffer<N> {
+    /// Creates a new, empty [RxRingBuffer].
+    pub const fn new() -> Self {
+        Self {
+            inner: Mutex::new(RefCell::new(RxRing::new())),
+        }
+    }
+
+    /// Gets the capacity of the ring buffer.
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Gets the number of received bytes in the ring buffer.
+    pub fn len(&self) -> usize {
+        critical_section::with(|cs| self.inner.borrow_ref(cs).buf.len())
+    }
+
+    /// Gets whether the ring buffer is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drains the UART receive FIFO into the ring buffer.
+    ///
+    /// Call from the UART interrupt handler.
+    ///
+    /// Receive errors are stored, and reported by the next [RxRingBuffer::read_bytes].
+    ///
+    /// Returns the number of bytes stored in the ring buffer.
+    pub fn service<T: Serial>(&self, uart: &T) -> usize {
+        critical_section::with(|cs| {
+            let mut ring = self.inner.borrow_ref_mut(cs);
+            let mut count = 0usize;
+
+            loop {
+                match uart.read_byte() {
+                    Ok(b) => {
+                        if ring.buf.push_back(b).is_ok() {
+                            count = count.saturating_add(1);
+                        } else {
+                            ring.set_err(Error::ReadOverrun);
+                        }
+                    }
+                    Err(nb::Error::WouldBlock) => break,
+                    Err(nb::Error::Other(err)) => ring.set_err(err),
+                }
+            }
+
+            count
+        })
+    }
+
+    /// Reads received bytes from the ring buffer.
+    ///
+    /// Non-blocking, returns `Ok(0)` if no bytes have been received.
+    ///
+    /// Returns:
+    ///
+    /// - `Ok(read: usize)` on success, `read` bytes copied into `buf`
+    /// - `Err(Error)` if a receive error occurred since the last read, e.g. [Error::ReadOverrun]
+    ///   if the ring buffer filled up. The error is cleared, and the next read returns the
+    ///   buffered data.
+    pub fn read_bytes(&self, buf: &mut [u8]) -> Result<usize> {
+        critical_section::with(|cs| {
+            let mut ring = self.inner.borrow_ref_mut(cs);
+
+            if let Some(err) = ring.err.take() {
+                return Err(err);
+            }
+
+            let mut count = 0usize;
+            for byte in buf.iter_mut() {
+                match ring.buf.pop_front() {
+                    Some(b) => {
+                        *byte = b;
+                        count = count.saturating_add(1);
+                    }
+                    None => break,
+                }
+            }
+
+            Ok(count)
+        })
+    }
+
+    /// Discards all received bytes, and any pending receive error.
+    pub fn clear(&self) {
+        critical_section::with(|cs| {
+            let mut ring = self.inner.borrow_ref_mut(cs);
+            ring.buf.clear();
+            ring.err = None;
+        });
+    }
+}
+
+impl<const N: usize> Default for RxRingBuffer<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<UART: Serial, D: DelayNs> Uart<UART, D> {
+    /// Drains the receive FIFO into the [RxRingBuffer].
+    ///
+    /// Convenience function for interrupt handlers with access to the [Uart], see
+    /// [RxRingBuffer::service].
+    pub fn on_rx_interrupt<const N: usize>(&self, ring: &RxRingBuffer<N>) -> usize {
+        ring.service(&self.uart)
+    }
+}
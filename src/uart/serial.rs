@@ -1,29 +1,77 @@
+use core::sync::atomic::AtomicBool;
+
 use crate::pac::{UART0, UART1, UART2, UART3, UART4, UART5};
 
-use super::{Config, Error, Result};
+use super::{Config, Error, FlowControl, ModemStatus, Result};
 
 /// Traits for access to a UART peripheral.
 ///
 /// Provides abstractions over common actions for UART peripherals, like setup, reading, and
 /// writing.
+///
+/// Register access borrows the peripheral, so ownership of the PAC peripheral guarantees
+/// exclusive access.
 pub trait Serial {
+    /// Gets the MMIO base address of the UART peripheral.
+    fn base_addr() -> usize;
     /// Performs setup initialization for the UART peripheral.
     fn setup(&mut self, config: Config) -> Result<()>;
     /// Reads a byte from the UART peripheral (blocking).
-    fn read_byte() -> nb::Result<u8, Error>;
+    ///
+    /// Line status errors are reported for the character at the top of the receive FIFO. On a
+    /// break, framing, or parity error the character is discarded. On an overrun, the lost
+    /// character is reported, and the next read returns the buffered data.
+    fn read_byte(&self) -> nb::Result<u8, Error>;
     /// Writes a byte to the UART peripheral (blocking).
-    fn write_byte(byte: u8) -> nb::Result<(), Error>;
-    /// Flushes the UART peripheral transmit buffer (blocking).
-    fn flush() -> nb::Result<(), Error>;
+    fn write_byte(&self, byte: u8) -> nb::Result<(), Error>;
+    /// Waits for the UART peripheral to finish transmitting (non-blocking).
+    ///
+    /// Returns `WouldBlock` until the transmit FIFO and shift register are empty. Unsent data is
+    /// preserved.
+    fn flush(&self) -> nb::Result<(), Error>;
+    /// Resets the UART peripheral receive and transmit FIFOs.
+    ///
+    /// **NOTE**: destructive, any unsent and unread data is discarded.
+    fn clear_fifos(&mut self);
+    /// Sets the hardware [FlowControl] of the UART peripheral.
+    fn set_flow_control(&mut self, flow_control: FlowControl);
+    /// Enables, or disables the receive data available interrupt of the UART peripheral.
+    fn set_rx_interrupt(&mut self, enable: bool);
+    /// Enables, or disables the internal loopback mode of the UART peripheral.
+    fn set_loopback(&mut self, enable: bool);
+    /// Asserts, or deasserts the `DTR` modem control output of the UART peripheral.
+    fn set_dtr(&mut self, assert: bool);
+    /// Asserts, or deasserts the `RTS` modem control output of the UART peripheral.
+    fn set_rts(&mut self, assert: bool);
+    /// Reads the [ModemStatus] of the UART peripheral, clearing the change indicators.
+    fn modem_status(&self) -> ModemStatus;
+    /// Starts, or stops transmitting a break condition (`TX` held low) on the UART peripheral.
+    fn set_break(&self, enable: bool);
+    /// Enables, or disables multi-transfer DMA mode (`DMA mode 1`) of the UART peripheral.
+    fn set_dma_mode(&mut self, enable: bool);
 }
 
+// Shadow copies of the `FCR` DMA mode (`DMAM`) bit of each UART peripheral.
+//
+// `FCR` is write-only, reads at its offset return `IIR`, so `FCR` is always written whole.
+static FCR_DMAM: [AtomicBool; 6] = [const { AtomicBool::new(false) }; 6];
+
+// `FCR` receiver trigger level: FIFO half full (8th byte)
+const FCR_RT_HALF: u8 = 0b10;
+
 // Convenience macro for implementing the [Serial] trait over a UART peripheral type.
 //
 // Abstracts register access to follow DRY principles.
 macro_rules! impl_uart {
-    ($uart:ident) => {
+    ($uart:ident, $idx:expr) => {
         impl $crate::uart::Serial for $uart {
+            fn base_addr() -> usize {
+                Self::ptr() as usize
+            }
+
             fn setup(&mut self, config: $crate::uart::Config) -> $crate::uart::Result<()> {
+                config.validate()?;
+
                 // wait for UART0 to be idle
                 while self.usr().read().busy().bit_is_set() {}
 
@@ -46,7 +94,9 @@ macro_rules! impl_uart {
                     // Configure the number of stop bits
                     match config.stop {
                         $crate::uart::Stop::One => w.stop().clear_bit(),
-                        $crate::uart::Stop::Two => w.stop().set_bit(),
+                        $crate::uart::Stop::Two | $crate::uart::Stop::OnePointFive => {
+                            w.stop().set_bit()
+                        }
                     };
 
                     // Configure the parity bits
@@ -63,15 +113,17 @@ macro_rules! impl_uart {
                     }
                 });
 
-                // Disable auto flow control: from `oreboot` startup
-                self.mcr().modify(|_, w| w.afce().clear_bit());
+                // Auto flow control is disabled by default: from `oreboot` startup
+                self.set_flow_control(config.flow_control);
 
-                self.fcr().modify(|_, w| {
+                FCR_DMAM[$idx].store(false, core::sync::atomic::Ordering::Relaxed);
+
+                self.fcr().write(|w| {
                     // Program FIFO enabled: from `oreboot` startup
                     w.fifoe().set_bit();
                     w.dmam().clear_bit();
                     // Trigger on the 8th byte
-                    w.rt().variant(0b10);
+                    w.rt().variant(FCR_RT_HALF);
                     // Reset the receiver and transmitter FIFOs: from `oreboot` startup
                     w.rfifor().set_bit();
                     w.xfifor().set_bit()
@@ -83,47 +135,126 @@ macro_rules! impl_uart {
                 Ok(())
             }
 
-            fn read_byte() -> nb::Result<u8, Error> {
-                // SAFETY: caller must ensure exclusive access to the UART peripheral
-                let uart = unsafe { &*Self::ptr() };
-                if uart.lsr().read().dr().bit_is_set() {
-                    Ok(uart.rbr().read().rbr().bits())
+            fn read_byte(&self) -> nb::Result<u8, Error> {
+                // Error bits are cleared by reading `LSR`
+                let lsr = self.lsr().read();
+
+                let err = if lsr.bi().bit_is_set() {
+                    Some(Error::BreakDetected)
+                } else if lsr.fe().bit_is_set() {
+                    Some(Error::FramingError)
+                } else if lsr.pe().bit_is_set() {
+                    Some(Error::ParityError)
+                } else {
+                    None
+                };
+
+                if let Some(err) = err {
+                    // Discard the bad character, so it does not wedge subsequent reads
+                    let _ = self.rbr().read().rbr().bits();
+                    Err(nb::Error::Other(err))
+                } else if lsr.oe().bit_is_set() {
+                    Err(nb::Error::Other(Error::ReadOverrun))
+                } else if lsr.dr().bit_is_set() {
+                    Ok(self.rbr().read().rbr().bits())
                 } else {
                     Err(nb::Error::WouldBlock)
                 }
             }
 
-            fn write_byte(byte: u8) -> nb::Result<(), Error> {
-                // SAFETY: caller must ensure exclusive access to the UART peripheral
-                let uart = unsafe { &*Self::ptr() };
-                if uart.lsr().read().thre().bit_is_set() {
-                    uart.thr().write(|w| w.thr().variant(byte));
+            fn write_byte(&self, byte: u8) -> nb::Result<(), Error> {
+                if self.lsr().read().thre().bit_is_set() {
+                    self.thr().write(|w| w.thr().variant(byte));
                     Ok(())
                 } else {
                     Err(nb::Error::WouldBlock)
                 }
             }
 
-            fn flush() -> nb::Result<(), Error> {
-                // SAFETY: caller must ensure exclusive access to the UART peripheral
-                let uart = unsafe { &*Self::ptr() };
-                // Read if the TX FIFO is empty, block otherwise
-                if uart.lsr().read().thre().bit_is_set() {
+            fn flush(&self) -> nb::Result<(), Error> {
+                // Done when both the TX FIFO and transmit shift register are empty
+                if self.lsr().read().temt().bit_is_set() {
                     Ok(())
-                } else if uart.usr().read().busy().bit_is_clear() {
-                    uart.fcr().modify(|_, w| w.xfifor().set_bit());
-                    Err(nb::Error::WouldBlock)
                 } else {
                     Err(nb::Error::WouldBlock)
                 }
             }
+
+            fn clear_fifos(&mut self) {
+                let dma_mode = FCR_DMAM[$idx].load(core::sync::atomic::Ordering::Relaxed);
+
+                self.fcr().write(|w| {
+                    w.fifoe().set_bit();
+                    w.rt().variant(FCR_RT_HALF);
+                    w.dmam().bit(dma_mode);
+                    w.rfifor().set_bit();
+                    w.xfifor().set_bit()
+                });
+            }
+
+            fn set_flow_control(&mut self, flow_control: FlowControl) {
+                self.mcr().modify(|_, w| match flow_control {
+                    FlowControl::None => w.afce().clear_bit(),
+                    FlowControl::RtsCts => {
+                        // `RTS` must be set for the receiver to drive it automatically
+                        w.rts().set_bit();
+                        w.afce().set_bit()
+                    }
+                });
+            }
+
+            fn set_rx_interrupt(&mut self, enable: bool) {
+                self.ier().modify(|_, w| w.erbfi().bit(enable));
+            }
+
+            fn set_loopback(&mut self, enable: bool) {
+                self.mcr().modify(|_, w| w.loopback().bit(enable));
+            }
+
+            fn set_dtr(&mut self, assert: bool) {
+                self.mcr().modify(|_, w| w.dtr().bit(assert));
+            }
+
+            fn set_rts(&mut self, assert: bool) {
+                self.mcr().modify(|_, w| w.rts().bit(assert));
+            }
+
+            fn modem_status(&self) -> ModemStatus {
+                // Delta bits are cleared by reading `MSR`
+                let msr = self.msr().read();
+
+                ModemStatus {
+                    cts: msr.cts().bit_is_set(),
+                    dsr: msr.dsr().bit_is_set(),
+                    ri: msr.ri().bit_is_set(),
+                    dcd: msr.dcd().bit_is_set(),
+                    delta_cts: msr.dcts().bit_is_set(),
+                    delta_dsr: msr.ddsr().bit_is_set(),
+                    trailing_ri: msr.teri().bit_is_set(),
+                    delta_dcd: msr.ddcd().bit_is_set(),
+                }
+            }
+
+            fn set_break(&self, enable: bool) {
+                self.lcr().modify(|_, w| w.brk().bit(enable));
+            }
+
+            fn set_dma_mode(&mut self, enable: bool) {
+                FCR_DMAM[$idx].store(enable, core::sync::atomic::Ordering::Relaxed);
+
+                self.fcr().write(|w| {
+                    w.fifoe().set_bit();
+                    w.rt().variant(FCR_RT_HALF);
+                    w.dmam().bit(enable)
+                });
+            }
         }
     };
 }
 
-impl_uart!(UART0);
-impl_uart!(UART1);
-impl_uart!(UART2);
-impl_uart!(UART3);
-impl_uart!(UART4);
-impl_uart!(UART5);
+impl_uart!(UART0, 0);
+impl_uart!(UART1, 1);
+impl_uart!(UART2, 2);
+impl_uart!(UART3, 3);
+impl_uart!(UART4, 4);
+impl_uart!(UART5, 5);